@@ -0,0 +1,424 @@
+use anyhow::{Context, Result};
+use git2::{Config, Repository};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::diff::{self, open_worktree_repository};
+use super::worktree::Worktree;
+
+/// Format used to sign commits, from `gpg.format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningFormat {
+    OpenPgp,
+    Ssh,
+    X509,
+}
+
+impl SigningFormat {
+    /// Parse a `gpg.format` value, defaulting to OpenPGP like git does
+    pub fn from_config_value(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("ssh") => SigningFormat::Ssh,
+            Some("x509") => SigningFormat::X509,
+            _ => SigningFormat::OpenPgp,
+        }
+    }
+
+    /// Short label for display in the UI
+    pub fn label(self) -> &'static str {
+        match self {
+            SigningFormat::OpenPgp => "GPG",
+            SigningFormat::Ssh => "SSH",
+            SigningFormat::X509 => "X.509",
+        }
+    }
+}
+
+/// Commit-related configuration of a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitConfig {
+    /// `user.name`
+    pub user_name: Option<String>,
+    /// `user.email`
+    pub user_email: Option<String>,
+    /// `commit.gpgsign`
+    pub sign: bool,
+    /// `gpg.format`
+    pub signing_format: SigningFormat,
+    /// `user.signingkey`
+    pub signing_key: Option<String>,
+}
+
+impl CommitConfig {
+    /// Read the commit configuration from a git config snapshot
+    fn from_config(config: &Config) -> Self {
+        let get_string = |name: &str| {
+            config
+                .get_string(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        };
+
+        Self {
+            user_name: get_string("user.name"),
+            user_email: get_string("user.email"),
+            sign: config.get_bool("commit.gpgsign").unwrap_or(false),
+            signing_format: SigningFormat::from_config_value(get_string("gpg.format").as_deref()),
+            signing_key: get_string("user.signingkey"),
+        }
+    }
+
+    /// Check that an author identity is configured
+    pub fn validate_identity(&self) -> Result<()> {
+        if self.user_name.is_none() || self.user_email.is_none() {
+            anyhow::bail!(
+                "Author identity unknown: set user.name and user.email in your git config"
+            );
+        }
+        if self.sign && self.signing_format == SigningFormat::Ssh && self.signing_key.is_none() {
+            anyhow::bail!("SSH commit signing is enabled but user.signingkey is not set");
+        }
+        Ok(())
+    }
+}
+
+/// A commit to create from the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRequest {
+    /// Full commit message (subject, blank line, body)
+    pub message: String,
+    /// Replace HEAD instead of creating a new commit on top of it
+    pub amend: bool,
+}
+
+/// Result of a successful commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitOutcome {
+    /// Full id of the new HEAD commit
+    pub commit_id: String,
+    /// First line of the commit message
+    pub summary: String,
+    /// Whether the commit carries a signature
+    pub signed: bool,
+    /// Combined output of git and the commit hooks
+    pub output: String,
+}
+
+impl CommitOutcome {
+    /// Abbreviated commit id for display
+    pub fn short_id(&self) -> &str {
+        &self.commit_id[..self.commit_id.len().min(7)]
+    }
+}
+
+/// Read the commit configuration that applies to a worktree
+pub fn read_commit_config(worktree: &Worktree) -> Result<CommitConfig> {
+    let repo = open_worktree_repository(worktree)?;
+    let config = repo
+        .config()
+        .and_then(|mut c| c.snapshot())
+        .context("Failed to read git config")?;
+    Ok(CommitConfig::from_config(&config))
+}
+
+/// Message of the HEAD commit, used to prefill the composer when amending
+///
+/// Returns None on an unborn branch.
+pub fn head_commit_message(worktree: &Worktree) -> Result<Option<String>> {
+    let repo = open_worktree_repository(worktree)?;
+    let Some(head) = head_commit(&repo)? else {
+        return Ok(None);
+    };
+    Ok(head.message().map(|m| m.trim_end().to_string()))
+}
+
+/// Create a commit from the index of a worktree, or amend HEAD
+///
+/// Runs `git commit` so that pre-commit and commit-msg hooks, `commit.gpgsign`
+/// and `gpg.format` behave exactly as on the command line. Hook output is
+/// returned in `CommitOutcome::output`, or in the error if the commit fails.
+pub fn create_commit(worktree: &Worktree, request: &CommitRequest) -> Result<CommitOutcome> {
+    validate_message(&request.message)?;
+
+    let config = read_commit_config(worktree)?;
+    config.validate_identity()?;
+
+    let repo = open_worktree_repository(worktree)?;
+    if request.amend {
+        if head_commit(&repo)?.is_none() {
+            anyhow::bail!("There is no commit to amend yet");
+        }
+    } else if diff::get_staged_changes(worktree)?.is_empty() {
+        anyhow::bail!("No staged changes to commit");
+    }
+
+    // Lines starting with '#' are part of the message, such as "#123 fix
+    // crash", since the composer doesn't add comment lines
+    let mut command = Command::new("git");
+    command
+        .args(["commit", "--cleanup=whitespace", "--file=-"])
+        .current_dir(&worktree.path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if request.amend {
+        command.arg("--amend");
+    }
+
+    let mut child = command.spawn().context("Failed to execute git commit")?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open git commit stdin")?
+        .write_all(request.message.as_bytes())
+        .context("Failed to pass commit message to git")?;
+    let output = child
+        .wait_with_output()
+        .context("Failed to wait for git commit")?;
+
+    let combined = combine_output(&output.stdout, &output.stderr);
+    if !output.status.success() {
+        anyhow::bail!("git commit failed:\n{}", combined);
+    }
+
+    let head = head_commit(&repo)?.context("HEAD is missing after commit")?;
+    Ok(CommitOutcome {
+        commit_id: head.id().to_string(),
+        summary: head.summary().unwrap_or_default().to_string(),
+        signed: head.header_field_bytes("gpgsig").is_ok(),
+        output: combined,
+    })
+}
+
+/// Reject messages git would refuse as empty
+fn validate_message(message: &str) -> Result<()> {
+    if message.trim().is_empty() {
+        anyhow::bail!("Commit message cannot be empty");
+    }
+    Ok(())
+}
+
+fn head_commit(repo: &Repository) -> Result<Option<git2::Commit<'_>>> {
+    match repo.head() {
+        Ok(head) => Ok(Some(
+            head.peel_to_commit()
+                .context("Failed to resolve HEAD commit")?,
+        )),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+        Err(e) => Err(e).context("Failed to get HEAD reference"),
+    }
+}
+
+/// Join stdout and stderr of git, which interleave hook and summary output
+fn combine_output(stdout: &[u8], stderr: &[u8]) -> String {
+    let stdout = String::from_utf8_lossy(stdout);
+    let stderr = String::from_utf8_lossy(stderr);
+    [stderr.trim_end(), stdout.trim_end()]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command as StdCommand;
+
+    /// Create a temporary repository with one commit and a staged change
+    fn init_test_repo(name: &str) -> PathBuf {
+        let temp_dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        run_git(&temp_dir, &["init"]);
+        run_git(&temp_dir, &["config", "user.name", "Maestro Test"]);
+        run_git(&temp_dir, &["config", "user.email", "test@maestro.dev"]);
+        run_git(&temp_dir, &["config", "commit.gpgsign", "false"]);
+
+        fs::write(temp_dir.join("README.md"), "# Test\n").unwrap();
+        run_git(&temp_dir, &["add", "."]);
+        run_git(&temp_dir, &["commit", "-m", "Initial commit"]);
+
+        fs::write(temp_dir.join("README.md"), "# Test\n\nMore\n").unwrap();
+        run_git(&temp_dir, &["add", "."]);
+
+        temp_dir
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+    }
+
+    fn write_hook(dir: &Path, name: &str, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let hook_path = dir.join(".git").join("hooks").join(name);
+        fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+        fs::write(&hook_path, script).unwrap();
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn test_worktree(path: &Path) -> Worktree {
+        Worktree::new(path.to_path_buf(), "main".to_string(), false, false)
+    }
+
+    fn request(message: &str, amend: bool) -> CommitRequest {
+        CommitRequest {
+            message: message.to_string(),
+            amend,
+        }
+    }
+
+    #[test]
+    fn test_signing_format_from_config_value() {
+        assert_eq!(
+            SigningFormat::from_config_value(None),
+            SigningFormat::OpenPgp
+        );
+        assert_eq!(
+            SigningFormat::from_config_value(Some("openpgp")),
+            SigningFormat::OpenPgp
+        );
+        assert_eq!(
+            SigningFormat::from_config_value(Some("ssh")),
+            SigningFormat::Ssh
+        );
+        assert_eq!(
+            SigningFormat::from_config_value(Some("X509")),
+            SigningFormat::X509
+        );
+    }
+
+    #[test]
+    fn test_validate_message() {
+        assert!(validate_message("Fix bug").is_ok());
+        assert!(validate_message("").is_err());
+        assert!(validate_message("  \n\n").is_err());
+        assert!(validate_message("#123 fix crash\n").is_ok());
+    }
+
+    #[test]
+    fn test_validate_identity() {
+        let mut config = CommitConfig {
+            user_name: Some("Maestro".to_string()),
+            user_email: Some("maestro@example.com".to_string()),
+            sign: false,
+            signing_format: SigningFormat::OpenPgp,
+            signing_key: None,
+        };
+        assert!(config.validate_identity().is_ok());
+
+        config.sign = true;
+        config.signing_format = SigningFormat::Ssh;
+        assert!(config.validate_identity().is_err());
+
+        config.signing_key = Some("~/.ssh/id_ed25519.pub".to_string());
+        assert!(config.validate_identity().is_ok());
+
+        config.user_email = None;
+        assert!(config.validate_identity().is_err());
+    }
+
+    #[test]
+    fn test_combine_output() {
+        assert_eq!(combine_output(b"out\n", b"err\n"), "err\nout");
+        assert_eq!(combine_output(b"out\n", b""), "out");
+        assert_eq!(combine_output(b"", b""), "");
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_create_and_amend_commit() {
+        let temp_dir = init_test_repo("maestro_commit_test");
+        let worktree = test_worktree(&temp_dir);
+
+        let outcome = create_commit(&worktree, &request("Add more\n\nBody text", false)).unwrap();
+        assert_eq!(outcome.summary, "Add more");
+        assert!(!outcome.signed);
+        assert_eq!(outcome.short_id().len(), 7);
+        assert!(diff::get_staged_changes(&worktree).unwrap().is_empty());
+
+        // Nothing staged anymore
+        assert!(create_commit(&worktree, &request("Empty", false)).is_err());
+
+        let amended = create_commit(&worktree, &request("Add more text", true)).unwrap();
+        assert_ne!(amended.commit_id, outcome.commit_id);
+        assert_eq!(
+            head_commit_message(&worktree).unwrap().as_deref(),
+            Some("Add more text")
+        );
+
+        // Lines starting with '#' are kept, even with commit.cleanup=strip
+        run_git(&temp_dir, &["config", "commit.cleanup", "strip"]);
+        create_commit(&worktree, &request("#123 fix crash", true)).unwrap();
+        assert_eq!(
+            head_commit_message(&worktree).unwrap().as_deref(),
+            Some("#123 fix crash")
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_commit_hooks_run_and_report_output() {
+        let temp_dir = init_test_repo("maestro_commit_hooks_test");
+        let worktree = test_worktree(&temp_dir);
+
+        write_hook(
+            &temp_dir,
+            "pre-commit",
+            "#!/bin/sh\necho 'pre-commit checks passed'\n",
+        );
+        write_hook(
+            &temp_dir,
+            "commit-msg",
+            "#!/bin/sh\nif grep -q WIP \"$1\"; then echo 'WIP commits are not allowed' >&2; exit 1; fi\n",
+        );
+
+        let err = create_commit(&worktree, &request("WIP stuff", false)).unwrap_err();
+        assert!(err.to_string().contains("WIP commits are not allowed"));
+
+        let outcome = create_commit(&worktree, &request("Finished stuff", false)).unwrap();
+        assert!(outcome.output.contains("pre-commit checks passed"));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository and an SSH key
+    fn test_commit_with_ssh_signing() {
+        let temp_dir = init_test_repo("maestro_commit_ssh_test");
+        let worktree = test_worktree(&temp_dir);
+        let key_path = temp_dir.join(".git").join("test_signing_key");
+
+        StdCommand::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key_path)
+            .output()
+            .expect("Failed to run ssh-keygen");
+
+        run_git(&temp_dir, &["config", "gpg.format", "ssh"]);
+        run_git(&temp_dir, &["config", "commit.gpgsign", "true"]);
+        run_git(
+            &temp_dir,
+            &["config", "user.signingkey", &key_path.to_string_lossy()],
+        );
+
+        let config = read_commit_config(&worktree).unwrap();
+        assert!(config.sign);
+        assert_eq!(config.signing_format, SigningFormat::Ssh);
+
+        let outcome = create_commit(&worktree, &request("Signed commit", false)).unwrap();
+        assert!(outcome.signed);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
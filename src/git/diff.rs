@@ -0,0 +1,535 @@
+use anyhow::{Context, Result};
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, Patch, Repository};
+use std::path::PathBuf;
+
+use super::worktree::Worktree;
+
+/// Number of unchanged context lines included around each hunk
+pub const DIFF_CONTEXT_LINES: u32 = 3;
+
+/// Kind of change recorded for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    /// File type changed (e.g. regular file to symlink)
+    TypeChanged,
+    /// New file not yet tracked by git
+    Untracked,
+    /// File has unresolved merge conflicts
+    Conflicted,
+}
+
+impl ChangeStatus {
+    /// Single-letter code used in compact file lists (mirrors `git status --short`)
+    pub fn short_code(&self) -> char {
+        match self {
+            ChangeStatus::Modified => 'M',
+            ChangeStatus::Added => 'A',
+            ChangeStatus::Deleted => 'D',
+            ChangeStatus::Renamed => 'R',
+            ChangeStatus::Copied => 'C',
+            ChangeStatus::TypeChanged => 'T',
+            ChangeStatus::Untracked => '?',
+            ChangeStatus::Conflicted => 'U',
+        }
+    }
+
+    fn from_delta(delta: Delta) -> Option<Self> {
+        match delta {
+            Delta::Modified => Some(ChangeStatus::Modified),
+            Delta::Added => Some(ChangeStatus::Added),
+            Delta::Deleted => Some(ChangeStatus::Deleted),
+            Delta::Renamed => Some(ChangeStatus::Renamed),
+            Delta::Copied => Some(ChangeStatus::Copied),
+            Delta::Typechange => Some(ChangeStatus::TypeChanged),
+            Delta::Untracked => Some(ChangeStatus::Untracked),
+            Delta::Conflicted => Some(ChangeStatus::Conflicted),
+            Delta::Unmodified | Delta::Ignored | Delta::Unreadable => None,
+        }
+    }
+}
+
+/// A single line inside a diff hunk
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    /// Line origin: '+' added, '-' removed, ' ' context.
+    /// '=', '>' and '<' mark end-of-file newline changes.
+    pub origin: char,
+    /// Line content without the trailing newline, for display
+    pub content: String,
+    /// Line as it is in the file, including its line ending, so patches
+    /// built from it match files with CRLF line endings or other encodings
+    pub raw: Vec<u8>,
+    /// Line number in the old file (None for added lines)
+    pub old_lineno: Option<u32>,
+    /// Line number in the new file (None for removed lines)
+    pub new_lineno: Option<u32>,
+}
+
+impl DiffLine {
+    /// Whether this line is an addition
+    pub fn is_addition(&self) -> bool {
+        self.origin == '+'
+    }
+
+    /// Whether this line is a removal
+    pub fn is_deletion(&self) -> bool {
+        self.origin == '-'
+    }
+
+    /// Whether this line is unchanged context
+    pub fn is_context(&self) -> bool {
+        self.origin == ' '
+    }
+}
+
+/// A contiguous block of changes within a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffHunk {
+    /// Hunk header, e.g. `@@ -1,4 +1,5 @@ fn main()`
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// A changed file with its hunks
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// Path relative to the worktree root (new path for renames)
+    pub path: PathBuf,
+    /// Previous path when the file was renamed or copied
+    pub old_path: Option<PathBuf>,
+    pub status: ChangeStatus,
+    pub hunks: Vec<DiffHunk>,
+    /// Whether this change is in the index (staged) or the working tree
+    pub staged: bool,
+    /// Whether git considers either side of the change binary
+    pub is_binary: bool,
+}
+
+impl FileChange {
+    /// Count of added and removed lines across all hunks
+    pub fn line_stats(&self) -> (usize, usize) {
+        self.hunks.iter().flat_map(|hunk| hunk.lines.iter()).fold(
+            (0, 0),
+            |(added, removed), line| {
+                if line.is_addition() {
+                    (added + 1, removed)
+                } else if line.is_deletion() {
+                    (added, removed + 1)
+                } else {
+                    (added, removed)
+                }
+            },
+        )
+    }
+}
+
+/// Staged and unstaged changes for a worktree
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorktreeChanges {
+    /// Changes between HEAD and the index
+    pub staged: Vec<FileChange>,
+    /// Changes between the index and the working tree (including untracked files)
+    pub unstaged: Vec<FileChange>,
+}
+
+impl WorktreeChanges {
+    /// Whether there are no staged or unstaged changes
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty() && self.unstaged.is_empty()
+    }
+}
+
+/// Compute both staged and unstaged changes for a worktree
+pub fn get_changes(worktree: &Worktree) -> Result<WorktreeChanges> {
+    let repo = open_worktree_repository(worktree)?;
+
+    Ok(WorktreeChanges {
+        staged: staged_changes(&repo)?,
+        unstaged: unstaged_changes(&repo)?,
+    })
+}
+
+/// Compute unstaged changes (working tree vs index) for a worktree
+///
+/// Untracked files are included with `ChangeStatus::Untracked` and their full
+/// content as a single added hunk.
+pub fn get_unstaged_changes(worktree: &Worktree) -> Result<Vec<FileChange>> {
+    let repo = open_worktree_repository(worktree)?;
+    unstaged_changes(&repo)
+}
+
+/// Compute staged changes (index vs HEAD) for a worktree
+///
+/// On an unborn branch (no commits yet) every staged file is reported as added.
+pub fn get_staged_changes(worktree: &Worktree) -> Result<Vec<FileChange>> {
+    let repo = open_worktree_repository(worktree)?;
+    staged_changes(&repo)
+}
+
+/// Open the repository for a worktree path
+pub(crate) fn open_worktree_repository(worktree: &Worktree) -> Result<Repository> {
+    Repository::open(&worktree.path).with_context(|| {
+        format!(
+            "Failed to open worktree repository at {}",
+            worktree.path.display()
+        )
+    })
+}
+
+/// Build the diff options shared by staged and unstaged diffs
+pub(crate) fn diff_options() -> DiffOptions {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(DIFF_CONTEXT_LINES)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true)
+        .include_typechange(true);
+    opts
+}
+
+/// Diff between the index and the working tree
+pub(crate) fn unstaged_diff(repo: &Repository) -> Result<Diff<'_>> {
+    let mut opts = diff_options();
+    let mut diff = repo
+        .diff_index_to_workdir(None, Some(&mut opts))
+        .context("Failed to diff index to working tree")?;
+    detect_renames(&mut diff, true)?;
+    Ok(diff)
+}
+
+/// Diff between HEAD and the index
+pub(crate) fn staged_diff(repo: &Repository) -> Result<Diff<'_>> {
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().context("Failed to resolve HEAD tree")?),
+        // Unborn branch: diff against an empty tree
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e).context("Failed to get HEAD reference"),
+    };
+
+    let mut opts = diff_options();
+    let mut diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))
+        .context("Failed to diff HEAD to index")?;
+    detect_renames(&mut diff, false)?;
+    Ok(diff)
+}
+
+fn unstaged_changes(repo: &Repository) -> Result<Vec<FileChange>> {
+    let diff = unstaged_diff(repo)?;
+    collect_file_changes(&diff, false)
+}
+
+fn staged_changes(repo: &Repository) -> Result<Vec<FileChange>> {
+    let diff = staged_diff(repo)?;
+    collect_file_changes(&diff, true)
+}
+
+/// Run rename detection on a diff
+///
+/// For working tree diffs untracked files are considered as rename targets so
+/// that `mv a b` shows up as a rename before anything is staged.
+fn detect_renames(diff: &mut Diff<'_>, include_untracked: bool) -> Result<()> {
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true).for_untracked(include_untracked);
+    diff.find_similar(Some(&mut find_opts))
+        .context("Failed to detect renamed files")?;
+    Ok(())
+}
+
+/// Convert a git2 diff into our FileChange models
+fn collect_file_changes(diff: &Diff<'_>, staged: bool) -> Result<Vec<FileChange>> {
+    let mut changes = Vec::new();
+
+    for idx in 0..diff.deltas().len() {
+        let delta = diff.get_delta(idx).context("Diff delta out of range")?;
+        let Some(status) = ChangeStatus::from_delta(delta.status()) else {
+            continue;
+        };
+
+        let new_path = delta.new_file().path().map(|p| p.to_path_buf());
+        let old_path = delta.old_file().path().map(|p| p.to_path_buf());
+        let path = new_path
+            .clone()
+            .or_else(|| old_path.clone())
+            .context("Diff entry has no path")?;
+        let old_path = match status {
+            ChangeStatus::Renamed | ChangeStatus::Copied => old_path,
+            _ => None,
+        };
+
+        // Generating the patch loads the content, which is what sets the binary flag
+        let patch = Patch::from_diff(diff, idx).context("Failed to generate patch")?;
+        let is_binary = match &patch {
+            Some(patch) => patch.delta().flags().is_binary(),
+            None => delta.flags().is_binary(),
+        };
+
+        let hunks = match (&patch, is_binary) {
+            (Some(patch), false) => collect_hunks(patch)?,
+            _ => Vec::new(),
+        };
+
+        changes.push(FileChange {
+            path,
+            old_path,
+            status,
+            hunks,
+            staged,
+            is_binary,
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Extract hunks and lines from a patch
+fn collect_hunks(patch: &Patch<'_>) -> Result<Vec<DiffHunk>> {
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx).context("Failed to read diff hunk")?;
+
+        let mut lines = Vec::with_capacity(line_count);
+        for line_idx in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_idx, line_idx)
+                .context("Failed to read diff line")?;
+            let content = String::from_utf8_lossy(line.content());
+
+            lines.push(DiffLine {
+                origin: line.origin(),
+                content: content.trim_end_matches(['\n', '\r']).to_string(),
+                raw: line.content().to_vec(),
+                old_lineno: line.old_lineno(),
+                new_lineno: line.new_lineno(),
+            });
+        }
+
+        hunks.push(DiffHunk {
+            header: String::from_utf8_lossy(hunk.header())
+                .trim_end()
+                .to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+
+    Ok(hunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command as StdCommand;
+
+    /// Create a temporary repository with a single committed README
+    fn init_test_repo(name: &str) -> PathBuf {
+        let temp_dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        run_git(&temp_dir, &["init"]);
+        run_git(&temp_dir, &["config", "user.name", "Maestro Test"]);
+        run_git(&temp_dir, &["config", "user.email", "test@maestro.dev"]);
+
+        fs::write(temp_dir.join("README.md"), "# Test\n\nline one\nline two\n").unwrap();
+        run_git(&temp_dir, &["add", "."]);
+        run_git(&temp_dir, &["commit", "-m", "Initial commit"]);
+
+        temp_dir
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+    }
+
+    fn test_worktree(path: &Path) -> Worktree {
+        Worktree::new(path.to_path_buf(), "main".to_string(), false, false)
+    }
+
+    #[test]
+    fn test_change_status_short_code() {
+        assert_eq!(ChangeStatus::Modified.short_code(), 'M');
+        assert_eq!(ChangeStatus::Added.short_code(), 'A');
+        assert_eq!(ChangeStatus::Deleted.short_code(), 'D');
+        assert_eq!(ChangeStatus::Renamed.short_code(), 'R');
+        assert_eq!(ChangeStatus::Untracked.short_code(), '?');
+    }
+
+    #[test]
+    fn test_change_status_from_delta() {
+        assert_eq!(
+            ChangeStatus::from_delta(Delta::Modified),
+            Some(ChangeStatus::Modified)
+        );
+        assert_eq!(
+            ChangeStatus::from_delta(Delta::Renamed),
+            Some(ChangeStatus::Renamed)
+        );
+        assert_eq!(ChangeStatus::from_delta(Delta::Unmodified), None);
+        assert_eq!(ChangeStatus::from_delta(Delta::Ignored), None);
+    }
+
+    #[test]
+    fn test_file_change_line_stats() {
+        let change = FileChange {
+            path: PathBuf::from("src/main.rs"),
+            old_path: None,
+            status: ChangeStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,2 +1,2 @@".to_string(),
+                old_start: 1,
+                old_lines: 2,
+                new_start: 1,
+                new_lines: 2,
+                lines: vec![
+                    DiffLine {
+                        origin: ' ',
+                        content: "fn main() {".to_string(),
+                        raw: b"fn main() {\n".to_vec(),
+                        old_lineno: Some(1),
+                        new_lineno: Some(1),
+                    },
+                    DiffLine {
+                        origin: '-',
+                        content: "    old();".to_string(),
+                        raw: b"    old();\n".to_vec(),
+                        old_lineno: Some(2),
+                        new_lineno: None,
+                    },
+                    DiffLine {
+                        origin: '+',
+                        content: "    new();".to_string(),
+                        raw: b"    new();\n".to_vec(),
+                        old_lineno: None,
+                        new_lineno: Some(2),
+                    },
+                ],
+            }],
+            staged: false,
+            is_binary: false,
+        };
+
+        assert_eq!(change.line_stats(), (1, 1));
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_unstaged_and_staged_changes() {
+        let temp_dir = init_test_repo("maestro_diff_changes_test");
+        let worktree = test_worktree(&temp_dir);
+
+        // Unstaged modification
+        fs::write(temp_dir.join("README.md"), "# Test\n\nline one\nline 2\n").unwrap();
+        // Staged new file
+        fs::write(temp_dir.join("staged.txt"), "staged\n").unwrap();
+        run_git(&temp_dir, &["add", "staged.txt"]);
+        // Untracked file
+        fs::write(temp_dir.join("untracked.txt"), "new\n").unwrap();
+
+        let changes = get_changes(&worktree).unwrap();
+
+        assert_eq!(changes.staged.len(), 1);
+        assert_eq!(changes.staged[0].path, PathBuf::from("staged.txt"));
+        assert_eq!(changes.staged[0].status, ChangeStatus::Added);
+        assert!(changes.staged[0].staged);
+
+        let readme = changes
+            .unstaged
+            .iter()
+            .find(|c| c.path == Path::new("README.md"))
+            .expect("README.md should be modified");
+        assert_eq!(readme.status, ChangeStatus::Modified);
+        assert_eq!(readme.hunks.len(), 1);
+        assert_eq!(readme.line_stats(), (1, 1));
+        let removed = readme.hunks[0]
+            .lines
+            .iter()
+            .find(|l| l.is_deletion())
+            .unwrap();
+        assert_eq!(removed.content, "line two");
+        assert_eq!(removed.old_lineno, Some(4));
+
+        let untracked = changes
+            .unstaged
+            .iter()
+            .find(|c| c.path == Path::new("untracked.txt"))
+            .expect("untracked.txt should be listed");
+        assert_eq!(untracked.status, ChangeStatus::Untracked);
+        assert_eq!(untracked.line_stats(), (1, 0));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_staged_rename_detection() {
+        let temp_dir = init_test_repo("maestro_diff_rename_test");
+        let worktree = test_worktree(&temp_dir);
+
+        run_git(&temp_dir, &["mv", "README.md", "DOCS.md"]);
+
+        let staged = get_staged_changes(&worktree).unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].status, ChangeStatus::Renamed);
+        assert_eq!(staged[0].path, PathBuf::from("DOCS.md"));
+        assert_eq!(staged[0].old_path, Some(PathBuf::from("README.md")));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_binary_detection() {
+        let temp_dir = init_test_repo("maestro_diff_binary_test");
+        let worktree = test_worktree(&temp_dir);
+
+        fs::write(temp_dir.join("image.bin"), [0u8, 159, 146, 150, 0, 1, 2]).unwrap();
+
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        let binary = unstaged
+            .iter()
+            .find(|c| c.path == Path::new("image.bin"))
+            .unwrap();
+        assert!(binary.is_binary);
+        assert!(binary.hunks.is_empty());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_staged_changes_on_unborn_branch() {
+        let temp_dir = std::env::temp_dir().join("maestro_diff_unborn_test");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        run_git(&temp_dir, &["init"]);
+
+        fs::write(temp_dir.join("first.txt"), "first\n").unwrap();
+        run_git(&temp_dir, &["add", "first.txt"]);
+
+        let staged = get_staged_changes(&test_worktree(&temp_dir)).unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].status, ChangeStatus::Added);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -1,5 +1,11 @@
+pub mod commit;
+pub mod diff;
 pub mod repository;
+pub mod staging;
+pub mod status;
 pub mod worktree;
 
+pub use diff::{ChangeStatus, DiffHunk, DiffLine, FileChange, WorktreeChanges};
 pub use repository::{get_repository_name, is_git_repository};
+pub use status::{AheadBehind, RepoOperation, WorktreeStatus};
 pub use worktree::{Worktree, list_worktrees};
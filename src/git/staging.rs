@@ -0,0 +1,766 @@
+use anyhow::{Context, Result};
+use git2::build::CheckoutBuilder;
+use git2::{ApplyLocation, Diff, Repository};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use super::diff::{ChangeStatus, DiffHunk, DiffLine, FileChange, open_worktree_repository};
+use super::worktree::Worktree;
+
+/// Marker git uses for a final line without a trailing newline
+const NO_NEWLINE_MARKER: &[u8] = b"\\ No newline at end of file\n";
+
+/// Where a partial patch is applied and in which direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatchTarget {
+    /// Apply unstaged changes to the index (`git add -p`)
+    StageToIndex,
+    /// Revert staged changes from the index (`git reset -p`)
+    UnstageFromIndex,
+    /// Revert unstaged changes in the working tree (`git checkout -p`)
+    DiscardFromWorkdir,
+}
+
+impl PatchTarget {
+    fn is_reverse(self) -> bool {
+        !matches!(self, PatchTarget::StageToIndex)
+    }
+
+    fn location(self) -> ApplyLocation {
+        match self {
+            PatchTarget::StageToIndex | PatchTarget::UnstageFromIndex => ApplyLocation::Index,
+            PatchTarget::DiscardFromWorkdir => ApplyLocation::WorkDir,
+        }
+    }
+}
+
+/// Stage all unstaged changes of a file
+pub fn stage_file(worktree: &Worktree, change: &FileChange) -> Result<()> {
+    ensure_unstaged(change)?;
+    let repo = open_worktree_repository(worktree)?;
+    let mut index = repo.index().context("Failed to open index")?;
+
+    match change.status {
+        ChangeStatus::Deleted => index.remove_path(&change.path),
+        ChangeStatus::Renamed => {
+            if let Some(old_path) = &change.old_path {
+                index
+                    .remove_path(old_path)
+                    .context("Failed to remove old path of renamed file")?;
+            }
+            index.add_path(&change.path)
+        }
+        _ => index.add_path(&change.path),
+    }
+    .with_context(|| format!("Failed to stage {}", change.path.display()))?;
+
+    index.write().context("Failed to write index")?;
+    Ok(())
+}
+
+/// Unstage all staged changes of a file, resetting its index entry to HEAD
+pub fn unstage_file(worktree: &Worktree, change: &FileChange) -> Result<()> {
+    ensure_staged(change)?;
+    let repo = open_worktree_repository(worktree)?;
+
+    let mut paths = vec![change.path.as_path()];
+    if let Some(old_path) = &change.old_path {
+        paths.push(old_path.as_path());
+    }
+
+    // On an unborn branch there is nothing to reset to, so entries are removed
+    let head = match repo.head() {
+        Ok(head) => Some(
+            head.peel_to_commit()
+                .context("Failed to resolve HEAD commit")?,
+        ),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e).context("Failed to get HEAD reference"),
+    };
+
+    repo.reset_default(head.as_ref().map(|c| c.as_object()), paths)
+        .with_context(|| format!("Failed to unstage {}", change.path.display()))?;
+    Ok(())
+}
+
+/// Discard all unstaged changes of a file, restoring it from the index
+///
+/// Untracked files are deleted from disk.
+pub fn discard_file(worktree: &Worktree, change: &FileChange) -> Result<()> {
+    ensure_unstaged(change)?;
+    let repo = open_worktree_repository(worktree)?;
+
+    match change.status {
+        ChangeStatus::Untracked => remove_worktree_file(worktree, &change.path),
+        ChangeStatus::Renamed => {
+            // The new path is untracked; restore the old one from the index
+            remove_worktree_file(worktree, &change.path)?;
+            match &change.old_path {
+                Some(old_path) => checkout_from_index(&repo, old_path),
+                None => Ok(()),
+            }
+        }
+        _ => checkout_from_index(&repo, &change.path),
+    }
+}
+
+/// Stage a single hunk of an unstaged file
+pub fn stage_hunk(worktree: &Worktree, change: &FileChange, hunk_index: usize) -> Result<()> {
+    let hunk = get_hunk(change, hunk_index)?;
+    stage_lines(worktree, change, hunk_index, 0..hunk.lines.len())
+}
+
+/// Unstage a single hunk of a staged file
+pub fn unstage_hunk(worktree: &Worktree, change: &FileChange, hunk_index: usize) -> Result<()> {
+    let hunk = get_hunk(change, hunk_index)?;
+    unstage_lines(worktree, change, hunk_index, 0..hunk.lines.len())
+}
+
+/// Discard a single hunk of an unstaged file from the working tree
+pub fn discard_hunk(worktree: &Worktree, change: &FileChange, hunk_index: usize) -> Result<()> {
+    let hunk = get_hunk(change, hunk_index)?;
+    discard_lines(worktree, change, hunk_index, 0..hunk.lines.len())
+}
+
+/// Stage a range of lines of a hunk
+///
+/// `lines` indexes into `DiffHunk::lines`; context lines in the range are ignored.
+pub fn stage_lines(
+    worktree: &Worktree,
+    change: &FileChange,
+    hunk_index: usize,
+    lines: Range<usize>,
+) -> Result<()> {
+    ensure_unstaged(change)?;
+    apply_selection(
+        worktree,
+        change,
+        hunk_index,
+        lines,
+        PatchTarget::StageToIndex,
+    )
+}
+
+/// Unstage a range of lines of a hunk
+///
+/// `lines` indexes into `DiffHunk::lines`; context lines in the range are ignored.
+pub fn unstage_lines(
+    worktree: &Worktree,
+    change: &FileChange,
+    hunk_index: usize,
+    lines: Range<usize>,
+) -> Result<()> {
+    ensure_staged(change)?;
+    apply_selection(
+        worktree,
+        change,
+        hunk_index,
+        lines,
+        PatchTarget::UnstageFromIndex,
+    )
+}
+
+/// Discard a range of lines of a hunk from the working tree
+///
+/// `lines` indexes into `DiffHunk::lines`; context lines in the range are ignored.
+pub fn discard_lines(
+    worktree: &Worktree,
+    change: &FileChange,
+    hunk_index: usize,
+    lines: Range<usize>,
+) -> Result<()> {
+    ensure_unstaged(change)?;
+    apply_selection(
+        worktree,
+        change,
+        hunk_index,
+        lines,
+        PatchTarget::DiscardFromWorkdir,
+    )
+}
+
+/// Apply a line selection of one hunk to the index or the working tree
+fn apply_selection(
+    worktree: &Worktree,
+    change: &FileChange,
+    hunk_index: usize,
+    lines: Range<usize>,
+    target: PatchTarget,
+) -> Result<()> {
+    let hunk = get_hunk(change, hunk_index)?;
+
+    if change.is_binary {
+        anyhow::bail!("Partial staging is not supported for binary files");
+    }
+    if matches!(change.status, ChangeStatus::Renamed | ChangeStatus::Copied) {
+        anyhow::bail!(
+            "Partial staging is not supported for renamed files; stage the whole file instead"
+        );
+    }
+
+    // Selecting every change of a file that is added or deleted as a whole is
+    // a whole-file operation, which also creates or removes the index entry
+    if selects_whole_file(change, hunk_index, &lines) {
+        match (change.status, target) {
+            (ChangeStatus::Untracked | ChangeStatus::Deleted, PatchTarget::StageToIndex) => {
+                return stage_file(worktree, change);
+            }
+            (ChangeStatus::Added | ChangeStatus::Deleted, PatchTarget::UnstageFromIndex) => {
+                return unstage_file(worktree, change);
+            }
+            (ChangeStatus::Untracked | ChangeStatus::Deleted, PatchTarget::DiscardFromWorkdir) => {
+                return discard_file(worktree, change);
+            }
+            _ => {}
+        }
+    }
+
+    if change.status == ChangeStatus::Deleted && target == PatchTarget::DiscardFromWorkdir {
+        anyhow::bail!("Cannot partially restore a deleted file; discard the whole file instead");
+    }
+
+    let new_file_mode = if change.status == ChangeStatus::Untracked {
+        Some(worktree_file_mode(worktree, &change.path))
+    } else {
+        None
+    };
+
+    let Some(patch) = build_hunk_patch(
+        &change.path,
+        hunk,
+        &lines,
+        target.is_reverse(),
+        new_file_mode,
+    ) else {
+        anyhow::bail!("Selection contains no changed lines");
+    };
+
+    let repo = open_worktree_repository(worktree)?;
+    apply_patch(&repo, &patch, target.location())
+        .with_context(|| format!("Failed to apply selection to {}", change.path.display()))
+}
+
+/// Build a single-hunk unified diff for the selected lines of a hunk
+///
+/// In forward mode (staging) unselected additions are dropped and unselected
+/// removals become context. In reverse mode (unstaging or discarding) the
+/// patch goes from the new side back to the old side, so selected additions
+/// become removals, selected removals become additions, unselected additions
+/// become context and unselected removals are dropped.
+///
+/// `new_file_mode` marks the patch as creating the file with that mode, for
+/// files not yet in the index.
+///
+/// Lines are copied byte for byte, line endings included, so the patch
+/// applies to files that aren't UTF-8 or use CRLF line endings.
+///
+/// Returns None if the selection contains no added or removed lines.
+fn build_hunk_patch(
+    path: &Path,
+    hunk: &DiffHunk,
+    selected: &Range<usize>,
+    reverse: bool,
+    new_file_mode: Option<u32>,
+) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut old_count = 0u32;
+    let mut new_count = 0u32;
+    let mut has_changes = false;
+
+    for (idx, line) in hunk.lines.iter().enumerate() {
+        if is_eofnl_marker(line) {
+            continue;
+        }
+
+        let is_selected = selected.contains(&idx);
+        let origin = match (line.origin, is_selected, reverse) {
+            (' ', _, _) => ' ',
+            ('+', true, false) | ('-', true, true) => '+',
+            ('-', true, false) | ('+', true, true) => '-',
+            ('-', false, false) | ('+', false, true) => ' ',
+            ('+', false, false) | ('-', false, true) => continue,
+            _ => continue,
+        };
+
+        match origin {
+            ' ' => {
+                old_count += 1;
+                new_count += 1;
+            }
+            '-' => {
+                old_count += 1;
+                has_changes = true;
+            }
+            _ => {
+                new_count += 1;
+                has_changes = true;
+            }
+        }
+
+        body.push(origin as u8);
+        body.extend_from_slice(&line.raw);
+        if !line.raw.ends_with(b"\n") {
+            body.push(b'\n');
+        }
+
+        // The marker follows the line that has no trailing newline
+        if hunk.lines.get(idx + 1).is_some_and(is_eofnl_marker) {
+            body.extend_from_slice(NO_NEWLINE_MARKER);
+        }
+    }
+
+    if !has_changes {
+        return None;
+    }
+
+    let old_start = if reverse {
+        hunk.new_start
+    } else {
+        hunk.old_start
+    };
+    // libgit2 locates a hunk by its new start; for a single hunk that is the
+    // old position, shifted by one when the hunk only inserts lines
+    let new_start = if old_count == 0 {
+        old_start + 1
+    } else {
+        old_start
+    };
+
+    let path = path.to_string_lossy();
+    let mut patch = format!("diff --git a/{path} b/{path}\n");
+    match new_file_mode {
+        Some(mode) => {
+            patch.push_str(&format!("new file mode {:o}\n", mode));
+            patch.push_str(&format!("--- /dev/null\n+++ b/{path}\n"));
+        }
+        None => patch.push_str(&format!("--- a/{path}\n+++ b/{path}\n")),
+    }
+    patch.push_str(&format!(
+        "@@ -{},{} +{},{} @@\n",
+        old_start, old_count, new_start, new_count
+    ));
+    let mut patch = patch.into_bytes();
+    patch.extend_from_slice(&body);
+
+    Some(patch)
+}
+
+/// Whether a line is one of git's "no newline at end of file" markers
+fn is_eofnl_marker(line: &DiffLine) -> bool {
+    matches!(line.origin, '=' | '>' | '<')
+}
+
+/// Whether a selection covers every changed line of the file
+fn selects_whole_file(change: &FileChange, hunk_index: usize, selected: &Range<usize>) -> bool {
+    change.hunks.iter().enumerate().all(|(idx, hunk)| {
+        hunk.lines.iter().enumerate().all(|(line_idx, line)| {
+            !(line.is_addition() || line.is_deletion())
+                || (idx == hunk_index && selected.contains(&line_idx))
+        })
+    })
+}
+
+fn apply_patch(repo: &Repository, patch: &[u8], location: ApplyLocation) -> Result<()> {
+    let diff = Diff::from_buffer(patch).context("Failed to parse generated patch")?;
+    repo.apply(&diff, location, None)
+        .context("Patch does not apply")?;
+    Ok(())
+}
+
+fn checkout_from_index(repo: &Repository, path: &Path) -> Result<()> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().path(path);
+    repo.checkout_index(None, Some(&mut checkout))
+        .with_context(|| format!("Failed to restore {}", path.display()))
+}
+
+fn remove_worktree_file(worktree: &Worktree, path: &Path) -> Result<()> {
+    let full_path = worktree.path.join(path);
+    fs::remove_file(&full_path).with_context(|| format!("Failed to delete {}", full_path.display()))
+}
+
+/// Git file mode for a file in the working tree
+fn worktree_file_mode(worktree: &Worktree, path: &Path) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let is_executable = fs::metadata(worktree.path.join(path))
+            .map(|m| m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false);
+        if is_executable {
+            return 0o100755;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (worktree, path);
+
+    0o100644
+}
+
+fn get_hunk(change: &FileChange, hunk_index: usize) -> Result<&DiffHunk> {
+    change.hunks.get(hunk_index).with_context(|| {
+        format!(
+            "Hunk {} does not exist in {}",
+            hunk_index,
+            change.path.display()
+        )
+    })
+}
+
+fn ensure_staged(change: &FileChange) -> Result<()> {
+    if !change.staged {
+        anyhow::bail!("{} has no staged changes", change.path.display());
+    }
+    Ok(())
+}
+
+fn ensure_unstaged(change: &FileChange) -> Result<()> {
+    if change.staged {
+        anyhow::bail!("{} is already staged", change.path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::diff::{get_staged_changes, get_unstaged_changes};
+    use std::path::PathBuf;
+    use std::process::Command as StdCommand;
+
+    const ORIGINAL: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+    /// Create a temporary repository with a committed ten-line file
+    fn init_test_repo(name: &str) -> PathBuf {
+        let temp_dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        run_git(&temp_dir, &["init"]);
+        run_git(&temp_dir, &["config", "user.name", "Maestro Test"]);
+        run_git(&temp_dir, &["config", "user.email", "test@maestro.dev"]);
+
+        fs::write(temp_dir.join("file.txt"), ORIGINAL).unwrap();
+        run_git(&temp_dir, &["add", "."]);
+        run_git(&temp_dir, &["commit", "-m", "Initial commit"]);
+
+        temp_dir
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+    }
+
+    fn index_content(dir: &Path, path: &str) -> String {
+        String::from_utf8(index_bytes(dir, path)).unwrap()
+    }
+
+    fn index_bytes(dir: &Path, path: &str) -> Vec<u8> {
+        StdCommand::new("git")
+            .args(["show", &format!(":{}", path)])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git show")
+            .stdout
+    }
+
+    /// Commit `original` as `path`, then change it to `modified` and stage
+    /// only the removal of the line "one", returning the index content
+    fn stage_removal_of_one(dir: &Path, path: &str, original: &[u8], modified: &[u8]) -> Vec<u8> {
+        fs::write(dir.join(path), original).unwrap();
+        run_git(dir, &["add", "."]);
+        run_git(dir, &["commit", "-m", "Add file"]);
+        fs::write(dir.join(path), modified).unwrap();
+
+        let worktree = test_worktree(dir);
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        let change = unstaged.iter().find(|c| c.path == Path::new(path)).unwrap();
+        let hunk = &change.hunks[0];
+        let remove_one = hunk.lines.iter().position(|l| l.content == "one").unwrap();
+        stage_lines(&worktree, change, 0, remove_one..remove_one + 1).unwrap();
+
+        index_bytes(dir, path)
+    }
+
+    fn test_worktree(path: &Path) -> Worktree {
+        Worktree::new(path.to_path_buf(), "main".to_string(), false, false)
+    }
+
+    fn line(origin: char, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            raw: format!("{}\n", content).into_bytes(),
+            old_lineno: None,
+            new_lineno: None,
+        }
+    }
+
+    fn sample_hunk() -> DiffHunk {
+        DiffHunk {
+            header: "@@ -1,3 +1,3 @@".to_string(),
+            old_start: 1,
+            old_lines: 3,
+            new_start: 1,
+            new_lines: 3,
+            lines: vec![
+                line(' ', "a"),
+                line('-', "b"),
+                line('-', "c"),
+                line('+', "B"),
+                line('+', "C"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_build_hunk_patch_forward_partial() {
+        let patch =
+            build_hunk_patch(Path::new("f.txt"), &sample_hunk(), &(1..2), false, None).unwrap();
+        // Unselected removal becomes context, additions outside the range are dropped
+        assert_eq!(
+            String::from_utf8(patch).unwrap(),
+            "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n\
+             @@ -1,3 +1,2 @@\n a\n-b\n c\n"
+        );
+    }
+
+    #[test]
+    fn test_build_hunk_patch_reverse_partial() {
+        let patch =
+            build_hunk_patch(Path::new("f.txt"), &sample_hunk(), &(3..4), true, None).unwrap();
+        // Selected addition is removed, unselected removals are dropped
+        assert_eq!(
+            String::from_utf8(patch).unwrap(),
+            "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n\
+             @@ -1,3 +1,2 @@\n a\n-B\n C\n"
+        );
+    }
+
+    #[test]
+    fn test_build_hunk_patch_context_only_selection() {
+        assert!(
+            build_hunk_patch(Path::new("f.txt"), &sample_hunk(), &(0..1), false, None).is_none()
+        );
+    }
+
+    #[test]
+    fn test_build_hunk_patch_no_newline_marker() {
+        let hunk = DiffHunk {
+            header: "@@ -1 +1 @@".to_string(),
+            old_start: 1,
+            old_lines: 1,
+            new_start: 1,
+            new_lines: 1,
+            lines: vec![line('-', "old"), line('+', "new"), line('<', "")],
+        };
+
+        let patch = build_hunk_patch(Path::new("f.txt"), &hunk, &(0..3), false, None).unwrap();
+        assert!(patch.ends_with(b"-old\n+new\n\\ No newline at end of file\n"));
+    }
+
+    #[test]
+    fn test_build_hunk_patch_new_file() {
+        let hunk = DiffHunk {
+            header: "@@ -0,0 +1,2 @@".to_string(),
+            old_start: 0,
+            old_lines: 0,
+            new_start: 1,
+            new_lines: 2,
+            lines: vec![line('+', "x"), line('+', "y")],
+        };
+
+        let patch =
+            build_hunk_patch(Path::new("new.txt"), &hunk, &(0..1), false, Some(0o100644)).unwrap();
+        assert_eq!(
+            String::from_utf8(patch).unwrap(),
+            "diff --git a/new.txt b/new.txt\nnew file mode 100644\n\
+             --- /dev/null\n+++ b/new.txt\n@@ -0,0 +1,1 @@\n+x\n"
+        );
+    }
+
+    #[test]
+    fn test_ensure_staged_and_unstaged() {
+        let mut change = FileChange {
+            path: PathBuf::from("f.txt"),
+            old_path: None,
+            status: ChangeStatus::Modified,
+            hunks: vec![],
+            staged: false,
+            is_binary: false,
+        };
+        assert!(ensure_unstaged(&change).is_ok());
+        assert!(ensure_staged(&change).is_err());
+
+        change.staged = true;
+        assert!(ensure_staged(&change).is_ok());
+        assert!(ensure_unstaged(&change).is_err());
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_stage_and_unstage_single_hunk() {
+        let temp_dir = init_test_repo("maestro_staging_hunk_test");
+        let worktree = test_worktree(&temp_dir);
+
+        // Two separate hunks: line 1 and line 10
+        fs::write(
+            temp_dir.join("file.txt"),
+            ORIGINAL.replace("one\n", "ONE\n").replace("ten\n", "TEN\n"),
+        )
+        .unwrap();
+
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        assert_eq!(unstaged[0].hunks.len(), 2);
+
+        stage_hunk(&worktree, &unstaged[0], 1).unwrap();
+        assert_eq!(
+            index_content(&temp_dir, "file.txt"),
+            ORIGINAL.replace("ten\n", "TEN\n")
+        );
+
+        let staged = get_staged_changes(&worktree).unwrap();
+        unstage_hunk(&worktree, &staged[0], 0).unwrap();
+        assert_eq!(index_content(&temp_dir, "file.txt"), ORIGINAL);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_stage_and_discard_lines() {
+        let temp_dir = init_test_repo("maestro_staging_lines_test");
+        let worktree = test_worktree(&temp_dir);
+
+        // One hunk replacing two adjacent lines
+        fs::write(
+            temp_dir.join("file.txt"),
+            ORIGINAL
+                .replace("five\n", "FIVE\n")
+                .replace("six\n", "SIX\n"),
+        )
+        .unwrap();
+
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        let hunk = &unstaged[0].hunks[0];
+        let remove_five = hunk.lines.iter().position(|l| l.content == "five").unwrap();
+
+        // Stage only the removal of "five"
+        stage_lines(&worktree, &unstaged[0], 0, remove_five..remove_five + 1).unwrap();
+        assert_eq!(
+            index_content(&temp_dir, "file.txt"),
+            ORIGINAL.replace("five\n", "")
+        );
+
+        // Discarding everything left restores the working tree to the index
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        discard_hunk(&worktree, &unstaged[0], 0).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("file.txt")).unwrap(),
+            ORIGINAL.replace("five\n", "")
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_stage_lines_with_crlf_line_endings() {
+        let temp_dir = init_test_repo("maestro_staging_crlf_test");
+
+        let index = stage_removal_of_one(
+            &temp_dir,
+            "crlf.txt",
+            b"start\r\none\r\ntwo\r\n",
+            b"start\r\nONE\r\nTWO\r\n",
+        );
+        assert_eq!(index, b"start\r\ntwo\r\n");
+
+        // Unstaging puts the original lines back, line endings included
+        let worktree = test_worktree(&temp_dir);
+        let staged = get_staged_changes(&worktree).unwrap();
+        unstage_hunk(&worktree, &staged[0], 0).unwrap();
+        assert_eq!(
+            index_bytes(&temp_dir, "crlf.txt"),
+            b"start\r\none\r\ntwo\r\n"
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_stage_lines_in_latin1_file() {
+        let temp_dir = init_test_repo("maestro_staging_latin1_test");
+
+        // "café" in Latin-1 is not valid UTF-8
+        let index = stage_removal_of_one(
+            &temp_dir,
+            "latin1.txt",
+            b"caf\xe9\none\ntwo\n",
+            b"caf\xe9\nONE\nTWO\n",
+        );
+        assert_eq!(index, b"caf\xe9\ntwo\n");
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_whole_file_operations() {
+        let temp_dir = init_test_repo("maestro_staging_file_test");
+        let worktree = test_worktree(&temp_dir);
+
+        fs::write(temp_dir.join("new.txt"), "brand new\n").unwrap();
+        fs::write(temp_dir.join("file.txt"), "changed\n").unwrap();
+
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        let new_file = unstaged
+            .iter()
+            .find(|c| c.path == Path::new("new.txt"))
+            .unwrap();
+        stage_file(&worktree, new_file).unwrap();
+        assert_eq!(index_content(&temp_dir, "new.txt"), "brand new\n");
+
+        let staged = get_staged_changes(&worktree).unwrap();
+        unstage_file(&worktree, &staged[0]).unwrap();
+        assert!(get_staged_changes(&worktree).unwrap().is_empty());
+
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        for change in &unstaged {
+            discard_file(&worktree, change).unwrap();
+        }
+        assert!(get_unstaged_changes(&worktree).unwrap().is_empty());
+        assert!(!temp_dir.join("new.txt").exists());
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("file.txt")).unwrap(),
+            ORIGINAL
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_stage_partial_untracked_file() {
+        let temp_dir = init_test_repo("maestro_staging_untracked_test");
+        let worktree = test_worktree(&temp_dir);
+
+        fs::write(temp_dir.join("new.txt"), "keep\ndrop\n").unwrap();
+
+        let unstaged = get_unstaged_changes(&worktree).unwrap();
+        let new_file = unstaged
+            .iter()
+            .find(|c| c.path == Path::new("new.txt"))
+            .unwrap();
+        stage_lines(&worktree, new_file, 0, 0..1).unwrap();
+        assert_eq!(index_content(&temp_dir, "new.txt"), "keep\n");
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{Context, Result};
+use git2::{BranchType, Oid, Repository, RepositoryState, Status, StatusOptions};
+use std::path::Path;
+
+/// Operation left in progress in a worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    /// `git am` applying patches from a mailbox
+    ApplyMailbox,
+}
+
+impl RepoOperation {
+    /// Short label used in sidebar badges
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepoOperation::Merge => "merging",
+            RepoOperation::Rebase => "rebasing",
+            RepoOperation::CherryPick => "cherry-picking",
+            RepoOperation::Revert => "reverting",
+            RepoOperation::Bisect => "bisecting",
+            RepoOperation::ApplyMailbox => "applying patches",
+        }
+    }
+
+    fn from_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(RepoOperation::Merge),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Some(RepoOperation::Rebase),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(RepoOperation::CherryPick)
+            }
+            RepositoryState::Revert | RepositoryState::RevertSequence => {
+                Some(RepoOperation::Revert)
+            }
+            RepositoryState::Bisect => Some(RepoOperation::Bisect),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+                Some(RepoOperation::ApplyMailbox)
+            }
+        }
+    }
+}
+
+/// Commits on HEAD and on another ref that the other side doesn't have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AheadBehind {
+    /// Short name of the ref HEAD is compared with, e.g. `origin/main`
+    pub reference: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+impl AheadBehind {
+    /// Whether HEAD and the ref point at the same commit history
+    pub fn is_even(&self) -> bool {
+        self.ahead == 0 && self.behind == 0
+    }
+}
+
+/// Summary of a worktree's state, shown as sidebar badges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorktreeStatus {
+    /// Files with changes in the index
+    pub staged: usize,
+    /// Tracked files with changes in the working directory
+    pub modified: usize,
+    /// Files not tracked by git
+    pub untracked: usize,
+    /// Files with unresolved conflicts
+    pub conflicted: usize,
+    /// Position relative to the upstream of the current branch
+    pub upstream: Option<AheadBehind>,
+    /// Position relative to the repository's default branch
+    pub default_branch: Option<AheadBehind>,
+    /// Merge, rebase or similar operation in progress
+    pub operation: Option<RepoOperation>,
+}
+
+impl WorktreeStatus {
+    /// Whether the worktree has no local changes
+    pub fn is_clean(&self) -> bool {
+        self.staged == 0 && self.modified == 0 && self.untracked == 0 && self.conflicted == 0
+    }
+
+    /// Whether the worktree is blocked on the user: conflicts or an
+    /// interrupted merge/rebase
+    pub fn needs_attention(&self) -> bool {
+        self.conflicted > 0 || self.operation.is_some()
+    }
+}
+
+/// Compute the status summary of a worktree
+///
+/// # Arguments
+///
+/// * `worktree_path` - Path to the worktree
+pub fn get_worktree_status(worktree_path: &Path) -> Result<WorktreeStatus> {
+    let repo = Repository::open(worktree_path).context("Failed to open worktree repository")?;
+
+    let mut status = count_changes(&repo)?;
+    status.operation = RepoOperation::from_state(repo.state());
+
+    // An unborn HEAD has nothing to compare yet
+    let Ok(head) = repo.head() else {
+        return Ok(status);
+    };
+    let Some(head_oid) = head.target() else {
+        return Ok(status);
+    };
+
+    if head.is_branch()
+        && let Some(name) = head.shorthand()
+    {
+        status.upstream = upstream_ahead_behind(&repo, name, head_oid)?;
+    }
+
+    if let Some((reference, oid)) = default_branch(&repo) {
+        let is_same_branch = head.shorthand() == Some(reference.as_str());
+        let is_upstream = status
+            .upstream
+            .as_ref()
+            .is_some_and(|upstream| upstream.reference == reference);
+
+        if !is_same_branch && !is_upstream {
+            status.default_branch = Some(ahead_behind(&repo, reference, head_oid, oid)?);
+        }
+    }
+
+    Ok(status)
+}
+
+/// Name and commit of the repository's default branch
+///
+/// Uses the branch `origin/HEAD` points to, falling back to a local `main` or
+/// `master` branch.
+pub fn default_branch(repo: &Repository) -> Option<(String, Oid)> {
+    if let Ok(origin_head) = repo.find_reference("refs/remotes/origin/HEAD")
+        && let Ok(resolved) = origin_head.resolve()
+        && let (Some(name), Some(oid)) = (resolved.shorthand(), resolved.target())
+    {
+        return Some((name.to_string(), oid));
+    }
+
+    ["main", "master"].iter().find_map(|name| {
+        let branch = repo.find_branch(name, BranchType::Local).ok()?;
+        let oid = branch.get().target()?;
+        Some((name.to_string(), oid))
+    })
+}
+
+fn count_changes(repo: &Repository) -> Result<WorktreeStatus> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .context("Failed to read worktree status")?;
+
+    let mut status = WorktreeStatus::default();
+    for entry in statuses.iter() {
+        let flags = entry.status();
+
+        if flags.is_conflicted() {
+            status.conflicted += 1;
+            continue;
+        }
+        if flags.is_wt_new() {
+            status.untracked += 1;
+            continue;
+        }
+        if flags.intersects(
+            Status::INDEX_NEW
+                | Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE,
+        ) {
+            status.staged += 1;
+        }
+        if flags.intersects(
+            Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED | Status::WT_TYPECHANGE,
+        ) {
+            status.modified += 1;
+        }
+    }
+
+    Ok(status)
+}
+
+fn upstream_ahead_behind(
+    repo: &Repository,
+    branch_name: &str,
+    head_oid: Oid,
+) -> Result<Option<AheadBehind>> {
+    let Ok(branch) = repo.find_branch(branch_name, BranchType::Local) else {
+        return Ok(None);
+    };
+    let Ok(upstream) = branch.upstream() else {
+        return Ok(None);
+    };
+
+    let reference = upstream.name()?.unwrap_or("upstream").to_string();
+    let Some(upstream_oid) = upstream.get().target() else {
+        return Ok(None);
+    };
+
+    ahead_behind(repo, reference, head_oid, upstream_oid).map(Some)
+}
+
+fn ahead_behind(
+    repo: &Repository,
+    reference: String,
+    local: Oid,
+    other: Oid,
+) -> Result<AheadBehind> {
+    let (ahead, behind) = repo
+        .graph_ahead_behind(local, other)
+        .with_context(|| format!("Failed to compare HEAD with {}", reference))?;
+
+    Ok(AheadBehind {
+        reference,
+        ahead,
+        behind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn init_repo(name: &str) -> PathBuf {
+        let temp_dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        let repo_dir = temp_dir.join("repo");
+        fs::create_dir_all(&repo_dir).unwrap();
+
+        run_git(&repo_dir, &["init", "-b", "main"]);
+        run_git(&repo_dir, &["config", "user.name", "Maestro Test"]);
+        run_git(&repo_dir, &["config", "user.email", "test@maestro.dev"]);
+        fs::write(repo_dir.join("a.txt"), "a\n").unwrap();
+        fs::write(repo_dir.join("b.txt"), "b\n").unwrap();
+        run_git(&repo_dir, &["add", "."]);
+        run_git(&repo_dir, &["commit", "-m", "Initial commit"]);
+
+        repo_dir
+    }
+
+    #[test]
+    fn test_worktree_status_flags() {
+        let mut status = WorktreeStatus::default();
+        assert!(status.is_clean());
+        assert!(!status.needs_attention());
+
+        status.untracked = 1;
+        assert!(!status.is_clean());
+        assert!(!status.needs_attention());
+
+        status.operation = Some(RepoOperation::Rebase);
+        assert!(status.needs_attention());
+        assert_eq!(RepoOperation::Rebase.label(), "rebasing");
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_change_counts() {
+        let repo_dir = init_repo("maestro_status_counts_test");
+
+        fs::write(repo_dir.join("a.txt"), "changed\n").unwrap();
+        fs::write(repo_dir.join("b.txt"), "staged\n").unwrap();
+        run_git(&repo_dir, &["add", "b.txt"]);
+        fs::write(repo_dir.join("b.txt"), "staged then changed\n").unwrap();
+        fs::write(repo_dir.join("new.txt"), "new\n").unwrap();
+
+        let status = get_worktree_status(&repo_dir).unwrap();
+        assert_eq!(status.staged, 1);
+        assert_eq!(status.modified, 2);
+        assert_eq!(status.untracked, 1);
+        assert_eq!(status.conflicted, 0);
+        assert_eq!(status.operation, None);
+        // main is the default branch itself
+        assert_eq!(status.default_branch, None);
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates a temporary git repository
+    fn test_ahead_behind_and_merge_conflict() {
+        let repo_dir = init_repo("maestro_status_ahead_behind_test");
+
+        // A bare clone acts as origin so main gets an upstream
+        let origin_dir = repo_dir.parent().unwrap().join("origin.git");
+        run_git(
+            &repo_dir,
+            &["clone", "--bare", ".", origin_dir.to_str().unwrap()],
+        );
+        run_git(
+            &repo_dir,
+            &["remote", "add", "origin", origin_dir.to_str().unwrap()],
+        );
+        run_git(&repo_dir, &["fetch", "origin"]);
+        run_git(&repo_dir, &["branch", "--set-upstream-to=origin/main"]);
+        run_git(&repo_dir, &["remote", "set-head", "origin", "main"]);
+
+        // Feature branch: two commits ahead of main
+        run_git(&repo_dir, &["checkout", "-b", "feature"]);
+        for content in ["one\n", "two\n"] {
+            fs::write(repo_dir.join("a.txt"), content).unwrap();
+            run_git(&repo_dir, &["commit", "-am", content.trim()]);
+        }
+
+        // main: one commit ahead of origin/main and of the feature's base
+        run_git(&repo_dir, &["checkout", "main"]);
+        fs::write(repo_dir.join("a.txt"), "main\n").unwrap();
+        run_git(&repo_dir, &["commit", "-am", "main"]);
+
+        let status = get_worktree_status(&repo_dir).unwrap();
+        let upstream = status.upstream.unwrap();
+        assert_eq!(upstream.reference, "origin/main");
+        assert_eq!((upstream.ahead, upstream.behind), (1, 0));
+        // origin/main is the upstream, so it isn't reported twice
+        assert_eq!(status.default_branch, None);
+
+        run_git(&repo_dir, &["checkout", "feature"]);
+        let status = get_worktree_status(&repo_dir).unwrap();
+        assert_eq!(status.upstream, None);
+        let default = status.default_branch.unwrap();
+        assert_eq!(default.reference, "origin/main");
+        assert_eq!((default.ahead, default.behind), (2, 0));
+
+        // Merging main into feature conflicts on a.txt
+        let _ = Command::new("git")
+            .args(["merge", "main"])
+            .current_dir(&repo_dir)
+            .output();
+        let status = get_worktree_status(&repo_dir).unwrap();
+        assert_eq!(status.operation, Some(RepoOperation::Merge));
+        assert_eq!(status.conflicted, 1);
+        assert!(status.needs_attention());
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+}
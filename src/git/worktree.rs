@@ -1,8 +1,14 @@
 use anyhow::{Context, Result};
 use git2::Repository;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use super::repository::get_repository_name;
+use super::status::default_branch;
+use crate::settings::config::IN_REPO_WORKTREES_DIR;
+use crate::settings::{WorktreeLocation, WorktreeRoot};
+
 /// Represents a git worktree
 #[derive(Debug, Clone, PartialEq)]
 pub struct Worktree {
@@ -14,6 +20,8 @@ pub struct Worktree {
     pub is_detached: bool,
     /// Whether the worktree is locked
     pub is_locked: bool,
+    /// Reason given when the worktree was locked, if any
+    pub lock_reason: Option<String>,
 }
 
 impl Worktree {
@@ -24,6 +32,7 @@ impl Worktree {
             branch,
             is_detached,
             is_locked,
+            lock_reason: None,
         }
     }
 }
@@ -41,7 +50,7 @@ impl Worktree {
 ///
 /// A Vec of Worktree structs, with the root worktree typically first in the list
 pub fn list_worktrees(repo_path: &Path) -> Result<Vec<Worktree>> {
-    let repo = Repository::discover(repo_path).context("Failed to discover git repository")?;
+    let repo = open_root_repository(repo_path)?;
 
     let mut worktrees = Vec::new();
 
@@ -56,6 +65,20 @@ pub fn list_worktrees(repo_path: &Path) -> Result<Vec<Worktree>> {
     Ok(worktrees)
 }
 
+/// Open the repository of the root worktree
+///
+/// `repo_path` may be the root worktree or any linked worktree; linked
+/// worktrees share the root repository's common git directory.
+fn open_root_repository(repo_path: &Path) -> Result<Repository> {
+    let repo = Repository::discover(repo_path).context("Failed to discover git repository")?;
+
+    if repo.is_worktree() {
+        return Repository::open(repo.commondir()).context("Failed to open root repository");
+    }
+
+    Ok(repo)
+}
+
 /// Get the root worktree information using git2
 fn get_root_worktree(repo: &Repository) -> Result<Worktree> {
     let workdir = repo
@@ -78,6 +101,7 @@ fn get_root_worktree(repo: &Repository) -> Result<Worktree> {
         branch,
         is_detached,
         is_locked: false, // Root worktree is never locked
+        lock_reason: None,
     })
 }
 
@@ -125,6 +149,7 @@ fn parse_worktree_list(repo: &Repository) -> Result<Vec<Worktree>> {
                 branch: String::new(),
                 is_detached: false,
                 is_locked: false,
+                lock_reason: None,
             });
         } else if let Some(branch) = line.strip_prefix("branch ") {
             if let Some(ref mut wt) = current_worktree {
@@ -141,10 +166,11 @@ fn parse_worktree_list(repo: &Repository) -> Result<Vec<Worktree>> {
                     wt.branch = "HEAD".to_string();
                 }
             }
-        } else if line.starts_with("locked") {
-            if let Some(ref mut wt) = current_worktree {
-                wt.is_locked = true;
-            }
+        } else if let Some(reason) = line.strip_prefix("locked")
+            && let Some(ref mut wt) = current_worktree
+        {
+            wt.is_locked = true;
+            wt.lock_reason = parse_lock_reason(reason);
         }
     }
 
@@ -158,6 +184,66 @@ fn parse_worktree_list(repo: &Repository) -> Result<Vec<Worktree>> {
     Ok(worktrees)
 }
 
+/// Parse the reason following `locked` in porcelain output
+///
+/// git prints the reason after a space, C-quoted when it contains special
+/// characters such as newlines. Returns None when no reason was given.
+fn parse_lock_reason(rest: &str) -> Option<String> {
+    let reason = rest.strip_prefix(' ').unwrap_or(rest);
+    if reason.is_empty() {
+        return None;
+    }
+
+    let Some(quoted) = reason.strip_prefix('"').and_then(|r| r.strip_suffix('"')) else {
+        return Some(reason.to_string());
+    };
+
+    let mut unquoted = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unquoted.push('\n'),
+            Some('t') => unquoted.push('\t'),
+            Some(other) => unquoted.push(other),
+            None => unquoted.push('\\'),
+        }
+    }
+
+    Some(unquoted)
+}
+
+/// What a new worktree checks out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorktreeSource {
+    /// Create a new branch starting at `base`, or at HEAD if None
+    NewBranch { name: String, base: Option<String> },
+    /// Check out an existing local branch, or a local branch tracking a
+    /// remote-tracking branch such as `origin/feature`
+    ExistingBranch(String),
+    /// Check out a branch, tag or commit in detached HEAD state
+    Detached(String),
+}
+
+/// Kind of a ref offered in the base-ref picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    LocalBranch,
+    RemoteBranch,
+    Tag,
+}
+
+/// A branch or tag of the repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRef {
+    /// Short name, e.g. `main`, `origin/main` or `v1.0`
+    pub name: String,
+    pub kind: RefKind,
+}
+
 /// Create a new worktree with a new branch
 ///
 /// Creates a new worktree in a sibling directory to the root repository.
@@ -186,21 +272,64 @@ pub fn create_worktree(
     branch_name: &str,
     worktree_name: Option<&str>,
 ) -> Result<Worktree> {
-    // Validate branch name
-    validate_branch_name(branch_name)?;
+    let source = WorktreeSource::NewBranch {
+        name: branch_name.to_string(),
+        base: None,
+    };
+    create_worktree_from(
+        repo_path,
+        &source,
+        worktree_name,
+        &WorktreeLocation::default(),
+    )
+}
 
+/// Create a new worktree from a new branch, an existing branch or a detached ref
+///
+/// Creates a new worktree in the directory configured by `location`.
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `source` - What the worktree checks out
+/// * `worktree_name` - Optional custom name for the worktree directory.
+///   If None, it is derived from the branch or ref name using the location's
+///   name template
+/// * `location` - Where the worktree directory is created
+///
+/// # Returns
+///
+/// The newly created Worktree struct
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A branch name is invalid or a ref does not exist
+/// - A new branch already exists
+/// - An existing branch is already checked out in another worktree
+/// - The worktree directory already exists
+/// - Git command fails
+pub fn create_worktree_from(
+    repo_path: &Path,
+    source: &WorktreeSource,
+    worktree_name: Option<&str>,
+    location: &WorktreeLocation,
+) -> Result<Worktree> {
     // Discover the repository
-    let repo = Repository::discover(repo_path).context("Failed to discover git repository")?;
+    let repo = open_root_repository(repo_path)?;
+
+    // Resolve the source into `git worktree add` arguments
+    let plan = plan_worktree_add(&repo, source)?;
 
     // Get the parent directory where worktrees should be created
-    let parent_dir = get_worktree_parent_dir(repo_path)?;
+    let parent_dir = get_worktree_parent_dir(&repo, location)?;
 
     // Determine the worktree directory name
     let dir_name = if let Some(name) = worktree_name {
         name.to_string()
     } else {
-        // Replace slashes with dashes for directory name
-        branch_name.replace('/', "-")
+        let repo_name = get_repository_name(&get_root_worktree(&repo)?.path)?;
+        default_worktree_dir_name(source, location, &repo_name)
     };
 
     let worktree_path = parent_dir.join(&dir_name);
@@ -218,15 +347,19 @@ pub fn create_worktree(
         .workdir()
         .context("Repository has no working directory")?;
 
-    // Execute git worktree add command
+    // Execute git worktree add command: options, then the path, then the start point
+    let mut args = vec!["worktree".to_string(), "add".to_string()];
+    args.extend(plan.options);
+    args.push(
+        worktree_path
+            .to_str()
+            .context("Invalid worktree path")?
+            .to_string(),
+    );
+    args.extend(plan.start_point);
+
     let output = Command::new("git")
-        .args(&[
-            "worktree",
-            "add",
-            "-b",
-            branch_name,
-            worktree_path.to_str().context("Invalid worktree path")?,
-        ])
+        .args(&args)
         .current_dir(workdir)
         .output()
         .context("Failed to execute git worktree add")?;
@@ -239,12 +372,586 @@ pub fn create_worktree(
     // Return the created worktree
     Ok(Worktree {
         path: worktree_path,
-        branch: branch_name.to_string(),
-        is_detached: false,
+        branch: plan.branch,
+        is_detached: plan.is_detached,
         is_locked: false,
+        lock_reason: None,
     })
 }
 
+/// List local branches, remote-tracking branches and tags for the base-ref picker
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+///
+/// # Returns
+///
+/// Local branches first, then remote-tracking branches, then tags, each sorted by name
+pub fn list_refs(repo_path: &Path) -> Result<Vec<GitRef>> {
+    let repo = open_root_repository(repo_path)?;
+    let mut refs = Vec::new();
+
+    for (branch_type, kind) in [
+        (git2::BranchType::Local, RefKind::LocalBranch),
+        (git2::BranchType::Remote, RefKind::RemoteBranch),
+    ] {
+        let mut names: Vec<String> = repo
+            .branches(Some(branch_type))
+            .context("Failed to list branches")?
+            .filter_map(|branch| branch.ok())
+            .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+            // `origin/HEAD` is a symbolic alias, not a branch to check out
+            .filter(|name| !name.ends_with("/HEAD"))
+            .collect();
+        names.sort();
+        refs.extend(names.into_iter().map(|name| GitRef { name, kind }));
+    }
+
+    let mut tags: Vec<String> = repo
+        .tag_names(None)
+        .context("Failed to list tags")?
+        .iter()
+        .flatten()
+        .map(str::to_string)
+        .collect();
+    tags.sort();
+    refs.extend(tags.into_iter().map(|name| GitRef {
+        name,
+        kind: RefKind::Tag,
+    }));
+
+    Ok(refs)
+}
+
+/// Find the worktree that has a local branch checked out
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `branch_name` - Local branch name
+///
+/// # Returns
+///
+/// The path of the worktree using the branch, or None if it is not checked out
+pub fn find_worktree_for_branch(repo_path: &Path, branch_name: &str) -> Result<Option<PathBuf>> {
+    Ok(list_worktrees(repo_path)?
+        .into_iter()
+        .find(|wt| !wt.is_detached && wt.branch == branch_name)
+        .map(|wt| wt.path))
+}
+
+/// Arguments for `git worktree add` derived from a `WorktreeSource`
+struct WorktreeAddPlan {
+    /// Options placed before the worktree path
+    options: Vec<String>,
+    /// Commit-ish placed after the worktree path
+    start_point: Option<String>,
+    /// Branch the worktree ends up on ("HEAD" if detached)
+    branch: String,
+    is_detached: bool,
+}
+
+/// Validate a worktree source against the repository and plan the git command
+fn plan_worktree_add(repo: &Repository, source: &WorktreeSource) -> Result<WorktreeAddPlan> {
+    match source {
+        WorktreeSource::NewBranch { name, base } => {
+            validate_branch_name(name)?;
+            if repo.find_branch(name, git2::BranchType::Local).is_ok() {
+                anyhow::bail!("Branch '{}' already exists", name);
+            }
+            if let Some(base) = base {
+                resolve_commit(repo, base)?;
+            }
+
+            Ok(WorktreeAddPlan {
+                options: vec!["-b".to_string(), name.clone()],
+                start_point: base.clone(),
+                branch: name.clone(),
+                is_detached: false,
+            })
+        }
+        WorktreeSource::ExistingBranch(name) => {
+            let workdir = repo
+                .workdir()
+                .context("Repository has no working directory")?;
+
+            // A local branch is checked out directly
+            if repo.find_branch(name, git2::BranchType::Local).is_ok() {
+                ensure_branch_not_checked_out(workdir, name)?;
+                return Ok(WorktreeAddPlan {
+                    options: Vec::new(),
+                    start_point: Some(name.clone()),
+                    branch: name.clone(),
+                    is_detached: false,
+                });
+            }
+
+            // A remote-tracking branch gets a local branch tracking it
+            if repo.find_branch(name, git2::BranchType::Remote).is_ok() {
+                let local_name = local_name_for_remote_branch(repo, name)?;
+                if repo
+                    .find_branch(&local_name, git2::BranchType::Local)
+                    .is_ok()
+                {
+                    anyhow::bail!(
+                        "Local branch '{}' already exists; check it out instead of '{}'",
+                        local_name,
+                        name
+                    );
+                }
+                return Ok(WorktreeAddPlan {
+                    options: vec!["--track".to_string(), "-b".to_string(), local_name.clone()],
+                    start_point: Some(name.clone()),
+                    branch: local_name,
+                    is_detached: false,
+                });
+            }
+
+            anyhow::bail!("Branch '{}' not found", name)
+        }
+        WorktreeSource::Detached(rev) => {
+            // Check out the commit itself so a branch name does not attach HEAD
+            let commit = resolve_commit(repo, rev)?;
+            Ok(WorktreeAddPlan {
+                options: vec!["--detach".to_string()],
+                start_point: Some(commit.to_string()),
+                branch: "HEAD".to_string(),
+                is_detached: true,
+            })
+        }
+    }
+}
+
+/// Fail if a local branch is already checked out in some worktree
+fn ensure_branch_not_checked_out(repo_path: &Path, branch_name: &str) -> Result<()> {
+    if let Some(path) = find_worktree_for_branch(repo_path, branch_name)? {
+        anyhow::bail!(
+            "Branch '{}' is already checked out at {}",
+            branch_name,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Resolve a branch, tag or commit to a commit id
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<git2::Oid> {
+    let object = repo
+        .revparse_single(rev)
+        .with_context(|| format!("Unknown branch, tag or commit: '{}'", rev))?;
+    let commit = object
+        .peel_to_commit()
+        .with_context(|| format!("'{}' does not point to a commit", rev))?;
+    Ok(commit.id())
+}
+
+/// Strip the remote name from a remote-tracking branch (`origin/feature` -> `feature`)
+fn local_name_for_remote_branch(repo: &Repository, remote_branch: &str) -> Result<String> {
+    let remotes = repo.remotes().context("Failed to list remotes")?;
+
+    // Prefer the longest matching remote name, since remote names may contain slashes
+    remotes
+        .iter()
+        .flatten()
+        .filter_map(|remote| {
+            remote_branch
+                .strip_prefix(remote)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .map(|rest| (remote.len(), rest))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, rest)| rest.to_string())
+        .with_context(|| format!("'{}' does not belong to a known remote", remote_branch))
+}
+
+/// Default directory name for a new worktree, from the location's name template
+fn default_worktree_dir_name(
+    source: &WorktreeSource,
+    location: &WorktreeLocation,
+    repo_name: &str,
+) -> String {
+    let name = match source {
+        WorktreeSource::NewBranch { name, .. } => name.clone(),
+        WorktreeSource::ExistingBranch(name) => name.clone(),
+        WorktreeSource::Detached(rev) => format!("detached-{}", rev),
+    };
+
+    location.dir_name(repo_name, &name)
+}
+
+/// Remove a linked worktree and its directory
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `worktree_path` - Path of the worktree to remove
+/// * `force` - Remove the worktree even if it has uncommitted or untracked changes
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The path is the root worktree
+/// - The worktree is locked, even with `force`
+/// - The worktree has changes and `force` is false
+/// - Git command fails
+pub fn remove_worktree(repo_path: &Path, worktree_path: &Path, force: bool) -> Result<()> {
+    let repo = open_root_repository(repo_path)?;
+    let root = get_root_worktree(&repo)?;
+
+    if same_path(&root.path, worktree_path) {
+        anyhow::bail!("The root worktree cannot be removed");
+    }
+    if let Some(locked) = parse_worktree_list(&repo)?
+        .into_iter()
+        .find(|wt| wt.is_locked && same_path(&wt.path, worktree_path))
+    {
+        match locked.lock_reason {
+            Some(reason) => anyhow::bail!("Worktree is locked ({}); unlock it first", reason),
+            None => anyhow::bail!("Worktree is locked; unlock it first"),
+        }
+    }
+
+    let mut args = vec!["worktree", "remove"];
+    if force {
+        args.push("--force");
+    }
+    args.push(worktree_path.to_str().context("Invalid worktree path")?);
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(&root.path)
+        .output()
+        .context("Failed to execute git worktree remove")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git worktree remove failed: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Lock a linked worktree so it is not pruned, moved or removed
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `worktree_path` - Path of the worktree to lock
+/// * `reason` - Optional reason shown alongside the lock
+///
+/// # Errors
+///
+/// Returns an error if the path is the root worktree, the worktree is
+/// already locked or the git command fails
+pub fn lock_worktree(repo_path: &Path, worktree_path: &Path, reason: Option<&str>) -> Result<()> {
+    let repo = open_root_repository(repo_path)?;
+    let root = get_root_worktree(&repo)?;
+
+    if same_path(&root.path, worktree_path) {
+        anyhow::bail!("The root worktree cannot be locked");
+    }
+
+    let mut command = Command::new("git");
+    command.args(["worktree", "lock"]);
+    if let Some(reason) = reason.map(str::trim).filter(|r| !r.is_empty()) {
+        command.args(["--reason", reason]);
+    }
+
+    let output = command
+        .arg(worktree_path)
+        .current_dir(&root.path)
+        .output()
+        .context("Failed to execute git worktree lock")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git worktree lock failed: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Unlock a locked worktree
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `worktree_path` - Path of the worktree to unlock
+///
+/// # Errors
+///
+/// Returns an error if the worktree is not locked or the git command fails
+pub fn unlock_worktree(repo_path: &Path, worktree_path: &Path) -> Result<()> {
+    let repo = open_root_repository(repo_path)?;
+    let root = get_root_worktree(&repo)?;
+
+    let output = Command::new("git")
+        .args(["worktree", "unlock"])
+        .arg(worktree_path)
+        .current_dir(&root.path)
+        .output()
+        .context("Failed to execute git worktree unlock")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git worktree unlock failed: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Prune administrative data of worktrees whose directories no longer exist
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+///
+/// # Returns
+///
+/// One message per pruned worktree, as reported by git
+pub fn prune_worktrees(repo_path: &Path) -> Result<Vec<String>> {
+    let repo = open_root_repository(repo_path)?;
+    let workdir = repo
+        .workdir()
+        .context("Repository has no working directory")?;
+
+    let output = Command::new("git")
+        .args(["worktree", "prune", "--verbose"])
+        .current_dir(workdir)
+        .output()
+        .context("Failed to execute git worktree prune")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git worktree prune failed: {}", stderr.trim());
+    }
+
+    // git reports pruned entries on stderr in verbose mode
+    let messages = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stdout).lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(messages)
+}
+
+/// Result of moving worktrees to a new location
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelocationReport {
+    /// Worktrees that were moved, as (old path, new path)
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    /// Worktrees that could not be moved, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Move a linked worktree to a new directory
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `worktree_path` - Current path of the worktree
+/// * `new_path` - Destination path; must not exist yet
+///
+/// # Errors
+///
+/// Returns an error if the worktree is the root worktree, is locked, or the
+/// destination already exists
+pub fn move_worktree(repo_path: &Path, worktree_path: &Path, new_path: &Path) -> Result<()> {
+    let repo = open_root_repository(repo_path)?;
+    let root = get_root_worktree(&repo)?;
+
+    if same_path(&root.path, worktree_path) {
+        anyhow::bail!("The root worktree cannot be moved");
+    }
+    if new_path.exists() {
+        anyhow::bail!("Destination already exists: {}", new_path.display());
+    }
+    if let Some(parent) = new_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create destination directory")?;
+    }
+
+    let output = Command::new("git")
+        .args(["worktree", "move"])
+        .arg(worktree_path)
+        .arg(new_path)
+        .current_dir(&root.path)
+        .output()
+        .context("Failed to execute git worktree move")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git worktree move failed: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Linked worktrees that are not in the configured location, with their destinations
+///
+/// Directory names are kept; only the parent directory changes.
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `location` - Configured worktree location
+pub fn worktrees_to_relocate(
+    repo_path: &Path,
+    location: &WorktreeLocation,
+) -> Result<Vec<(Worktree, PathBuf)>> {
+    let repo = open_root_repository(repo_path)?;
+    let parent_dir = get_worktree_parent_dir(&repo, location)?;
+
+    let moves = list_worktrees(repo_path)?
+        .into_iter()
+        .skip(1) // The root worktree stays where it is
+        .filter(|wt| {
+            wt.path
+                .parent()
+                .is_none_or(|parent| !same_path(parent, &parent_dir))
+        })
+        .filter_map(|wt| {
+            let name = wt.path.file_name()?.to_owned();
+            let destination = parent_dir.join(name);
+            Some((wt, destination))
+        })
+        .collect();
+
+    Ok(moves)
+}
+
+/// Move all linked worktrees into the configured location
+///
+/// Locked worktrees and worktrees whose destination already exists are
+/// reported as failed and left in place.
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `location` - Configured worktree location
+pub fn relocate_worktrees(
+    repo_path: &Path,
+    location: &WorktreeLocation,
+) -> Result<RelocationReport> {
+    let mut report = RelocationReport::default();
+
+    for (worktree, destination) in worktrees_to_relocate(repo_path, location)? {
+        if worktree.is_locked {
+            report
+                .failed
+                .push((worktree.path, "Worktree is locked".to_string()));
+            continue;
+        }
+
+        match move_worktree(repo_path, &worktree.path, &destination) {
+            Ok(()) => report.moved.push((worktree.path, destination)),
+            Err(e) => report.failed.push((worktree.path, e.to_string())),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Count files with uncommitted changes in a worktree, including untracked files
+///
+/// # Arguments
+///
+/// * `worktree_path` - Path to the worktree
+pub fn count_uncommitted_changes(worktree_path: &Path) -> Result<usize> {
+    let repo = Repository::open(worktree_path).context("Failed to open worktree repository")?;
+
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .context("Failed to read worktree status")?;
+
+    Ok(statuses.len())
+}
+
+/// Delete a local branch if it is fully merged into the default branch
+///
+/// The default branch is resolved like the status summary does: the branch
+/// `origin/HEAD` points to, then a local `main` or `master`. Repositories
+/// without any of those compare against the root worktree's HEAD.
+///
+/// # Arguments
+///
+/// * `repo_path` - Path to the git repository (can be any worktree)
+/// * `branch_name` - Local branch to delete
+///
+/// # Returns
+///
+/// `true` if the branch was deleted, `false` if it is not merged and was kept
+///
+/// # Errors
+///
+/// Returns an error if the branch does not exist, is checked out in a worktree
+/// or is the default branch itself
+pub fn delete_branch_if_merged(repo_path: &Path, branch_name: &str) -> Result<bool> {
+    let repo = open_root_repository(repo_path)?;
+    let root = get_root_worktree(&repo)?;
+
+    if !root.is_detached && root.branch == branch_name {
+        anyhow::bail!(
+            "Cannot delete '{}': it is checked out in the root worktree",
+            branch_name
+        );
+    }
+
+    // HEAD of the root repository is the main checkout, not a linked worktree
+    let mut branch = repo
+        .find_branch(branch_name, git2::BranchType::Local)
+        .with_context(|| format!("Branch '{}' not found", branch_name))?;
+
+    let branch_tip = branch
+        .get()
+        .peel_to_commit()
+        .context("Failed to resolve branch commit")?
+        .id();
+    let base = match default_branch(&repo) {
+        // origin/HEAD names the remote-tracking branch of the local default
+        Some((name, _)) if name.strip_prefix("origin/").unwrap_or(&name) == branch_name => {
+            anyhow::bail!("Cannot delete '{}': it is the default branch", branch_name);
+        }
+        Some((_, oid)) => oid,
+        None => repo
+            .head()
+            .and_then(|h| h.peel_to_commit())
+            .context("Failed to resolve HEAD commit")?
+            .id(),
+    };
+
+    let is_merged = base == branch_tip
+        || repo
+            .graph_descendant_of(base, branch_tip)
+            .context("Failed to compare branch with the default branch")?;
+
+    if !is_merged {
+        return Ok(false);
+    }
+
+    branch
+        .delete()
+        .with_context(|| format!("Failed to delete branch '{}'", branch_name))?;
+
+    Ok(true)
+}
+
+/// Compare two paths, resolving symlinks where possible
+fn same_path(a: &Path, b: &Path) -> bool {
+    let a = a.canonicalize().unwrap_or_else(|_| a.to_path_buf());
+    let b = b.canonicalize().unwrap_or_else(|_| b.to_path_buf());
+    a == b
+}
+
 /// Validate a branch name according to git ref naming rules
 ///
 /// # Arguments
@@ -310,27 +1017,52 @@ pub fn validate_branch_name(name: &str) -> Result<()> {
 
 /// Get the parent directory where worktrees should be created
 ///
-/// Returns the parent directory of the root repository, where sibling
-/// worktrees will be created.
+/// Resolves the configured location against the root worktree. For the
+/// in-repository layout the directory is also excluded from git status.
 ///
 /// # Arguments
 ///
-/// * `repo_path` - Path to the git repository
+/// * `repo` - The root repository
+/// * `location` - Configured worktree location
 ///
 /// # Returns
 ///
 /// PathBuf to the parent directory
-fn get_worktree_parent_dir(repo_path: &Path) -> Result<PathBuf> {
-    let repo = Repository::discover(repo_path).context("Failed to discover git repository")?;
-    let workdir = repo
-        .workdir()
-        .context("Repository has no working directory")?;
+fn get_worktree_parent_dir(repo: &Repository, location: &WorktreeLocation) -> Result<PathBuf> {
+    let root = get_root_worktree(repo)?;
+    let repo_name = get_repository_name(&root.path)?;
+    let parent = location.parent_dir(&root.path, &repo_name)?;
 
-    let parent = workdir
-        .parent()
-        .context("Repository is at filesystem root")?;
+    if location.root == WorktreeRoot::InRepo {
+        exclude_in_repo_worktrees(repo)?;
+    }
 
-    Ok(parent.to_path_buf())
+    Ok(parent)
+}
+
+/// Add the in-repository worktree directory to `.git/info/exclude`
+fn exclude_in_repo_worktrees(repo: &Repository) -> Result<()> {
+    let exclude_path = repo.commondir().join("info").join("exclude");
+    let pattern = format!("/{}/", IN_REPO_WORKTREES_DIR);
+
+    let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+
+    let mut contents = existing;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(&pattern);
+    contents.push('\n');
+
+    if let Some(parent) = exclude_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create .git/info directory")?;
+    }
+    fs::write(&exclude_path, contents).context("Failed to update .git/info/exclude")?;
+
+    Ok(())
 }
 
 /// Temporary struct for parsing worktree list output
@@ -339,6 +1071,7 @@ struct ParsedWorktree {
     branch: String,
     is_detached: bool,
     is_locked: bool,
+    lock_reason: Option<String>,
 }
 
 impl ParsedWorktree {
@@ -348,6 +1081,7 @@ impl ParsedWorktree {
             branch: self.branch,
             is_detached: self.is_detached,
             is_locked: self.is_locked,
+            lock_reason: self.lock_reason,
         }
     }
 }
@@ -386,6 +1120,56 @@ mod tests {
         assert_eq!(wt.branch, "main");
         assert!(!wt.is_detached);
         assert!(!wt.is_locked);
+        assert_eq!(wt.lock_reason, None);
+    }
+
+    #[test]
+    fn test_parse_lock_reason() {
+        assert_eq!(parse_lock_reason(""), None);
+        assert_eq!(
+            parse_lock_reason(" on removable drive"),
+            Some("on removable drive".to_string())
+        );
+        assert_eq!(
+            parse_lock_reason(r#" "agent run\nstarted at 10:00""#),
+            Some("agent run\nstarted at 10:00".to_string())
+        );
+        assert_eq!(
+            parse_lock_reason(r#" "say \"hi\"""#),
+            Some("say \"hi\"".to_string())
+        );
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_lock_and_unlock_worktree() {
+        let (repo_dir, worktree_dir) = init_repo_with_worktree("maestro_lock_worktree_test");
+
+        lock_worktree(&repo_dir, &worktree_dir, Some("long agent run")).unwrap();
+        let worktrees = list_worktrees(&repo_dir).unwrap();
+        assert!(worktrees[1].is_locked);
+        assert_eq!(worktrees[1].lock_reason.as_deref(), Some("long agent run"));
+
+        // Locked worktrees are protected from removal, even when forced
+        let err = remove_worktree(&repo_dir, &worktree_dir, true).unwrap_err();
+        assert!(err.to_string().contains("long agent run"));
+        assert!(worktree_dir.exists());
+
+        assert!(lock_worktree(&repo_dir, &repo_dir, None).is_err());
+
+        unlock_worktree(&repo_dir, &worktree_dir).unwrap();
+        let worktrees = list_worktrees(&repo_dir).unwrap();
+        assert!(!worktrees[1].is_locked);
+        assert_eq!(worktrees[1].lock_reason, None);
+        assert!(unlock_worktree(&repo_dir, &worktree_dir).is_err());
+
+        // A blank reason locks without one
+        lock_worktree(&repo_dir, &worktree_dir, Some("  ")).unwrap();
+        let worktrees = list_worktrees(&repo_dir).unwrap();
+        assert!(worktrees[1].is_locked);
+        assert_eq!(worktrees[1].lock_reason, None);
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
     }
 
     #[test]
@@ -632,4 +1416,347 @@ mod tests {
         // Clean up
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    /// Create a repository with one commit and a linked worktree on `feature`
+    fn init_repo_with_worktree(name: &str) -> (PathBuf, PathBuf) {
+        let temp_dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        let repo_dir = temp_dir.join("repo");
+        fs::create_dir_all(&repo_dir).unwrap();
+
+        for args in [
+            &["init"][..],
+            &["config", "user.name", "Maestro Test"],
+            &["config", "user.email", "test@maestro.dev"],
+        ] {
+            StdCommand::new("git")
+                .args(args)
+                .current_dir(&repo_dir)
+                .output()
+                .expect("Failed to configure git repo");
+        }
+
+        fs::write(repo_dir.join("README.md"), "# Test").unwrap();
+        for args in [
+            &["add", "."][..],
+            &["commit", "-m", "Initial commit"],
+            &["worktree", "add", "-b", "feature", "../feature"],
+        ] {
+            StdCommand::new("git")
+                .args(args)
+                .current_dir(&repo_dir)
+                .output()
+                .expect("Failed to set up git repo");
+        }
+
+        (repo_dir, temp_dir.join("feature"))
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_remove_worktree_requires_force_when_dirty() {
+        let (repo_dir, worktree_dir) = init_repo_with_worktree("maestro_remove_worktree_test");
+        assert!(worktree_dir.exists());
+
+        fs::write(worktree_dir.join("scratch.txt"), "uncommitted").unwrap();
+        assert_eq!(count_uncommitted_changes(&worktree_dir).unwrap(), 1);
+
+        assert!(remove_worktree(&repo_dir, &worktree_dir, false).is_err());
+        assert!(worktree_dir.exists());
+
+        remove_worktree(&repo_dir, &worktree_dir, true).unwrap();
+        assert!(!worktree_dir.exists());
+        assert_eq!(list_worktrees(&repo_dir).unwrap().len(), 1);
+
+        // The root worktree is never removed
+        assert!(remove_worktree(&repo_dir, &repo_dir, true).is_err());
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_prune_worktrees() {
+        let (repo_dir, worktree_dir) = init_repo_with_worktree("maestro_prune_worktree_test");
+
+        fs::remove_dir_all(&worktree_dir).unwrap();
+        let pruned = prune_worktrees(&repo_dir).unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(list_worktrees(&repo_dir).unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_delete_branch_if_merged() {
+        let (repo_dir, worktree_dir) = init_repo_with_worktree("maestro_delete_branch_test");
+
+        // An unmerged commit keeps the branch
+        fs::write(worktree_dir.join("feature.txt"), "feature").unwrap();
+        for args in [&["add", "."][..], &["commit", "-m", "Feature work"]] {
+            StdCommand::new("git")
+                .args(args)
+                .current_dir(&worktree_dir)
+                .output()
+                .expect("Failed to commit in worktree");
+        }
+        remove_worktree(&repo_dir, &worktree_dir, false).unwrap();
+        assert!(!delete_branch_if_merged(&repo_dir, "feature").unwrap());
+
+        StdCommand::new("git")
+            .args(["merge", "--ff-only", "feature"])
+            .current_dir(&repo_dir)
+            .output()
+            .expect("Failed to merge");
+        assert!(delete_branch_if_merged(&repo_dir, "feature").unwrap());
+        assert!(delete_branch_if_merged(&repo_dir, "feature").is_err());
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_delete_branch_compares_with_default_branch() {
+        let (repo_dir, worktree_dir) = init_repo_with_worktree("maestro_delete_default_test");
+        let default = get_root_worktree(&open_root_repository(&repo_dir).unwrap())
+            .unwrap()
+            .branch;
+
+        fs::write(worktree_dir.join("feature.txt"), "feature").unwrap();
+        run_git(&worktree_dir, &["add", "."]);
+        run_git(&worktree_dir, &["commit", "-m", "Feature work"]);
+        remove_worktree(&repo_dir, &worktree_dir, false).unwrap();
+
+        // Merged into the root's current branch, but not into the default branch
+        run_git(&repo_dir, &["checkout", "-b", "side"]);
+        run_git(&repo_dir, &["merge", "--ff-only", "feature"]);
+        assert!(!delete_branch_if_merged(&repo_dir, "feature").unwrap());
+
+        // Merged into the default branch while the root is elsewhere
+        run_git(&repo_dir, &["checkout", &default]);
+        run_git(&repo_dir, &["merge", "--ff-only", "feature"]);
+        run_git(&repo_dir, &["checkout", "-b", "elsewhere", "HEAD~1"]);
+        assert!(delete_branch_if_merged(&repo_dir, "feature").unwrap());
+        assert!(delete_branch_if_merged(&repo_dir, &default).is_err());
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories
+    fn test_delete_branch_keeps_default_branch_of_clone() {
+        let temp_dir = std::env::temp_dir().join("maestro_delete_clone_default_test");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let origin_dir = temp_dir.join("origin");
+        fs::create_dir_all(&origin_dir).unwrap();
+        run_git(&origin_dir, &["init", "-b", "main"]);
+        run_git(&origin_dir, &["config", "user.name", "Maestro Test"]);
+        run_git(&origin_dir, &["config", "user.email", "test@maestro.dev"]);
+        fs::write(origin_dir.join("README.md"), "# Test").unwrap();
+        run_git(&origin_dir, &["add", "."]);
+        run_git(&origin_dir, &["commit", "-m", "Initial commit"]);
+        run_git(&temp_dir, &["clone", "origin", "clone"]);
+        let clone_dir = temp_dir.join("clone");
+
+        // Local main is merged into origin/main, but it is the default branch
+        run_git(&clone_dir, &["checkout", "-b", "other"]);
+        let error = delete_branch_if_merged(&clone_dir, "main").unwrap_err();
+        assert!(error.to_string().contains("default branch"));
+        assert!(
+            Repository::open(&clone_dir)
+                .unwrap()
+                .find_branch("main", git2::BranchType::Local)
+                .is_ok()
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        StdCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+    }
+
+    #[test]
+    fn test_default_worktree_dir_name() {
+        let location = WorktreeLocation::default();
+
+        let new_branch = WorktreeSource::NewBranch {
+            name: "feature/login".to_string(),
+            base: Some("main".to_string()),
+        };
+        assert_eq!(
+            default_worktree_dir_name(&new_branch, &location, "maestro"),
+            "feature-login"
+        );
+
+        let existing = WorktreeSource::ExistingBranch("origin/fix/crash".to_string());
+        assert_eq!(
+            default_worktree_dir_name(&existing, &location, "maestro"),
+            "origin-fix-crash"
+        );
+
+        let detached = WorktreeSource::Detached("v1.0".to_string());
+        assert_eq!(
+            default_worktree_dir_name(&detached, &location, "maestro"),
+            "detached-v1.0"
+        );
+
+        let templated = WorktreeLocation {
+            root: WorktreeRoot::Global,
+            name_template: "{repo}-{branch}".to_string(),
+        };
+        assert_eq!(
+            default_worktree_dir_name(&new_branch, &templated, "maestro"),
+            "maestro-feature-login"
+        );
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_create_worktree_from_existing_branch_and_base() {
+        let (repo_dir, feature_dir) = init_repo_with_worktree("maestro_existing_branch_test");
+
+        // `feature` is checked out in the linked worktree already
+        let existing = WorktreeSource::ExistingBranch("feature".to_string());
+        let err = create_worktree_from(
+            &repo_dir,
+            &existing,
+            Some("feature-again"),
+            &WorktreeLocation::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("already checked out"));
+        assert_eq!(
+            find_worktree_for_branch(&repo_dir, "feature").unwrap(),
+            Some(feature_dir.clone())
+        );
+
+        remove_worktree(&repo_dir, &feature_dir, false).unwrap();
+        let worktree =
+            create_worktree_from(&repo_dir, &existing, None, &WorktreeLocation::default()).unwrap();
+        assert_eq!(worktree.branch, "feature");
+        assert!(worktree.path.ends_with("feature"));
+
+        // New branch from an explicit base
+        let new_branch = WorktreeSource::NewBranch {
+            name: "hotfix".to_string(),
+            base: Some("feature".to_string()),
+        };
+        let worktree =
+            create_worktree_from(&repo_dir, &new_branch, None, &WorktreeLocation::default())
+                .unwrap();
+        assert_eq!(worktree.branch, "hotfix");
+
+        let unknown_base = WorktreeSource::NewBranch {
+            name: "other".to_string(),
+            base: Some("does-not-exist".to_string()),
+        };
+        assert!(
+            create_worktree_from(&repo_dir, &unknown_base, None, &WorktreeLocation::default())
+                .is_err()
+        );
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_create_worktree_from_remote_branch_and_tag() {
+        let (origin_dir, _) = init_repo_with_worktree("maestro_remote_branch_test");
+        run_git(&origin_dir, &["tag", "v1.0"]);
+
+        let clone_dir = origin_dir.parent().unwrap().join("clone");
+        StdCommand::new("git")
+            .args(["clone", "repo", "clone"])
+            .current_dir(origin_dir.parent().unwrap())
+            .output()
+            .expect("Failed to clone");
+
+        let refs = list_refs(&clone_dir).unwrap();
+        assert!(refs.contains(&GitRef {
+            name: "origin/feature".to_string(),
+            kind: RefKind::RemoteBranch,
+        }));
+        assert!(refs.contains(&GitRef {
+            name: "v1.0".to_string(),
+            kind: RefKind::Tag,
+        }));
+        assert!(!refs.iter().any(|r| r.name == "origin/HEAD"));
+
+        let remote = WorktreeSource::ExistingBranch("origin/feature".to_string());
+        let worktree = create_worktree_from(
+            &clone_dir,
+            &remote,
+            Some("pr-review"),
+            &WorktreeLocation::default(),
+        )
+        .unwrap();
+        assert_eq!(worktree.branch, "feature");
+        assert!(!worktree.is_detached);
+
+        let detached = WorktreeSource::Detached("v1.0".to_string());
+        let worktree =
+            create_worktree_from(&clone_dir, &detached, None, &WorktreeLocation::default())
+                .unwrap();
+        assert!(worktree.is_detached);
+        assert!(worktree.path.ends_with("detached-v1.0"));
+
+        let worktrees = list_worktrees(&clone_dir).unwrap();
+        assert_eq!(worktrees.len(), 3);
+        assert!(worktrees.iter().any(|wt| wt.is_detached));
+
+        let _ = fs::remove_dir_all(origin_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_in_repo_location_and_relocation() {
+        let (repo_dir, feature_dir) = init_repo_with_worktree("maestro_relocate_test");
+        let in_repo = WorktreeLocation {
+            root: WorktreeRoot::InRepo,
+            name_template: "{branch}".to_string(),
+        };
+
+        let source = WorktreeSource::NewBranch {
+            name: "inside".to_string(),
+            base: None,
+        };
+        let worktree = create_worktree_from(&repo_dir, &source, None, &in_repo).unwrap();
+        assert_eq!(worktree.path, repo_dir.join(".worktrees").join("inside"));
+
+        // The nested worktree directory does not show up as untracked
+        assert_eq!(count_uncommitted_changes(&repo_dir).unwrap(), 0);
+
+        // Listing from inside a linked worktree still reports the root first
+        let worktrees = list_worktrees(&worktree.path).unwrap();
+        assert_eq!(worktrees.len(), 3);
+        assert!(same_path(&worktrees[0].path, &repo_dir));
+
+        let planned = worktrees_to_relocate(&repo_dir, &in_repo).unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].0.path, feature_dir);
+
+        let report = relocate_worktrees(&repo_dir, &in_repo).unwrap();
+        assert!(report.failed.is_empty());
+        assert_eq!(
+            report.moved,
+            vec![(
+                feature_dir.clone(),
+                repo_dir.join(".worktrees").join("feature")
+            )]
+        );
+        assert!(!feature_dir.exists());
+        assert!(
+            worktrees_to_relocate(&repo_dir, &in_repo)
+                .unwrap()
+                .is_empty()
+        );
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
 }
@@ -1,6 +1,16 @@
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Directory under the home directory used by `WorktreeRoot::Global`
+pub const GLOBAL_WORKTREES_DIR: &str = "maestro-worktrees";
+
+/// Directory inside the repository used by `WorktreeRoot::InRepo`
+pub const IN_REPO_WORKTREES_DIR: &str = ".worktrees";
+
+/// Default worktree directory name template
+pub const DEFAULT_WORKTREE_NAME_TEMPLATE: &str = "{branch}";
 
 /// Represents a workspace entry with its metadata
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -29,6 +39,110 @@ impl WorkspaceEntry {
     }
 }
 
+/// Directory that new worktrees are created in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeRoot {
+    /// Next to the repository: `<repo>/../<name>`
+    #[default]
+    Sibling,
+    /// Inside the repository: `<repo>/.worktrees/<name>`
+    InRepo,
+    /// Under the home directory: `~/maestro-worktrees/<repo>/<name>`
+    Global,
+}
+
+impl WorktreeRoot {
+    /// All options, in the order shown in the settings panel
+    pub const ALL: [WorktreeRoot; 3] = [
+        WorktreeRoot::Sibling,
+        WorktreeRoot::InRepo,
+        WorktreeRoot::Global,
+    ];
+
+    /// Human-readable description of the layout
+    pub fn description(self) -> &'static str {
+        match self {
+            WorktreeRoot::Sibling => "Next to the repository",
+            WorktreeRoot::InRepo => "Inside the repository (.worktrees/)",
+            WorktreeRoot::Global => "~/maestro-worktrees/<repo>/",
+        }
+    }
+}
+
+/// Where worktrees are created and how their directories are named
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorktreeLocation {
+    /// Directory that contains the worktrees
+    pub root: WorktreeRoot,
+    /// Directory name template; `{repo}` and `{branch}` are substituted
+    pub name_template: String,
+}
+
+impl Default for WorktreeLocation {
+    fn default() -> Self {
+        Self {
+            root: WorktreeRoot::default(),
+            name_template: DEFAULT_WORKTREE_NAME_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl WorktreeLocation {
+    /// Directory that contains the worktrees of a repository
+    ///
+    /// # Arguments
+    ///
+    /// * `repo_root` - Working directory of the root worktree
+    /// * `repo_name` - Name of the repository, used by the global layout
+    pub fn parent_dir(&self, repo_root: &Path, repo_name: &str) -> Result<PathBuf> {
+        match self.root {
+            WorktreeRoot::Sibling => repo_root
+                .parent()
+                .map(Path::to_path_buf)
+                .context("Repository is at filesystem root"),
+            WorktreeRoot::InRepo => Ok(repo_root.join(IN_REPO_WORKTREES_DIR)),
+            WorktreeRoot::Global => Ok(dirs::home_dir()
+                .context("Could not determine home directory")?
+                .join(GLOBAL_WORKTREES_DIR)
+                .join(repo_name)),
+        }
+    }
+
+    /// Directory name for a worktree, from the name template
+    ///
+    /// Slashes in the result are replaced with dashes so the worktree is
+    /// always a direct child of the parent directory.
+    pub fn dir_name(&self, repo_name: &str, branch: &str) -> String {
+        let template = if self.name_template.trim().is_empty() {
+            DEFAULT_WORKTREE_NAME_TEMPLATE
+        } else {
+            self.name_template.as_str()
+        };
+
+        template
+            .replace("{repo}", repo_name)
+            .replace("{branch}", branch)
+            .replace('/', "-")
+    }
+}
+
+/// Behaviour of the embedded terminals
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TerminalSettings {
+    /// Ask before pasting multiple lines into an application without
+    /// bracketed paste, where each line would run on its own
+    pub confirm_multiline_paste: bool,
+}
+
+impl Default for TerminalSettings {
+    fn default() -> Self {
+        Self {
+            confirm_multiline_paste: true,
+        }
+    }
+}
+
 /// Application settings
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Settings {
@@ -36,6 +150,12 @@ pub struct Settings {
     pub recent_workspaces: Vec<WorkspaceEntry>,
     /// Maximum number of recent workspaces to keep
     pub max_recent_workspaces: usize,
+    /// Where new worktrees are created
+    #[serde(default)]
+    pub worktree_location: WorktreeLocation,
+    /// Behaviour of the embedded terminals
+    #[serde(default)]
+    pub terminal: TerminalSettings,
 }
 
 impl Default for Settings {
@@ -43,6 +163,8 @@ impl Default for Settings {
         Self {
             recent_workspaces: Vec::new(),
             max_recent_workspaces: 10,
+            worktree_location: WorktreeLocation::default(),
+            terminal: TerminalSettings::default(),
         }
     }
 }
@@ -190,6 +312,55 @@ mod tests {
         assert_eq!(settings.recent_workspaces[2].name, "workspace1");
     }
 
+    #[test]
+    fn test_worktree_location_parent_dir() {
+        let repo_root = Path::new("/projects/maestro");
+        let mut location = WorktreeLocation::default();
+
+        assert_eq!(
+            location.parent_dir(repo_root, "maestro").unwrap(),
+            PathBuf::from("/projects")
+        );
+
+        location.root = WorktreeRoot::InRepo;
+        assert_eq!(
+            location.parent_dir(repo_root, "maestro").unwrap(),
+            PathBuf::from("/projects/maestro/.worktrees")
+        );
+
+        location.root = WorktreeRoot::Global;
+        let global = location.parent_dir(repo_root, "maestro").unwrap();
+        assert!(global.ends_with("maestro-worktrees/maestro"));
+    }
+
+    #[test]
+    fn test_worktree_location_dir_name() {
+        let mut location = WorktreeLocation::default();
+        assert_eq!(
+            location.dir_name("maestro", "feature/login"),
+            "feature-login"
+        );
+
+        location.name_template = "{repo}-{branch}".to_string();
+        assert_eq!(
+            location.dir_name("maestro", "feature/login"),
+            "maestro-feature-login"
+        );
+
+        location.name_template = "  ".to_string();
+        assert_eq!(location.dir_name("maestro", "fix"), "fix");
+    }
+
+    #[test]
+    fn test_settings_without_worktree_location_deserialize() {
+        let json = r#"{"recent_workspaces":[],"max_recent_workspaces":5}"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+
+        assert_eq!(settings.max_recent_workspaces, 5);
+        assert_eq!(settings.worktree_location, WorktreeLocation::default());
+        assert!(settings.terminal.confirm_multiline_paste);
+    }
+
     #[test]
     fn test_serialization() {
         let mut settings = Settings::new();
@@ -1,5 +1,5 @@
 pub mod config;
 pub mod persistence;
 
-pub use config::{Settings, WorkspaceEntry};
+pub use config::{Settings, TerminalSettings, WorkspaceEntry, WorktreeLocation, WorktreeRoot};
 pub use persistence::{load_settings, save_settings};
@@ -0,0 +1,861 @@
+//! Box-drawing, block, Powerline and Braille characters drawn as geometry.
+//!
+//! Fonts draw these characters to their own idea of the cell size, so lines
+//! and blocks from neighbouring cells often don't meet and the look changes
+//! from font to font. Like Kitty and WezTerm, the terminal draws them itself
+//! from rectangles and polygons scaled to the cell box, which makes the
+//! borders, bars and plots of TUIs seamless regardless of the font.
+//!
+//! Covered are Box Drawing (U+2500-U+257F), Block Elements (U+2580-U+259F),
+//! Braille Patterns (U+2800-U+28FF), the Powerline symbols (U+E0B0-U+E0BF)
+//! and the Powerline extra symbols (U+E0C0-U+E0D7). The pictorial extras
+//! (flames, pixels, ice, honeycomb and lego) are simplified to shapes that
+//! still join the neighbouring cell.
+
+use std::f32::consts::FRAC_PI_2;
+
+/// Size of a cell and the width of a light line in it, in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellBox {
+    pub width: f32,
+    pub height: f32,
+    pub thickness: f32,
+}
+
+/// A filled shape in pixels relative to the top left corner of the cell
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlyphShape {
+    /// Rectangle filled with the text color at `alpha` opacity
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        alpha: f32,
+    },
+    /// Closed polygon filled with the text color
+    Polygon(Vec<(f32, f32)>),
+}
+
+/// Shapes drawing `ch` in a cell, or `None` if the font should draw it
+pub fn builtin_glyph(ch: char, cell: CellBox) -> Option<Vec<GlyphShape>> {
+    let code = ch as u32;
+    match code {
+        0x2500..=0x257F => Some(box_drawing(code, cell)),
+        0x2580..=0x259F => Some(block_element(code, cell)),
+        0x2800..=0x28FF => Some(braille(code, cell)),
+        0xE0B0..=0xE0BF => Some(powerline(code, cell)),
+        0xE0C0..=0xE0D7 => powerline_extra(code, cell),
+        _ => None,
+    }
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> GlyphShape {
+    GlyphShape::Rect {
+        x,
+        y,
+        width,
+        height,
+        alpha: 1.,
+    }
+}
+
+/// Line weight towards one edge of the cell
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Weight {
+    None,
+    Light,
+    Heavy,
+    Double,
+}
+
+impl Weight {
+    /// Width of the stroke, or of each of the two strokes of a double line
+    fn thickness(self, light: f32) -> f32 {
+        match self {
+            Weight::Heavy => light * 2.,
+            _ => light,
+        }
+    }
+}
+
+/// Weights of the lines towards the top, right, bottom and left edge of each
+/// character in U+2500-U+257F: 0 none, 1 light, 2 heavy, 3 double. Arcs and
+/// diagonals are drawn separately and have no entry.
+#[rustfmt::skip]
+const BOX_LINES: [&str; 0x80] = [
+    "0101", "0202", "1010", "2020", "0101", "0202", "1010", "2020", // ─━│┃┄┅┆┇
+    "0101", "0202", "1010", "2020", "0110", "0210", "0120", "0220", // ┈┉┊┋┌┍┎┏
+    "0011", "0012", "0021", "0022", "1100", "1200", "2100", "2200", // ┐┑┒┓└┕┖┗
+    "1001", "1002", "2001", "2002", "1110", "1210", "2110", "1120", // ┘┙┚┛├┝┞┟
+    "2120", "2210", "1220", "2220", "1011", "1012", "2011", "1021", // ┠┡┢┣┤┥┦┧
+    "2021", "2012", "1022", "2022", "0111", "0112", "0211", "0212", // ┨┩┪┫┬┭┮┯
+    "0121", "0122", "0221", "0222", "1101", "1102", "1201", "1202", // ┰┱┲┳┴┵┶┷
+    "2101", "2102", "2201", "2202", "1111", "1112", "1211", "1212", // ┸┹┺┻┼┽┾┿
+    "2111", "1121", "2121", "2112", "2211", "1122", "1221", "2212", // ╀╁╂╃╄╅╆╇
+    "1222", "2122", "2221", "2222", "0101", "0202", "1010", "2020", // ╈╉╊╋╌╍╎╏
+    "0303", "3030", "0310", "0130", "0330", "0013", "0031", "0033", // ═║╒╓╔╕╖╗
+    "1300", "3100", "3300", "1003", "3001", "3003", "1310", "3130", // ╘╙╚╛╜╝╞╟
+    "3330", "1013", "3031", "3033", "0313", "0131", "0333", "1303", // ╠╡╢╣╤╥╦╧
+    "3101", "3303", "1313", "3131", "3333", "", "", "", //               ╨╩╪╫╬╭╮╯
+    "", "", "", "", "0001", "1000", "0100", "0010", //                   ╰╱╲╳╴╵╶╷
+    "0002", "2000", "0200", "0020", "0201", "1020", "0102", "2010", // ╸╹╺╻╼╽╾╿
+];
+
+fn box_weights(code: u32) -> [Weight; 4] {
+    let mut weights = [Weight::None; 4];
+    for (weight, digit) in weights
+        .iter_mut()
+        .zip(BOX_LINES[(code - 0x2500) as usize].bytes())
+    {
+        *weight = match digit {
+            b'1' => Weight::Light,
+            b'2' => Weight::Heavy,
+            b'3' => Weight::Double,
+            _ => Weight::None,
+        };
+    }
+    weights
+}
+
+/// Start and end of a stroke across `center`, snapped to whole pixels so
+/// strokes of the same width line up from cell to cell
+fn span(center: f32, thickness: f32) -> (f32, f32) {
+    let start = (center - thickness / 2.).floor();
+    (start, start + thickness)
+}
+
+/// How far a line reaches into the center of the cell, depending on the
+/// lines crossing its path
+#[derive(Clone, Copy)]
+enum Reach {
+    /// Up to the center, where it meets the line opposite
+    Center,
+    /// Across crossing strokes of the given width
+    Cover(f32),
+    /// Up to the nearer stroke of a crossing double line
+    NearStroke,
+    /// Across both strokes of a crossing double line
+    FarStroke,
+}
+
+impl Reach {
+    /// Reach of a light or heavy line between crossing lines on either side
+    fn single(before: Weight, after: Weight, light: f32) -> Reach {
+        match (before, after) {
+            (Weight::Double, Weight::Double) => Reach::NearStroke,
+            (Weight::Double, _) | (_, Weight::Double) => Reach::FarStroke,
+            (Weight::None, Weight::None) => Reach::Center,
+            _ => Reach::Cover(before.thickness(light).max(after.thickness(light))),
+        }
+    }
+
+    /// Reach of one stroke of a double line, `near` being the crossing line
+    /// on the side of that stroke
+    fn double(near: Weight, far: Weight, light: f32) -> Reach {
+        match (near, far) {
+            (Weight::Double, _) => Reach::NearStroke,
+            (_, Weight::Double) => Reach::FarStroke,
+            (Weight::None, Weight::None) => Reach::Center,
+            _ => Reach::Cover(near.thickness(light).max(far.thickness(light))),
+        }
+    }
+
+    /// Coordinate where a line coming from the start (`from_end` false) or
+    /// the end of the axis stops
+    fn end(self, center: f32, gap: f32, light: f32, from_end: bool) -> f32 {
+        let edge = |(start, end): (f32, f32)| if from_end { start } else { end };
+        let toward_edge = if from_end { gap } else { -gap };
+        match self {
+            Reach::Center => center,
+            Reach::Cover(thickness) => edge(span(center, thickness)),
+            Reach::NearStroke => edge(span(center + toward_edge, light)),
+            Reach::FarStroke => edge(span(center - toward_edge, light)),
+        }
+    }
+}
+
+fn box_drawing(code: u32, cell: CellBox) -> Vec<GlyphShape> {
+    match code {
+        0x256D..=0x2570 => return arc(code, cell),
+        0x2571 => return vec![line(cell.width, 0., 0., cell.height, cell.thickness)],
+        0x2572 => return vec![line(0., 0., cell.width, cell.height, cell.thickness)],
+        0x2573 => {
+            return vec![
+                line(cell.width, 0., 0., cell.height, cell.thickness),
+                line(0., 0., cell.width, cell.height, cell.thickness),
+            ];
+        }
+        _ => {}
+    }
+
+    let weights = box_weights(code);
+    let dashes = match code {
+        0x2504..=0x2507 => 3,
+        0x2508..=0x250B => 4,
+        0x254C..=0x254F => 2,
+        _ => 0,
+    };
+    if dashes > 0 {
+        return dashed(weights, dashes, cell);
+    }
+    box_lines(weights, cell)
+}
+
+/// Light, heavy and double lines from the center to the edges of the cell
+fn box_lines([up, right, down, left]: [Weight; 4], cell: CellBox) -> Vec<GlyphShape> {
+    let light = cell.thickness;
+    // Distance of each stroke of a double line from the center
+    let gap = light;
+    let (cx, cy) = (cell.width / 2., cell.height / 2.);
+    let mut shapes = Vec::new();
+
+    // Strokes of a line with the reach of each, by their center across it
+    let strokes = |weight: Weight, center: f32, before: Weight, after: Weight| match weight {
+        Weight::None => vec![],
+        Weight::Double => vec![
+            (center - gap, Reach::double(before, after, light)),
+            (center + gap, Reach::double(after, before, light)),
+        ],
+        _ => vec![(center, Reach::single(before, after, light))],
+    };
+
+    for (weight, from_end) in [(left, false), (right, true)] {
+        for (center, reach) in strokes(weight, cy, up, down) {
+            let (y0, y1) = span(center, weight.thickness(light));
+            let x = reach.end(cx, gap, light, from_end);
+            let (x0, x1) = if from_end { (x, cell.width) } else { (0., x) };
+            shapes.push(rect(x0, y0, x1 - x0, y1 - y0));
+        }
+    }
+    for (weight, from_end) in [(up, false), (down, true)] {
+        for (center, reach) in strokes(weight, cx, left, right) {
+            let (x0, x1) = span(center, weight.thickness(light));
+            let y = reach.end(cy, gap, light, from_end);
+            let (y0, y1) = if from_end { (y, cell.height) } else { (0., y) };
+            shapes.push(rect(x0, y0, x1 - x0, y1 - y0));
+        }
+    }
+    shapes
+}
+
+/// A horizontal or vertical line broken into `dashes` dashes, one per
+/// section of the cell so they repeat evenly across cells
+fn dashed([up, right, _, _]: [Weight; 4], dashes: u32, cell: CellBox) -> Vec<GlyphShape> {
+    let horizontal = right != Weight::None;
+    let weight = if horizontal { right } else { up };
+    let (length, across) = if horizontal {
+        (cell.width, cell.height)
+    } else {
+        (cell.height, cell.width)
+    };
+    let (start, end) = span(across / 2., weight.thickness(cell.thickness));
+    let section = length / dashes as f32;
+    let gap = (section / 4.).round().max(1.);
+
+    (0..dashes)
+        .map(|i| {
+            let along = (section * i as f32 + gap / 2.).round();
+            let dash = section - gap;
+            if horizontal {
+                rect(along, start, dash, end - start)
+            } else {
+                rect(start, along, end - start, dash)
+            }
+        })
+        .collect()
+}
+
+/// Rounded corners ╭╮╯╰: a quarter circle joining a vertical and a
+/// horizontal light line
+fn arc(code: u32, cell: CellBox) -> Vec<GlyphShape> {
+    const SEGMENTS: usize = 12;
+    let light = cell.thickness;
+    // Directions of the two arms from the center
+    let (dx, dy) = match code {
+        0x256D => (1., 1.),
+        0x256E => (-1., 1.),
+        0x256F => (-1., -1.),
+        _ => (1., -1.),
+    };
+    let (x0, x1) = span(cell.width / 2., light);
+    let (y0, y1) = span(cell.height / 2., light);
+    let (lx, ly) = ((x0 + x1) / 2., (y0 + y1) / 2.);
+    let to_x_edge = if dx > 0. { cell.width - lx } else { lx };
+    let to_y_edge = if dy > 0. { cell.height - ly } else { ly };
+    let radius = to_x_edge.min(to_y_edge);
+    let (ox, oy) = (lx + dx * radius, ly + dy * radius);
+
+    let point = |r: f32, angle: f32| (ox - dx * r * angle.cos(), oy - dy * r * angle.sin());
+    let angles = (0..=SEGMENTS).map(|i| FRAC_PI_2 * i as f32 / SEGMENTS as f32);
+    let mut points: Vec<_> = angles
+        .clone()
+        .map(|a| point(radius + light / 2., a))
+        .collect();
+    points.extend(angles.rev().map(|a| point(radius - light / 2., a)));
+
+    let mut shapes = vec![GlyphShape::Polygon(points)];
+    // Straight parts left over on the longer side of the cell
+    let arc_y = ly + dy * radius;
+    if (to_y_edge - radius) > 0. {
+        let (start, end) = if dy > 0. {
+            (arc_y, cell.height)
+        } else {
+            (0., arc_y)
+        };
+        shapes.push(rect(x0, start, x1 - x0, end - start));
+    }
+    let arc_x = lx + dx * radius;
+    if (to_x_edge - radius) > 0. {
+        let (start, end) = if dx > 0. {
+            (arc_x, cell.width)
+        } else {
+            (0., arc_x)
+        };
+        shapes.push(rect(start, y0, end - start, y1 - y0));
+    }
+    shapes
+}
+
+/// A straight line of the given width between two points
+fn line(x0: f32, y0: f32, x1: f32, y1: f32, thickness: f32) -> GlyphShape {
+    let length = (x1 - x0).hypot(y1 - y0);
+    let nx = -(y1 - y0) / length * thickness / 2.;
+    let ny = (x1 - x0) / length * thickness / 2.;
+    GlyphShape::Polygon(vec![
+        (x0 + nx, y0 + ny),
+        (x1 + nx, y1 + ny),
+        (x1 - nx, y1 - ny),
+        (x0 - nx, y0 - ny),
+    ])
+}
+
+/// Quadrants filled by ▖▗▘▙▚▛▜▝▞▟, as bits for the upper left, upper
+/// right, lower left and lower right quadrant
+const QUADRANTS: [u8; 10] = [4, 8, 1, 13, 9, 7, 11, 2, 6, 14];
+
+fn block_element(code: u32, cell: CellBox) -> Vec<GlyphShape> {
+    let (w, h) = (cell.width, cell.height);
+    // Splitting points are rounded the same way for every character, so
+    // complementary blocks such as ▀ and ▄ tile without overlap
+    let x_at = |eighths: u32| (w * eighths as f32 / 8.).round();
+    let y_at = |eighths: u32| (h * eighths as f32 / 8.).round();
+
+    match code {
+        0x2580 => vec![rect(0., 0., w, y_at(4))],
+        // ▁▂▃▄▅▆▇█
+        0x2581..=0x2588 => {
+            let top = y_at(8 - (code - 0x2580));
+            vec![rect(0., top, w, h - top)]
+        }
+        // ▉▊▋▌▍▎▏
+        0x2589..=0x258F => vec![rect(0., 0., x_at(0x2590 - code), h)],
+        0x2590 => vec![rect(x_at(4), 0., w - x_at(4), h)],
+        // ░▒▓
+        0x2591..=0x2593 => vec![GlyphShape::Rect {
+            x: 0.,
+            y: 0.,
+            width: w,
+            height: h,
+            alpha: (code - 0x2590) as f32 / 4.,
+        }],
+        0x2594 => vec![rect(0., 0., w, y_at(1))],
+        0x2595 => vec![rect(x_at(7), 0., w - x_at(7), h)],
+        _ => {
+            let bits = QUADRANTS[(code - 0x2596) as usize];
+            let (mx, my) = (x_at(4), y_at(4));
+            [
+                rect(0., 0., mx, my),
+                rect(mx, 0., w - mx, my),
+                rect(0., my, mx, h - my),
+                rect(mx, my, w - mx, h - my),
+            ]
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| bits & (1 << i) != 0)
+            .map(|(_, shape)| shape)
+            .collect()
+        }
+    }
+}
+
+/// Column and row of the dot for each bit of a Braille pattern: dots 1-3
+/// and 7 form the left column, 4-6 and 8 the right one
+const BRAILLE_DOTS: [(u32, u32); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (0, 3),
+    (1, 3),
+];
+
+/// Braille patterns as a 2x4 grid of square dots
+fn braille(code: u32, cell: CellBox) -> Vec<GlyphShape> {
+    let bits = code - 0x2800;
+    let dot = (cell.width / 4.).round().max(1.);
+
+    BRAILLE_DOTS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .map(|(_, &(col, row))| {
+            let cx = cell.width * (2 * col + 1) as f32 / 4.;
+            let cy = cell.height * (2 * row + 1) as f32 / 8.;
+            rect((cx - dot / 2.).round(), (cy - dot / 2.).round(), dot, dot)
+        })
+        .collect()
+}
+
+/// Powerline separators: solid and thin arrows, half circles and slants
+fn powerline(code: u32, cell: CellBox) -> Vec<GlyphShape> {
+    const SEGMENTS: usize = 24;
+    let (w, h) = (cell.width, cell.height);
+    let t = cell.thickness;
+    // Half ellipse from the top left to the bottom left corner
+    let half_circle = |inset: f32| {
+        (0..=SEGMENTS).map(move |i| {
+            let angle = std::f32::consts::PI * i as f32 / SEGMENTS as f32 - FRAC_PI_2;
+            (
+                (w - inset) * angle.cos(),
+                h / 2. + (h / 2. - inset) * angle.sin(),
+            )
+        })
+    };
+    let solid_circle = || GlyphShape::Polygon(half_circle(0.).collect());
+    let thin_circle = || {
+        let mut points: Vec<_> = half_circle(0.).collect();
+        points.extend(half_circle(t).collect::<Vec<_>>().into_iter().rev());
+        GlyphShape::Polygon(points)
+    };
+    let arrow = || vec![line(0., 0., w, h / 2., t), line(w, h / 2., 0., h, t)];
+
+    match code {
+        0xE0B0 => vec![GlyphShape::Polygon(vec![(0., 0.), (w, h / 2.), (0., h)])],
+        0xE0B1 => arrow(),
+        0xE0B2 => vec![GlyphShape::Polygon(vec![(w, 0.), (0., h / 2.), (w, h)])],
+        0xE0B3 => arrow().into_iter().map(|shape| mirror(shape, w)).collect(),
+        0xE0B4 => vec![solid_circle()],
+        0xE0B5 => vec![thin_circle()],
+        0xE0B6 => vec![mirror(solid_circle(), w)],
+        0xE0B7 => vec![mirror(thin_circle(), w)],
+        0xE0B8 => vec![GlyphShape::Polygon(vec![(0., 0.), (0., h), (w, h)])],
+        0xE0BA => vec![GlyphShape::Polygon(vec![(w, 0.), (w, h), (0., h)])],
+        0xE0BC => vec![GlyphShape::Polygon(vec![(0., 0.), (w, 0.), (0., h)])],
+        0xE0BE => vec![GlyphShape::Polygon(vec![(0., 0.), (w, 0.), (w, h)])],
+        // Slanted separators
+        0xE0B9 | 0xE0BF => vec![line(0., 0., w, h, t)],
+        _ => vec![line(w, 0., 0., h, t)],
+    }
+}
+
+/// Right edge of a flame filling the cell from the left, top to bottom, as
+/// fractions of the cell
+const FLAME_EDGE: [(f32, f32); 9] = [
+    (0.35, 0.),
+    (0.85, 0.12),
+    (0.5, 0.22),
+    (1., 0.38),
+    (0.55, 0.5),
+    (0.9, 0.66),
+    (0.45, 0.78),
+    (0.75, 0.9),
+    (0.3, 1.),
+];
+
+/// Right edge of icicles filling the cell from the left
+const ICE_EDGE: [(f32, f32); 8] = [
+    (1., 0.),
+    (0.55, 0.14),
+    (0.85, 0.28),
+    (0.35, 0.42),
+    (1., 0.56),
+    (0.6, 0.72),
+    (0.9, 0.86),
+    (0.45, 1.),
+];
+
+/// Right edge of a lego separator: a wall with two studs pointing right
+const LEGO_EDGE: [(f32, f32); 10] = [
+    (0.6, 0.),
+    (0.6, 0.2),
+    (1., 0.2),
+    (1., 0.4),
+    (0.6, 0.4),
+    (0.6, 0.6),
+    (1., 0.6),
+    (1., 0.8),
+    (0.6, 0.8),
+    (0.6, 1.),
+];
+
+/// Powerline extra separators. Characters without an assignment in the
+/// range are left to the font.
+fn powerline_extra(code: u32, cell: CellBox) -> Option<Vec<GlyphShape>> {
+    let (w, h) = (cell.width, cell.height);
+    let t = cell.thickness;
+    let scale = |edge: &[(f32, f32)]| -> Vec<(f32, f32)> {
+        edge.iter().map(|&(x, y)| (x * w, y * h)).collect()
+    };
+    // Area left of an edge running from the top to the bottom of the cell
+    let filled = |edge: &[(f32, f32)]| {
+        let mut points = vec![(0., 0.)];
+        points.extend(scale(edge));
+        points.push((0., h));
+        GlyphShape::Polygon(points)
+    };
+    let outline = |edge: &[(f32, f32)]| polyline(&scale(edge), t);
+    // Rectangle between fractions of the cell, snapped to whole pixels
+    let block = |x0: f32, y0: f32, x1: f32, y1: f32| {
+        let (x0, y0) = ((x0 * w).round(), (y0 * h).round());
+        rect(x0, y0, (x1 * w).round() - x0, (y1 * h).round() - y0)
+    };
+    let mirrored = |shapes: Vec<GlyphShape>| -> Vec<GlyphShape> {
+        shapes.into_iter().map(|shape| mirror(shape, w)).collect()
+    };
+
+    let shapes = match code {
+        0xE0C0 => vec![filled(&FLAME_EDGE)],
+        0xE0C1 => outline(&FLAME_EDGE),
+        0xE0C2 => vec![mirror(filled(&FLAME_EDGE), w)],
+        0xE0C3 => mirrored(outline(&FLAME_EDGE)),
+        0xE0C4 => pixels(4, cell),
+        0xE0C5 => mirrored(pixels(4, cell)),
+        0xE0C6 => pixels(2, cell),
+        0xE0C7 => mirrored(pixels(2, cell)),
+        0xE0C8 => vec![filled(&ICE_EDGE)],
+        0xE0CA => vec![mirror(filled(&ICE_EDGE), w)],
+        0xE0CC => honeycomb(cell)
+            .into_iter()
+            .map(GlyphShape::Polygon)
+            .collect(),
+        0xE0CD => honeycomb(cell)
+            .into_iter()
+            .flat_map(|mut points| {
+                points.push(points[0]);
+                polyline(&points, t)
+            })
+            .collect(),
+        0xE0CE => vec![filled(&LEGO_EDGE)],
+        0xE0CF => outline(&LEGO_EDGE),
+        // A brick seen from the front, studs on top
+        0xE0D0 => vec![
+            block(0., 0.3, 1., 1.),
+            block(0.15, 0.15, 0.4, 0.3),
+            block(0.6, 0.15, 0.85, 0.3),
+        ],
+        // A brick seen from the side, studs to the right
+        0xE0D1 => vec![
+            block(0., 0.1, 0.7, 0.9),
+            block(0.7, 0.25, 0.9, 0.4),
+            block(0.7, 0.6, 0.9, 0.75),
+        ],
+        // Trapezoids with their parallel sides along the top and bottom
+        0xE0D2 => vec![GlyphShape::Polygon(vec![
+            (0., 0.),
+            (w / 2., 0.),
+            (w, h),
+            (0., h),
+        ])],
+        0xE0D4 => vec![GlyphShape::Polygon(vec![
+            (w / 2., 0.),
+            (w, 0.),
+            (w, h),
+            (0., h),
+        ])],
+        // The cell around a solid arrow, to start a segment with a notch
+        0xE0D6 => vec![
+            GlyphShape::Polygon(vec![(0., 0.), (w, 0.), (w, h / 2.)]),
+            GlyphShape::Polygon(vec![(w, h / 2.), (w, h), (0., h)]),
+        ],
+        0xE0D7 => vec![
+            GlyphShape::Polygon(vec![(0., 0.), (w, 0.), (0., h / 2.)]),
+            GlyphShape::Polygon(vec![(0., h / 2.), (w, h), (0., h)]),
+        ],
+        _ => return None,
+    };
+    Some(shapes)
+}
+
+/// Squares in `columns` columns thinning out to the right, for the
+/// pixelated separators
+fn pixels(columns: u32, cell: CellBox) -> Vec<GlyphShape> {
+    let (w, h) = (cell.width, cell.height);
+    let rows = (h * columns as f32 / w).round().max(1.) as u32;
+    let x_at = |col: u32| (w * col as f32 / columns as f32).round();
+    let y_at = |row: u32| (h * row as f32 / rows as f32).round();
+    // Which squares of each column are filled, repeating down the cell
+    let filled = |col: u32, row: u32| match (columns, col) {
+        (2, 0) | (4, 0 | 1) => true,
+        (2, _) | (4, 2) => row.is_multiple_of(2),
+        _ => row % 4 == 1,
+    };
+
+    (0..columns)
+        .flat_map(|col| (0..rows).map(move |row| (col, row)))
+        .filter(|&(col, row)| filled(col, row))
+        .map(|(col, row)| {
+            let (x0, y0) = (x_at(col), y_at(row));
+            rect(x0, y0, x_at(col + 1) - x0, y_at(row + 1) - y0)
+        })
+        .collect()
+}
+
+/// Flat-topped hexagons stacked down the cell, with a gap between them
+fn honeycomb(cell: CellBox) -> Vec<Vec<(f32, f32)>> {
+    let (w, h) = (cell.width, cell.height);
+    let inset = cell.thickness / 2.;
+    let count = (h / (w * 0.866)).round().max(1.);
+    let height = h / count;
+
+    (0..count as u32)
+        .map(|i| {
+            let top = height * i as f32 + inset;
+            let bottom = top + height - 2. * inset;
+            let middle = (top + bottom) / 2.;
+            vec![
+                (w * 0.25, top),
+                (w * 0.75, top),
+                (w - inset, middle),
+                (w * 0.75, bottom),
+                (w * 0.25, bottom),
+                (inset, middle),
+            ]
+        })
+        .collect()
+}
+
+/// Lines of the given width joining consecutive points
+fn polyline(points: &[(f32, f32)], thickness: f32) -> Vec<GlyphShape> {
+    points
+        .windows(2)
+        .map(|pair| line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, thickness))
+        .collect()
+}
+
+/// A shape flipped from left to right within a cell of the given width
+fn mirror(shape: GlyphShape, width: f32) -> GlyphShape {
+    match shape {
+        GlyphShape::Polygon(points) => {
+            GlyphShape::Polygon(points.into_iter().map(|(x, y)| (width - x, y)).collect())
+        }
+        GlyphShape::Rect {
+            x,
+            y,
+            width: rect_width,
+            height,
+            alpha,
+        } => GlyphShape::Rect {
+            x: width - x - rect_width,
+            y,
+            width: rect_width,
+            height,
+            alpha,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL: CellBox = CellBox {
+        width: 9.,
+        height: 19.,
+        thickness: 1.,
+    };
+
+    /// Rectangles of a glyph as (x0, y0, x1, y1)
+    fn rects(ch: char) -> Vec<(f32, f32, f32, f32)> {
+        builtin_glyph(ch, CELL)
+            .unwrap()
+            .into_iter()
+            .filter_map(|shape| match shape {
+                GlyphShape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    ..
+                } => Some((x, y, x + width, y + height)),
+                GlyphShape::Polygon(_) => None,
+            })
+            .collect()
+    }
+
+    fn covers(rects: &[(f32, f32, f32, f32)], x: f32, y: f32) -> bool {
+        rects
+            .iter()
+            .any(|&(x0, y0, x1, y1)| x0 <= x && x < x1 && y0 <= y && y < y1)
+    }
+
+    #[test]
+    fn test_lines_reach_the_cell_edges() {
+        // ─ spans the width in one pixel row, centered vertically
+        let horizontal = rects('─');
+        assert_eq!(horizontal.len(), 2);
+        assert!(covers(&horizontal, 0., 9.) && covers(&horizontal, 8.5, 9.));
+        assert!(!covers(&horizontal, 4., 8.) && !covers(&horizontal, 4., 10.));
+
+        // ┃ spans the height two pixels wide
+        let heavy = rects('┃');
+        assert!(covers(&heavy, 3., 0.) && covers(&heavy, 4., 18.5));
+        assert!(!covers(&heavy, 2., 5.) && !covers(&heavy, 5., 5.));
+
+        // ┼ has all four arms
+        let cross = rects('┼');
+        for (x, y) in [(0., 9.), (8.5, 9.), (4., 0.), (4., 18.5)] {
+            assert!(covers(&cross, x, y), "┼ misses ({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_corners_join_without_gaps() {
+        // The arms of ┌ meet in a square corner, nothing sticks out above or left
+        let corner = rects('┌');
+        assert!(covers(&corner, 4., 9.));
+        assert!(!covers(&corner, 3., 9.) && !covers(&corner, 4., 8.));
+
+        // A light arm reaches across the full width of a heavy one
+        let mixed = rects('┒');
+        assert!(covers(&mixed, 4., 9.) && covers(&mixed, 3., 9.));
+        assert!(!covers(&mixed, 5., 9.) && !covers(&mixed, 4., 8.));
+    }
+
+    #[test]
+    fn test_double_lines() {
+        // ═ is two full width strokes with a one pixel gap
+        let double = rects('═');
+        assert!(covers(&double, 0., 8.) && covers(&double, 8.5, 10.));
+        assert!(!covers(&double, 4., 9.));
+
+        // ╔ has an outer corner around an inner one
+        let corner = rects('╔');
+        assert!(covers(&corner, 3., 8.) && covers(&corner, 5., 10.));
+        assert!(!covers(&corner, 4., 9.) && !covers(&corner, 3., 7.));
+
+        // The strokes of ╬ stop at the crossing strokes, leaving the middle open
+        let cross = rects('╬');
+        assert!(covers(&cross, 3., 8.) && covers(&cross, 5., 10.));
+        assert!(!covers(&cross, 4., 8.) && !covers(&cross, 4., 9.));
+    }
+
+    #[test]
+    fn test_dashed_lines_repeat_per_cell() {
+        let dashes = rects('┄');
+        assert_eq!(dashes.len(), 3);
+        assert!(dashes.iter().all(|&(x0, _, x1, _)| x0 >= 0. && x1 <= 9.));
+        assert_eq!(rects('╎').len(), 2);
+    }
+
+    #[test]
+    fn test_blocks_tile_the_cell() {
+        assert_eq!(rects('█'), vec![(0., 0., 9., 19.)]);
+
+        // ▀ and ▄ split the cell at the same row
+        let (upper, lower) = (rects('▀')[0], rects('▄')[0]);
+        assert_eq!(upper.3, lower.1);
+        assert_eq!((upper.1, lower.3), (0., 19.));
+
+        // ▌ and ▐ split it at the same column
+        assert_eq!(rects('▌')[0].2, rects('▐')[0].0);
+
+        // ▚ fills the upper left and lower right quadrant
+        let quadrants = rects('▚');
+        assert!(covers(&quadrants, 0., 0.) && covers(&quadrants, 8., 18.));
+        assert!(!covers(&quadrants, 8., 0.) && !covers(&quadrants, 0., 18.));
+
+        let Some(shade) = builtin_glyph('▒', CELL) else {
+            panic!("▒ is drawn as geometry");
+        };
+        assert!(matches!(shade[0], GlyphShape::Rect { alpha, .. } if alpha == 0.5));
+    }
+
+    #[test]
+    fn test_braille_dots() {
+        assert!(rects('\u{2800}').is_empty());
+        assert_eq!(rects('\u{28FF}').len(), 8);
+
+        // ⡀ is dot 7, the bottom of the left column
+        let dot = rects('\u{2840}');
+        assert_eq!(dot.len(), 1);
+        assert!(dot[0].0 < 4.5 && dot[0].1 > 14.);
+    }
+
+    #[test]
+    fn test_powerline_triangles() {
+        let Some(shapes) = builtin_glyph('\u{E0B0}', CELL) else {
+            panic!("Powerline arrows are drawn as geometry");
+        };
+        assert_eq!(
+            shapes,
+            vec![GlyphShape::Polygon(vec![(0., 0.), (9., 9.5), (0., 19.)])]
+        );
+    }
+
+    #[test]
+    fn test_powerline_extras_mirror() {
+        // The left flame is the right one flipped
+        let right = builtin_glyph('\u{E0C0}', CELL).unwrap();
+        let left = builtin_glyph('\u{E0C2}', CELL).unwrap();
+        assert_eq!(left, vec![mirror(right[0].clone(), CELL.width)]);
+
+        // Mirrored pixels keep their squares inside the cell
+        let pixels = rects('\u{E0C5}');
+        assert!(covers(&pixels, 8.5, 0.) && !covers(&pixels, 0., 0.));
+    }
+
+    #[test]
+    fn test_other_characters_use_the_font() {
+        assert!(builtin_glyph('A', CELL).is_none());
+        assert!(builtin_glyph('■', CELL).is_none());
+        // Unassigned code points between the Powerline extras
+        assert!(builtin_glyph('\u{E0C9}', CELL).is_none());
+        assert!(builtin_glyph('\u{E0D8}', CELL).is_none());
+    }
+
+    #[test]
+    fn test_all_shapes_stay_in_the_cell() {
+        let chars = (0x2500..=0x259F)
+            .chain(0x2800..=0x28FF)
+            .chain(0xE0B0..=0xE0D7)
+            .filter(|code| !matches!(code, 0xE0C9 | 0xE0CB | 0xE0D3 | 0xE0D5))
+            .filter_map(char::from_u32);
+
+        // Line ends may stick out by half their width
+        let margin = CELL.thickness;
+        let inside = |x: f32, y: f32| {
+            (-margin..=CELL.width + margin).contains(&x)
+                && (-margin..=CELL.height + margin).contains(&y)
+        };
+
+        for ch in chars {
+            let shapes = builtin_glyph(ch, CELL).unwrap();
+            assert!(
+                !shapes.is_empty() || ch == '\u{2800}',
+                "{:?} draws nothing",
+                ch
+            );
+            for shape in shapes {
+                let points = match shape {
+                    GlyphShape::Rect {
+                        x,
+                        y,
+                        width,
+                        height,
+                        ..
+                    } => {
+                        assert!(width > 0. && height > 0., "{:?} has an empty rectangle", ch);
+                        vec![(x, y), (x + width, y + height)]
+                    }
+                    GlyphShape::Polygon(points) => points,
+                };
+                for (x, y) in points {
+                    assert!(inside(x, y), "{:?} leaves the cell at ({}, {})", ch, x, y);
+                }
+            }
+        }
+    }
+}
@@ -6,7 +6,7 @@
 //!
 //! Decorative characters typically need to:
 //! - Bypass contrast adjustment (use exact colors specified)
-//! - Be rendered with special fonts or fallback chains
+//! - Be drawn as geometry instead of font glyphs (see `builtin_glyphs`)
 //! - Connect properly with adjacent decorative characters (box drawing)
 
 /// Checks if a character is a decorative/special character that needs
@@ -4,13 +4,19 @@
 //! - Polls the PTY for output in a background thread
 //! - Batches events to reduce UI updates
 //! - Handles commands from the UI thread
+//!
+//! The thread sleeps in `poll` until the PTY has output or a command
+//! arrives through the waker, and signals the UI through a notification
+//! channel whenever a batch of events is ready.
 
+use std::os::unix::io::RawFd;
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::{Receiver, Sender, TryRecvError, unbounded};
 use mio::unix::SourceFd;
-use mio::{Events, Interest, Poll, Token};
+use mio::{Events, Interest, Poll, Token, Waker};
 use nix::errno::Errno;
 use nix::unistd::read;
 
@@ -21,18 +27,33 @@ use crate::terminal::pty::PtyProcess;
 /// Token for PTY read events in mio
 const PTY_TOKEN: Token = Token(0);
 
+/// Token for the waker that interrupts the poll when a command is sent
+const WAKER_TOKEN: Token = Token(1);
+
 /// Maximum duration to batch events before sending to UI (4ms)
 const BATCH_DURATION: Duration = Duration::from_millis(4);
 
 /// Maximum number of events to batch before sending to UI
 const MAX_BATCH_SIZE: usize = 100;
 
+/// How long to sleep without output before checking whether the process
+/// exited anyway, e.g. when a background job still holds the PTY open
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often to check for the exit after the PTY hung up, until the
+/// process has been reaped
+const HANGUP_EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(5);
+
 /// Result of starting the event loop
 pub struct EventLoopHandle {
     /// Handle for sending commands to the PTY
     pub terminal_handle: TerminalHandle,
     /// Receiver for events from the PTY
     pub event_rx: Receiver<TerminalEvent>,
+    /// Receives a notification whenever new events are waiting in `event_rx`.
+    /// Notifications that arrive while one is still pending are merged, so a
+    /// burst of output wakes the UI once.
+    pub notify_rx: async_channel::Receiver<()>,
     /// Join handle for the background thread
     thread_handle: Option<JoinHandle<()>>,
 }
@@ -64,39 +85,52 @@ impl Drop for EventLoopHandle {
 /// * `pty` - The PTY process to manage
 ///
 /// # Returns
-/// An `EventLoopHandle` containing the terminal handle and event receiver.
-pub fn start_event_loop(pty: PtyProcess) -> EventLoopHandle {
+/// An `EventLoopHandle` containing the terminal handle and event receiver,
+/// or an error if the poll can't be set up.
+pub fn start_event_loop(pty: PtyProcess) -> std::io::Result<EventLoopHandle> {
     let (command_tx, command_rx) = unbounded();
     let (event_tx, event_rx) = unbounded();
+    let (notify_tx, notify_rx) = async_channel::bounded(1);
 
-    let terminal_handle = TerminalHandle::new(command_tx);
+    let poll = Poll::new()?;
+    let waker = Arc::new(Waker::new(poll.registry(), WAKER_TOKEN)?);
+    let terminal_handle = TerminalHandle::new(command_tx).with_waker(waker);
 
+    let events = EventSender { event_tx, notify_tx };
     let thread_handle = thread::spawn(move || {
-        run_event_loop(pty, command_rx, event_tx);
+        run_event_loop(pty, poll, command_rx, events);
     });
 
-    EventLoopHandle {
+    Ok(EventLoopHandle {
         terminal_handle,
         event_rx,
+        notify_rx,
         thread_handle: Some(thread_handle),
+    })
+}
+
+/// Sends events to the UI thread and lets it know they are waiting
+struct EventSender {
+    event_tx: Sender<TerminalEvent>,
+    notify_tx: async_channel::Sender<()>,
+}
+
+impl EventSender {
+    fn send(&self, event: TerminalEvent) {
+        let _ = self.event_tx.send(event);
+        // A full channel means the UI hasn't picked up the last notification
+        // yet and will see this event along with the others
+        let _ = self.notify_tx.try_send(());
     }
 }
 
 /// The main event loop running in the background thread.
 fn run_event_loop(
     mut pty: PtyProcess,
+    mut poll: Poll,
     command_rx: Receiver<TerminalCommand>,
-    event_tx: Sender<TerminalEvent>,
+    events_out: EventSender,
 ) {
-    // Set up mio polling
-    let mut poll = match Poll::new() {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("Failed to create poll: {}", e);
-            return;
-        }
-    };
-
     let fd = pty.master_fd();
     let mut source_fd = SourceFd(&fd);
 
@@ -109,57 +143,64 @@ fn run_event_loop(
     let mut output_buffer: Vec<u8> = Vec::with_capacity(4096);
     let mut batch_start: Option<Instant> = None;
     let mut batch_count = 0;
-
-    loop {
-        // Check for commands from UI thread (non-blocking)
-        match command_rx.try_recv() {
-            Ok(TerminalCommand::Input(data)) => {
-                if let Err(e) = pty.write(&data) {
-                    eprintln!("Failed to write to PTY: {}", e);
+    // Set once reading fails because the last process holding the PTY
+    // closed it, which is when the shell is about to exit
+    let mut hung_up = false;
+
+    'event_loop: loop {
+        // Handle all commands the UI thread sent since the last wakeup
+        loop {
+            match command_rx.try_recv() {
+                Ok(TerminalCommand::Input(data)) => {
+                    if let Err(e) = pty.write(&data) {
+                        eprintln!("Failed to write to PTY: {}", e);
+                    }
                 }
-            }
-            Ok(TerminalCommand::Resize { rows, cols }) => {
-                if let Err(e) = pty.resize(rows, cols) {
-                    eprintln!("Failed to resize PTY: {}", e);
-                } else {
-                    let _ = event_tx.send(TerminalEvent::Resized { rows, cols });
+                Ok(TerminalCommand::Resize { rows, cols }) => {
+                    if let Err(e) = pty.resize(rows, cols) {
+                        eprintln!("Failed to resize PTY: {}", e);
+                    } else {
+                        events_out.send(TerminalEvent::Resized { rows, cols });
+                    }
                 }
-            }
-            Ok(TerminalCommand::Shutdown) => {
-                // Flush any remaining output
-                if !output_buffer.is_empty() {
-                    let _ = event_tx.send(TerminalEvent::Output(output_buffer.clone()));
+                Ok(TerminalCommand::Shutdown) => {
+                    // Flush any remaining output
+                    if !output_buffer.is_empty() {
+                        events_out.send(TerminalEvent::Output(output_buffer.clone()));
+                    }
+                    break 'event_loop;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    // UI thread has disconnected, shut down
+                    break 'event_loop;
                 }
-                break;
-            }
-            Err(TryRecvError::Empty) => {}
-            Err(TryRecvError::Disconnected) => {
-                // UI thread has disconnected, shut down
-                break;
             }
         }
 
         // Check if process is still alive
-        if !pty.is_alive() {
-            // Flush any remaining output
+        if let Some(status) = pty.exit_status() {
+            // Flush any remaining output, including the last words of the
+            // process that are still waiting in the PTY
+            read_available(fd, &mut output_buffer);
             if !output_buffer.is_empty() {
-                let _ = event_tx.send(TerminalEvent::Output(output_buffer.clone()));
+                events_out.send(TerminalEvent::Output(output_buffer.clone()));
             }
-            // Send process exited event (exit code 0 as default, could be improved)
-            let _ = event_tx.send(TerminalEvent::ProcessExited(0));
+            events_out.send(TerminalEvent::ProcessExited(status));
             break;
         }
 
-        // Poll for PTY events with a short timeout
-        let poll_timeout = if batch_start.is_some() {
-            // If we're batching, use remaining batch time
-            Some(Duration::from_millis(1))
+        // Sleep until the PTY or the UI thread has something for us, or the
+        // batch is due
+        let poll_timeout = if let Some(start) = batch_start {
+            BATCH_DURATION.saturating_sub(start.elapsed())
+        } else if hung_up {
+            HANGUP_EXIT_CHECK_INTERVAL
         } else {
-            // Otherwise, poll with a longer timeout
-            Some(Duration::from_millis(10))
+            EXIT_CHECK_INTERVAL
         };
 
-        if let Err(e) = poll.poll(&mut events, poll_timeout) {
+        if let Err(e) = poll.poll(&mut events, Some(poll_timeout)) {
             if e.kind() != std::io::ErrorKind::Interrupted {
                 eprintln!("Poll error: {}", e);
                 break;
@@ -187,6 +228,7 @@ fn run_event_loop(
                         Err(Errno::EAGAIN) | Err(Errno::EWOULDBLOCK) => break,
                         Err(Errno::EIO) => {
                             // PTY closed
+                            hung_up = true;
                             break;
                         }
                         Err(e) => {
@@ -206,7 +248,7 @@ fn run_event_loop(
         };
 
         if should_flush && !output_buffer.is_empty() {
-            let _ = event_tx.send(TerminalEvent::Output(std::mem::take(&mut output_buffer)));
+            events_out.send(TerminalEvent::Output(std::mem::take(&mut output_buffer)));
             output_buffer = Vec::with_capacity(4096);
             batch_start = None;
             batch_count = 0;
@@ -217,6 +259,17 @@ fn run_event_loop(
     let _ = poll.registry().deregister(&mut source_fd);
 }
 
+/// Reads whatever output the PTY has buffered without waiting for more.
+fn read_available(fd: RawFd, output: &mut Vec<u8>) {
+    let mut buf = [0u8; 4096];
+    while let Ok(n) = read(fd, &mut buf) {
+        if n == 0 {
+            break;
+        }
+        output.extend_from_slice(&buf[..n]);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,7 +282,7 @@ mod tests {
         let pty = PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string()))
             .expect("Failed to spawn PTY");
 
-        let handle = start_event_loop(pty);
+        let handle = start_event_loop(pty).expect("Failed to start event loop");
 
         // Send shutdown command
         handle.terminal_handle.shutdown().expect("Failed to send shutdown");
@@ -244,7 +297,7 @@ mod tests {
         let pty = PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string()))
             .expect("Failed to spawn PTY");
 
-        let handle = start_event_loop(pty);
+        let handle = start_event_loop(pty).expect("Failed to start event loop");
 
         // Send a simple command
         handle
@@ -278,13 +331,74 @@ mod tests {
         handle.terminal_handle.shutdown().expect("Failed to shutdown");
     }
 
+    #[test]
+    fn test_event_loop_notifies_about_output_without_input() {
+        let temp_dir = std::env::temp_dir();
+        let pty = PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string()))
+            .expect("Failed to spawn PTY");
+
+        let handle = start_event_loop(pty).expect("Failed to start event loop");
+        handle
+            .terminal_handle
+            .send_input(b"sleep 0.3; printf 'delayed_%s\\n' output\n".to_vec())
+            .expect("Failed to send input");
+
+        // Output that arrives long after the input still announces itself
+        let mut output = String::new();
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) && !output.contains("delayed_output") {
+            if handle.notify_rx.try_recv().is_err() {
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            while let Ok(event) = handle.event_rx.try_recv() {
+                if let TerminalEvent::Output(data) = event {
+                    output.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+        }
+
+        assert!(
+            output.contains("delayed_output"),
+            "Should have been notified about the delayed output, got {:?}",
+            output
+        );
+        handle.terminal_handle.shutdown().expect("Failed to shutdown");
+    }
+
+    #[test]
+    fn test_event_loop_reports_exit_status() {
+        let temp_dir = std::env::temp_dir();
+        let pty = PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string()))
+            .expect("Failed to spawn PTY");
+
+        let handle = start_event_loop(pty).expect("Failed to start event loop");
+        handle
+            .terminal_handle
+            .send_input(b"kill -9 $$\n".to_vec())
+            .expect("Failed to send input");
+
+        let mut exit_status = None;
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            let event = handle.event_rx.recv_timeout(Duration::from_millis(50));
+            if let Ok(TerminalEvent::ProcessExited(status)) = event {
+                exit_status = Some(status);
+                break;
+            }
+        }
+
+        let status = exit_status.expect("Should have received the exit status");
+        assert_eq!(status.to_string(), "exited with code 137 (SIGKILL)");
+    }
+
     #[test]
     fn test_event_loop_resize() {
         let temp_dir = std::env::temp_dir();
         let pty = PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string()))
             .expect("Failed to spawn PTY");
 
-        let handle = start_event_loop(pty);
+        let handle = start_event_loop(pty).expect("Failed to start event loop");
 
         // Send resize command
         handle
@@ -323,7 +437,7 @@ mod tests {
         let pty = PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string()))
             .expect("Failed to spawn PTY");
 
-        let handle = start_event_loop(pty);
+        let handle = start_event_loop(pty).expect("Failed to start event loop");
 
         // Send many commands quickly - should not block
         let start = Instant::now();
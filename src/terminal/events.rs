@@ -3,13 +3,57 @@
 //! This module defines the event and command types used for asynchronous
 //! communication between the PTY background thread and the UI.
 
+use std::fmt;
+
+use nix::sys::signal::Signal;
+
+/// How the process in a terminal ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The process exited with the given code.
+    Exited(i32),
+    /// The process was terminated by a signal.
+    Signaled(Signal),
+    /// The process is gone but its status could not be collected.
+    Unknown,
+}
+
+impl ExitStatus {
+    /// Exit code as a shell would report it, 128 plus the signal number for
+    /// processes killed by a signal.
+    pub fn code(self) -> Option<i32> {
+        match self {
+            ExitStatus::Exited(code) => Some(code),
+            ExitStatus::Signaled(signal) => Some(128 + signal as i32),
+            ExitStatus::Unknown => None,
+        }
+    }
+
+    /// Whether the process finished cleanly.
+    pub fn success(self) -> bool {
+        self == ExitStatus::Exited(0)
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, self.code()) {
+            (ExitStatus::Signaled(signal), Some(code)) => {
+                write!(f, "exited with code {} ({})", code, signal.as_str())
+            }
+            (_, Some(code)) => write!(f, "exited with code {}", code),
+            (_, None) => write!(f, "exited"),
+        }
+    }
+}
+
 /// Events sent from the PTY background thread to the UI.
 #[derive(Debug, Clone)]
 pub enum TerminalEvent {
     /// Raw bytes received from the PTY output.
     Output(Vec<u8>),
-    /// The PTY process has exited with the given exit code.
-    ProcessExited(i32),
+    /// The PTY process has exited, with how it ended.
+    ProcessExited(ExitStatus),
     /// Confirmation that the PTY was resized to the given dimensions.
     Resized { rows: u16, cols: u16 },
 }
@@ -40,13 +84,25 @@ mod tests {
 
     #[test]
     fn test_terminal_event_process_exited() {
-        let event = TerminalEvent::ProcessExited(0);
+        let event = TerminalEvent::ProcessExited(ExitStatus::Exited(0));
         match event {
-            TerminalEvent::ProcessExited(code) => assert_eq!(code, 0),
+            TerminalEvent::ProcessExited(status) => assert!(status.success()),
             _ => panic!("Expected ProcessExited event"),
         }
     }
 
+    #[test]
+    fn test_exit_status_display() {
+        assert_eq!(ExitStatus::Exited(0).to_string(), "exited with code 0");
+        assert_eq!(ExitStatus::Exited(2).code(), Some(2));
+        assert_eq!(
+            ExitStatus::Signaled(Signal::SIGKILL).to_string(),
+            "exited with code 137 (SIGKILL)"
+        );
+        assert!(!ExitStatus::Signaled(Signal::SIGTERM).success());
+        assert_eq!(ExitStatus::Unknown.to_string(), "exited");
+    }
+
     #[test]
     fn test_terminal_event_resized() {
         let event = TerminalEvent::Resized { rows: 24, cols: 80 };
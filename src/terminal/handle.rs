@@ -3,7 +3,10 @@
 //! This module provides a thread-safe handle for communicating with
 //! the PTY event loop running in a background thread.
 
+use std::sync::Arc;
+
 use crossbeam_channel::Sender;
+use mio::Waker;
 
 use crate::terminal::events::TerminalCommand;
 
@@ -14,12 +17,32 @@ use crate::terminal::events::TerminalCommand;
 #[derive(Clone)]
 pub struct TerminalHandle {
     command_tx: Sender<TerminalCommand>,
+    /// Wakes the background thread from its poll so commands are handled
+    /// right away
+    waker: Option<Arc<Waker>>,
 }
 
 impl TerminalHandle {
     /// Creates a new terminal handle with the given command sender.
     pub fn new(command_tx: Sender<TerminalCommand>) -> Self {
-        TerminalHandle { command_tx }
+        TerminalHandle {
+            command_tx,
+            waker: None,
+        }
+    }
+
+    /// Wakes the background thread with the given waker after each command.
+    pub fn with_waker(mut self, waker: Arc<Waker>) -> Self {
+        self.waker = Some(waker);
+        self
+    }
+
+    fn send(&self, command: TerminalCommand) -> Result<(), crossbeam_channel::SendError<TerminalCommand>> {
+        self.command_tx.send(command)?;
+        if let Some(Err(e)) = self.waker.as_ref().map(|waker| waker.wake()) {
+            eprintln!("Failed to wake PTY event loop: {}", e);
+        }
+        Ok(())
     }
 
     /// Sends user input to the PTY.
@@ -27,7 +50,7 @@ impl TerminalHandle {
     /// This method is non-blocking and will return immediately.
     /// The input will be queued and processed by the background thread.
     pub fn send_input(&self, data: Vec<u8>) -> Result<(), crossbeam_channel::SendError<TerminalCommand>> {
-        self.command_tx.send(TerminalCommand::Input(data))
+        self.send(TerminalCommand::Input(data))
     }
 
     /// Requests a resize of the PTY.
@@ -35,7 +58,7 @@ impl TerminalHandle {
     /// This method is non-blocking and will return immediately.
     /// The resize will be processed by the background thread.
     pub fn resize(&self, rows: u16, cols: u16) -> Result<(), crossbeam_channel::SendError<TerminalCommand>> {
-        self.command_tx.send(TerminalCommand::Resize { rows, cols })
+        self.send(TerminalCommand::Resize { rows, cols })
     }
 
     /// Requests a clean shutdown of the PTY and background thread.
@@ -43,7 +66,7 @@ impl TerminalHandle {
     /// This method is non-blocking and will return immediately.
     /// The shutdown will be processed by the background thread.
     pub fn shutdown(&self) -> Result<(), crossbeam_channel::SendError<TerminalCommand>> {
-        self.command_tx.send(TerminalCommand::Shutdown)
+        self.send(TerminalCommand::Shutdown)
     }
 }
 
@@ -0,0 +1,394 @@
+//! Encoding of key presses into the bytes terminal applications expect.
+//!
+//! Keys are encoded the way xterm does, following the mode flags of the
+//! `Term`: application cursor mode (DECCKM) switches the arrow keys to SS3
+//! sequences, and modifiers are sent as the `1 + bits` parameter of CSI
+//! sequences. Applications that push kitty keyboard protocol flags with
+//! `CSI > flags u` get the unambiguous `CSI code ; modifiers u` encoding
+//! instead. Key releases aren't reported.
+
+use alacritty_terminal::term::TermMode;
+
+/// Modifier keys held during a key press
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub control: bool,
+    /// The Super/Command key, only ever sent with the kitty protocol
+    pub super_key: bool,
+}
+
+impl KeyModifiers {
+    /// Modifier parameter shared by xterm and kitty: 1 plus a bit per key
+    fn param(self) -> u8 {
+        1 + self.shift as u8
+            + 2 * self.alt as u8
+            + 4 * self.control as u8
+            + 8 * self.super_key as u8
+    }
+
+    fn is_empty(self) -> bool {
+        self.param() == 1
+    }
+}
+
+/// A key press, with keys named the way GPUI names them ("up", "f5", "a")
+#[derive(Debug, Clone, Copy)]
+pub struct KeyPress<'a> {
+    pub key: &'a str,
+    /// Text the key produces with the current modifiers, if any
+    pub text: Option<&'a str>,
+    pub modifiers: KeyModifiers,
+    /// Whether this is an auto-repeat of a held key
+    pub repeat: bool,
+}
+
+/// Keys that are encoded as escape sequences rather than text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Functional {
+    /// Cursor keys: `CSI x`, or `SS3 x` in application cursor mode
+    Cursor(char),
+    /// F1 to F4: `SS3 x`
+    Ss3(char),
+    /// Editing and function keys: `CSI n ~`
+    Tilde(u8),
+}
+
+fn functional_key(key: &str) -> Option<Functional> {
+    use Functional::*;
+    Some(match key {
+        "up" => Cursor('A'),
+        "down" => Cursor('B'),
+        "right" => Cursor('C'),
+        "left" => Cursor('D'),
+        "home" => Cursor('H'),
+        "end" => Cursor('F'),
+        "insert" => Tilde(2),
+        "delete" => Tilde(3),
+        "pageup" => Tilde(5),
+        "pagedown" => Tilde(6),
+        "f1" => Ss3('P'),
+        "f2" => Ss3('Q'),
+        "f3" => Ss3('R'),
+        "f4" => Ss3('S'),
+        "f5" => Tilde(15),
+        "f6" => Tilde(17),
+        "f7" => Tilde(18),
+        "f8" => Tilde(19),
+        "f9" => Tilde(20),
+        "f10" => Tilde(21),
+        "f11" => Tilde(23),
+        "f12" => Tilde(24),
+        _ => return None,
+    })
+}
+
+/// Unicode key code of keys that have one, as used by the kitty protocol
+fn key_code(key: &str) -> Option<u32> {
+    match key {
+        "enter" => Some(13),
+        "tab" => Some(9),
+        "backspace" => Some(127),
+        "escape" => Some(27),
+        "space" => Some(' ' as u32),
+        _ => {
+            let mut chars = key.chars();
+            let c = chars.next()?;
+            // Named keys we don't know, such as "capslock", have no code
+            if chars.next().is_some() {
+                return None;
+            }
+            Some(c.to_lowercase().next().unwrap_or(c) as u32)
+        }
+    }
+}
+
+/// Control character sent for Ctrl with a key, following xterm
+fn control_byte(key: &str) -> Option<u8> {
+    let mut chars = key.chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return (key == "space").then_some(0);
+    }
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 1),
+        '@' | ' ' | '2' => Some(0x00),
+        '[' | '3' => Some(0x1b),
+        '\\' | '4' => Some(0x1c),
+        ']' | '5' => Some(0x1d),
+        '^' | '6' => Some(0x1e),
+        '_' | '-' | '/' | '7' => Some(0x1f),
+        '?' | '8' => Some(0x7f),
+        _ => None,
+    }
+}
+
+/// Encodes a key press for the terminal, or None if it sends nothing
+pub fn encode_key(press: &KeyPress, mode: TermMode) -> Option<Vec<u8>> {
+    let kitty = mode
+        .intersects(TermMode::KITTY_KEYBOARD_PROTOCOL)
+        .then(|| encode_kitty(press, mode))
+        .flatten();
+    kitty.or_else(|| encode_legacy(press, mode))
+}
+
+/// Encodes a key press the way xterm does
+fn encode_legacy(press: &KeyPress, mode: TermMode) -> Option<Vec<u8>> {
+    let mods = press.modifiers;
+    // Super combinations are shortcuts of the application, not the shell
+    if mods.super_key {
+        return None;
+    }
+
+    if let Some(functional) = functional_key(press.key) {
+        return Some(encode_functional(functional, mods, "", mode));
+    }
+
+    let mut bytes = Vec::new();
+    // Alt acts as Meta, prefixing the key with ESC
+    if mods.alt {
+        bytes.push(0x1b);
+    }
+
+    match press.key {
+        "enter" => bytes.push(b'\r'),
+        "escape" => bytes.push(0x1b),
+        "tab" if mods.shift => return Some(b"\x1b[Z".to_vec()),
+        "tab" => bytes.push(b'\t'),
+        "backspace" if mods.control => bytes.push(0x08),
+        "backspace" => bytes.push(0x7f),
+        key if mods.control => match control_byte(key) {
+            Some(byte) => bytes.push(byte),
+            None => bytes.extend_from_slice(press.text?.as_bytes()),
+        },
+        key => {
+            let text = match press.text {
+                Some(text) => text,
+                None if key.chars().count() == 1 => key,
+                None => return None,
+            };
+            bytes.extend_from_slice(text.as_bytes());
+        }
+    }
+
+    Some(bytes)
+}
+
+/// Encodes cursor, editing and function keys
+///
+/// `event_type` is the kitty event type suffix of the modifiers, which is
+/// only ever non-empty with the kitty protocol.
+fn encode_functional(
+    functional: Functional,
+    mods: KeyModifiers,
+    event_type: &str,
+    mode: TermMode,
+) -> Vec<u8> {
+    let kitty = mode.intersects(TermMode::KITTY_KEYBOARD_PROTOCOL);
+    let params = if mods.is_empty() && event_type.is_empty() {
+        None
+    } else {
+        Some(format!("{}{}", mods.param(), event_type))
+    };
+
+    let sequence = match (functional, params) {
+        // CSI R would be taken for a cursor position report
+        (Functional::Ss3('R'), _) if kitty => {
+            return encode_functional(Functional::Tilde(13), mods, event_type, mode);
+        }
+        (Functional::Cursor(c), None) if mode.contains(TermMode::APP_CURSOR) => {
+            format!("\x1bO{}", c)
+        }
+        (Functional::Ss3(c), None) if !kitty => format!("\x1bO{}", c),
+        (Functional::Cursor(c) | Functional::Ss3(c), None) => format!("\x1b[{}", c),
+        (Functional::Cursor(c) | Functional::Ss3(c), Some(params)) => {
+            format!("\x1b[1;{}{}", params, c)
+        }
+        (Functional::Tilde(n), None) => format!("\x1b[{}~", n),
+        (Functional::Tilde(n), Some(params)) => format!("\x1b[{};{}~", n, params),
+    };
+    sequence.into_bytes()
+}
+
+/// Encodes a key press with the kitty keyboard protocol, or None where the
+/// active flags keep the legacy encoding
+fn encode_kitty(press: &KeyPress, mode: TermMode) -> Option<Vec<u8>> {
+    let mods = press.modifiers;
+    let report_all = mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC);
+    let event_type = if press.repeat && mode.contains(TermMode::REPORT_EVENT_TYPES) {
+        ":2"
+    } else {
+        ""
+    };
+
+    if let Some(functional) = functional_key(press.key) {
+        return Some(encode_functional(functional, mods, event_type, mode));
+    }
+
+    let code = key_code(press.key)?;
+    let is_text_key = !matches!(code, 9 | 13 | 27 | 127);
+
+    // Without "report all keys", text typed without Ctrl, Alt or Super, as
+    // well as plain Enter, Tab and Backspace, is still sent as is
+    if !report_all {
+        let plain = !mods.control && !mods.alt && !mods.super_key;
+        if code != 27 && plain && (is_text_key || !mods.shift) {
+            return None;
+        }
+    }
+
+    let mut key = code.to_string();
+    if mods.shift && mode.contains(TermMode::REPORT_ALTERNATE_KEYS) {
+        let shifted = press.text.and_then(|text| {
+            let mut chars = text.chars();
+            let c = chars.next()?;
+            (chars.next().is_none() && c as u32 != code).then_some(c as u32)
+        });
+        if let Some(shifted) = shifted {
+            key = format!("{}:{}", key, shifted);
+        }
+    }
+
+    let text: Vec<String> = match press.text {
+        Some(text)
+            if report_all
+                && is_text_key
+                && !mods.control
+                && mode.contains(TermMode::REPORT_ASSOCIATED_TEXT) =>
+        {
+            text.chars().map(|c| (c as u32).to_string()).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let mut sequence = format!("\x1b[{}", key);
+    if !mods.is_empty() || !event_type.is_empty() || !text.is_empty() {
+        sequence.push_str(&format!(";{}{}", mods.param(), event_type));
+    }
+    if !text.is_empty() {
+        sequence.push_str(&format!(";{}", text.join(":")));
+    }
+    sequence.push('u');
+
+    Some(sequence.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(key: &str, text: Option<&'static str>, modifiers: KeyModifiers) -> Vec<u8> {
+        press_in(key, text, modifiers, TermMode::default())
+    }
+
+    fn press_in(
+        key: &str,
+        text: Option<&'static str>,
+        modifiers: KeyModifiers,
+        mode: TermMode,
+    ) -> Vec<u8> {
+        let press = KeyPress {
+            key,
+            text,
+            modifiers,
+            repeat: false,
+        };
+        encode_key(&press, mode).unwrap_or_default()
+    }
+
+    const NONE: KeyModifiers = KeyModifiers {
+        shift: false,
+        alt: false,
+        control: false,
+        super_key: false,
+    };
+    const SHIFT: KeyModifiers = KeyModifiers {
+        shift: true,
+        ..NONE
+    };
+    const ALT: KeyModifiers = KeyModifiers { alt: true, ..NONE };
+    const CTRL: KeyModifiers = KeyModifiers {
+        control: true,
+        ..NONE
+    };
+
+    #[test]
+    fn test_text_and_control_keys() {
+        assert_eq!(press("a", Some("a"), NONE), b"a");
+        assert_eq!(press("a", Some("A"), SHIFT), b"A");
+        assert_eq!(press("c", None, CTRL), b"\x03");
+        assert_eq!(press("[", None, CTRL), b"\x1b");
+        assert_eq!(press("\\", None, CTRL), b"\x1c");
+        assert_eq!(press("space", Some(" "), CTRL), b"\x00");
+        assert_eq!(press("b", Some("b"), ALT), b"\x1bb");
+        assert_eq!(
+            press("x", None, KeyModifiers { alt: true, ..CTRL }),
+            b"\x1b\x18"
+        );
+        assert_eq!(press("enter", None, NONE), b"\r");
+        assert_eq!(press("tab", None, SHIFT), b"\x1b[Z");
+        assert_eq!(press("backspace", None, ALT), b"\x1b\x7f");
+        assert!(
+            press(
+                "c",
+                None,
+                KeyModifiers {
+                    super_key: true,
+                    ..NONE
+                }
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_cursor_keys_follow_decckm() {
+        assert_eq!(press("up", None, NONE), b"\x1b[A");
+        assert_eq!(press_in("up", None, NONE, TermMode::APP_CURSOR), b"\x1bOA");
+        // Modified cursor keys always use the CSI form
+        assert_eq!(
+            press_in("right", None, CTRL, TermMode::APP_CURSOR),
+            b"\x1b[1;5C"
+        );
+        assert_eq!(press("left", None, SHIFT), b"\x1b[1;2D");
+        assert_eq!(press("home", None, ALT), b"\x1b[1;3H");
+    }
+
+    #[test]
+    fn test_function_and_editing_keys() {
+        assert_eq!(press("f1", None, NONE), b"\x1bOP");
+        assert_eq!(press("f4", None, SHIFT), b"\x1b[1;2S");
+        assert_eq!(press("f5", None, NONE), b"\x1b[15~");
+        assert_eq!(press("f12", None, CTRL), b"\x1b[24;5~");
+        assert_eq!(press("delete", None, NONE), b"\x1b[3~");
+        assert_eq!(press("pageup", None, SHIFT), b"\x1b[5;2~");
+        assert!(press("capslock", None, NONE).is_empty());
+    }
+
+    #[test]
+    fn test_kitty_protocol() {
+        let disambiguate = TermMode::DISAMBIGUATE_ESC_CODES;
+        assert_eq!(press_in("a", Some("a"), NONE, disambiguate), b"a");
+        assert_eq!(press_in("escape", None, NONE, disambiguate), b"\x1b[27u");
+        assert_eq!(press_in("c", None, CTRL, disambiguate), b"\x1b[99;5u");
+        assert_eq!(press_in("i", Some("i"), ALT, disambiguate), b"\x1b[105;3u");
+        assert_eq!(press_in("enter", None, NONE, disambiguate), b"\r");
+        assert_eq!(press_in("enter", None, SHIFT, disambiguate), b"\x1b[13;2u");
+        assert_eq!(press_in("f1", None, NONE, disambiguate), b"\x1b[P");
+        assert_eq!(press_in("f3", None, NONE, disambiguate), b"\x1b[13~");
+        assert_eq!(press_in("up", None, CTRL, disambiguate), b"\x1b[1;5A");
+
+        let report_all = disambiguate
+            | TermMode::REPORT_ALL_KEYS_AS_ESC
+            | TermMode::REPORT_ALTERNATE_KEYS
+            | TermMode::REPORT_ASSOCIATED_TEXT;
+        assert_eq!(press_in("a", Some("a"), NONE, report_all), b"\x1b[97;1;97u");
+        assert_eq!(
+            press_in("a", Some("A"), SHIFT, report_all),
+            b"\x1b[97:65;2;65u"
+        );
+        assert_eq!(press_in("enter", None, NONE, report_all), b"\x1b[13u");
+    }
+}
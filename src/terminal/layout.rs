@@ -0,0 +1,599 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::terminal::session::SessionState;
+
+/// Identifies a pane within a worktree's terminal layout
+pub type PaneId = u64;
+
+/// How a split arranges its two panes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitDirection {
+    /// Panes stacked on top of each other, divided by a horizontal line
+    Horizontal,
+    /// Panes side by side, divided by a vertical line
+    Vertical,
+}
+
+/// Direction to move pane focus in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Area of a pane as fractions of its tab's area
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaneRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PaneRect {
+    const FULL: PaneRect = PaneRect {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+}
+
+/// A node of a tab's split tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaneNode {
+    /// A single terminal
+    Pane { id: PaneId },
+    /// Two nodes sharing the space evenly
+    Split {
+        direction: SplitDirection,
+        first: Box<PaneNode>,
+        second: Box<PaneNode>,
+    },
+}
+
+impl PaneNode {
+    /// Pane ids in reading order (left to right, top to bottom)
+    pub fn panes(&self) -> Vec<PaneId> {
+        let mut panes = Vec::new();
+        self.collect_panes(&mut panes);
+        panes
+    }
+
+    fn collect_panes(&self, panes: &mut Vec<PaneId>) {
+        match self {
+            PaneNode::Pane { id } => panes.push(*id),
+            PaneNode::Split { first, second, .. } => {
+                first.collect_panes(panes);
+                second.collect_panes(panes);
+            }
+        }
+    }
+
+    /// Whether the pane is part of this tree
+    pub fn contains(&self, pane: PaneId) -> bool {
+        match self {
+            PaneNode::Pane { id } => *id == pane,
+            PaneNode::Split { first, second, .. } => first.contains(pane) || second.contains(pane),
+        }
+    }
+
+    /// Replace `target` by a split of `target` and `new_pane`
+    fn split(&mut self, target: PaneId, direction: SplitDirection, new_pane: PaneId) -> bool {
+        match self {
+            PaneNode::Pane { id } if *id == target => {
+                *self = PaneNode::Split {
+                    direction,
+                    first: Box::new(PaneNode::Pane { id: target }),
+                    second: Box::new(PaneNode::Pane { id: new_pane }),
+                };
+                true
+            }
+            PaneNode::Pane { .. } => false,
+            PaneNode::Split { first, second, .. } => {
+                first.split(target, direction, new_pane)
+                    || second.split(target, direction, new_pane)
+            }
+        }
+    }
+
+    /// Remove `target` from the tree, its sibling taking over the space.
+    /// Returns None when the tree consisted of `target` alone.
+    fn remove(self, target: PaneId) -> Option<PaneNode> {
+        match self {
+            PaneNode::Pane { id } if id == target => None,
+            PaneNode::Pane { .. } => Some(self),
+            PaneNode::Split {
+                direction,
+                first,
+                second,
+            } => match (first.remove(target), second.remove(target)) {
+                (Some(first), Some(second)) => Some(PaneNode::Split {
+                    direction,
+                    first: Box::new(first),
+                    second: Box::new(second),
+                }),
+                (Some(remaining), None) | (None, Some(remaining)) => Some(remaining),
+                (None, None) => None,
+            },
+        }
+    }
+
+    /// Areas of all panes when the tree fills `rect`
+    pub fn rects(&self, rect: PaneRect) -> Vec<(PaneId, PaneRect)> {
+        let mut rects = Vec::new();
+        self.collect_rects(rect, &mut rects);
+        rects
+    }
+
+    fn collect_rects(&self, rect: PaneRect, rects: &mut Vec<(PaneId, PaneRect)>) {
+        match self {
+            PaneNode::Pane { id } => rects.push((*id, rect)),
+            PaneNode::Split {
+                direction,
+                first,
+                second,
+            } => {
+                let (first_rect, second_rect) = match direction {
+                    SplitDirection::Horizontal => {
+                        let height = rect.height / 2.0;
+                        (
+                            PaneRect { height, ..rect },
+                            PaneRect {
+                                y: rect.y + height,
+                                height,
+                                ..rect
+                            },
+                        )
+                    }
+                    SplitDirection::Vertical => {
+                        let width = rect.width / 2.0;
+                        (
+                            PaneRect { width, ..rect },
+                            PaneRect {
+                                x: rect.x + width,
+                                width,
+                                ..rect
+                            },
+                        )
+                    }
+                };
+                first.collect_rects(first_rect, rects);
+                second.collect_rects(second_rect, rects);
+            }
+        }
+    }
+}
+
+/// A named tab holding a tree of split panes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerminalTab {
+    pub name: String,
+    pub root: PaneNode,
+    /// Pane that receives keyboard input when the tab is active
+    pub focused: PaneId,
+}
+
+impl TerminalTab {
+    fn new(name: String, pane: PaneId) -> Self {
+        TerminalTab {
+            name,
+            root: PaneNode::Pane { id: pane },
+            focused: pane,
+        }
+    }
+}
+
+/// Tabs and splits of the terminals of one worktree
+///
+/// The layout only tracks pane ids; the views owning it spawn a terminal
+/// session for every pane and stop sessions whose pane went away. It is
+/// saved next to the worktree's [`SessionState`] so it comes back when the
+/// worktree is reopened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerminalLayout {
+    pub worktree_path: PathBuf,
+    tabs: Vec<TerminalTab>,
+    active_tab: usize,
+    next_pane_id: PaneId,
+}
+
+impl TerminalLayout {
+    /// A layout with a single tab holding a single pane
+    pub fn new(worktree_path: PathBuf) -> Self {
+        TerminalLayout {
+            worktree_path,
+            tabs: vec![TerminalTab::new("Terminal 1".to_string(), 0)],
+            active_tab: 0,
+            next_pane_id: 1,
+        }
+    }
+
+    /// All tabs in display order
+    pub fn tabs(&self) -> &[TerminalTab] {
+        &self.tabs
+    }
+
+    /// Index of the visible tab
+    pub fn active_tab_index(&self) -> usize {
+        self.active_tab
+    }
+
+    /// The visible tab
+    pub fn active_tab(&self) -> &TerminalTab {
+        &self.tabs[self.active_tab]
+    }
+
+    /// Pane that receives keyboard input
+    pub fn focused_pane(&self) -> PaneId {
+        self.active_tab().focused
+    }
+
+    /// Every pane of every tab
+    pub fn all_panes(&self) -> Vec<PaneId> {
+        self.tabs.iter().flat_map(|tab| tab.root.panes()).collect()
+    }
+
+    fn allocate_pane(&mut self) -> PaneId {
+        let id = self.next_pane_id;
+        self.next_pane_id += 1;
+        id
+    }
+
+    /// Lowest "Terminal N" name not used by another tab
+    fn default_tab_name(&self) -> String {
+        (1..)
+            .map(|n| format!("Terminal {}", n))
+            .find(|name| !self.tabs.iter().any(|tab| &tab.name == name))
+            .expect("unbounded range always yields a free name")
+    }
+
+    /// Open a new tab with a single pane and make it active
+    pub fn add_tab(&mut self, name: Option<String>) -> PaneId {
+        let name = name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| self.default_tab_name());
+        let pane = self.allocate_pane();
+        self.tabs.push(TerminalTab::new(name, pane));
+        self.active_tab = self.tabs.len() - 1;
+        pane
+    }
+
+    /// Rename a tab; blank names are rejected
+    pub fn rename_tab(&mut self, index: usize, name: &str) -> bool {
+        let name = name.trim();
+        match self.tabs.get_mut(index) {
+            Some(tab) if !name.is_empty() => {
+                tab.name = name.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Close a tab and all of its panes. Closing the last tab leaves a
+    /// fresh one behind so the worktree always has a terminal.
+    pub fn close_tab(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        self.tabs.remove(index);
+
+        if self.tabs.is_empty() {
+            let pane = self.allocate_pane();
+            let name = self.default_tab_name();
+            self.tabs.push(TerminalTab::new(name, pane));
+        }
+        if self.active_tab > index || self.active_tab >= self.tabs.len() {
+            self.active_tab = self.active_tab.saturating_sub(1);
+        }
+    }
+
+    /// Make a tab visible
+    pub fn select_tab(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.active_tab = index;
+        }
+    }
+
+    /// Cycle to the next tab
+    pub fn next_tab(&mut self) {
+        self.active_tab = (self.active_tab + 1) % self.tabs.len();
+    }
+
+    /// Cycle to the previous tab
+    pub fn previous_tab(&mut self) {
+        self.active_tab = (self.active_tab + self.tabs.len() - 1) % self.tabs.len();
+    }
+
+    /// Split the focused pane, focusing the new pane
+    pub fn split_focused(&mut self, direction: SplitDirection) -> PaneId {
+        let pane = self.allocate_pane();
+        let tab = &mut self.tabs[self.active_tab];
+        tab.root.split(tab.focused, direction, pane);
+        tab.focused = pane;
+        pane
+    }
+
+    /// Close a pane. A tab losing its last pane is closed as well.
+    pub fn close_pane(&mut self, pane: PaneId) {
+        let Some(index) = self.tabs.iter().position(|tab| tab.root.contains(pane)) else {
+            return;
+        };
+
+        let tab = &mut self.tabs[index];
+        let order = tab.root.panes();
+        let root = std::mem::replace(&mut tab.root, PaneNode::Pane { id: pane });
+        match root.remove(pane) {
+            Some(root) => {
+                if tab.focused == pane {
+                    // Focus the pane that preceded the closed one
+                    let position = order.iter().position(|id| *id == pane).unwrap_or(0);
+                    let remaining = root.panes();
+                    tab.focused = remaining[position.saturating_sub(1).min(remaining.len() - 1)];
+                }
+                tab.root = root;
+            }
+            None => self.close_tab(index),
+        }
+    }
+
+    /// Focus a pane, switching to its tab
+    pub fn focus_pane(&mut self, pane: PaneId) -> bool {
+        match self.tabs.iter().position(|tab| tab.root.contains(pane)) {
+            Some(index) => {
+                self.active_tab = index;
+                self.tabs[index].focused = pane;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Focus the next pane of the active tab in reading order
+    pub fn focus_next(&mut self) {
+        self.cycle_focus(1);
+    }
+
+    /// Focus the previous pane of the active tab in reading order
+    pub fn focus_previous(&mut self) {
+        self.cycle_focus(-1);
+    }
+
+    fn cycle_focus(&mut self, step: isize) {
+        let tab = &mut self.tabs[self.active_tab];
+        let panes = tab.root.panes();
+        let position = panes.iter().position(|id| *id == tab.focused).unwrap_or(0);
+        let next = (position as isize + step).rem_euclid(panes.len() as isize);
+        tab.focused = panes[next as usize];
+    }
+
+    /// Focus the nearest pane next to the focused one in the given
+    /// direction. Returns false when there is no pane that way.
+    pub fn focus_direction(&mut self, direction: FocusDirection) -> bool {
+        let tab = &mut self.tabs[self.active_tab];
+        let rects = tab.root.rects(PaneRect::FULL);
+        let Some(&(_, current)) = rects.iter().find(|(id, _)| *id == tab.focused) else {
+            return false;
+        };
+
+        const EPSILON: f32 = 1e-4;
+        let overlap = |a0: f32, a1: f32, b0: f32, b1: f32| a1.min(b1) - a0.max(b0);
+
+        // Candidates must share an edge with the focused pane; among them
+        // the one overlapping it the most wins
+        let target = rects
+            .iter()
+            .filter(|(id, _)| *id != tab.focused)
+            .filter_map(|(id, rect)| {
+                let (adjacent, shared) = match direction {
+                    FocusDirection::Left => (
+                        (rect.right() - current.x).abs() < EPSILON,
+                        overlap(rect.y, rect.bottom(), current.y, current.bottom()),
+                    ),
+                    FocusDirection::Right => (
+                        (rect.x - current.right()).abs() < EPSILON,
+                        overlap(rect.y, rect.bottom(), current.y, current.bottom()),
+                    ),
+                    FocusDirection::Up => (
+                        (rect.bottom() - current.y).abs() < EPSILON,
+                        overlap(rect.x, rect.right(), current.x, current.right()),
+                    ),
+                    FocusDirection::Down => (
+                        (rect.y - current.bottom()).abs() < EPSILON,
+                        overlap(rect.x, rect.right(), current.x, current.right()),
+                    ),
+                };
+                (adjacent && shared > EPSILON).then_some((*id, shared))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id);
+
+        match target {
+            Some(id) => {
+                tab.focused = id;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a deserialized layout is internally consistent
+    fn is_valid(&self) -> bool {
+        let panes = self.all_panes();
+        let mut unique = panes.clone();
+        unique.sort_unstable();
+        unique.dedup();
+
+        !self.tabs.is_empty()
+            && self.active_tab < self.tabs.len()
+            && unique.len() == panes.len()
+            && panes.iter().all(|id| *id < self.next_pane_id)
+            && self.tabs.iter().all(|tab| tab.root.contains(tab.focused))
+    }
+
+    /// Saves the layout next to the worktree's session state
+    pub fn save(&self) -> Result<()> {
+        let file_path = SessionState::layout_file(&self.worktree_path)?;
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create sessions directory")?;
+        }
+
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize terminal layout")?;
+
+        // Write to a temp file first so a crash never leaves half a layout
+        let temp_path = file_path.with_extension("json.tmp");
+        fs::write(&temp_path, json).context("Failed to write terminal layout")?;
+        fs::rename(&temp_path, &file_path).context("Failed to replace terminal layout")?;
+
+        Ok(())
+    }
+
+    /// Loads the saved layout of a worktree, if there is a usable one
+    pub fn load(worktree_path: &Path) -> Result<Option<Self>> {
+        let Ok(file_path) = SessionState::layout_file(worktree_path) else {
+            return Ok(None);
+        };
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&file_path).context("Failed to read terminal layout")?;
+        let layout: TerminalLayout =
+            serde_json::from_str(&contents).context("Failed to deserialize terminal layout")?;
+
+        if layout.worktree_path != worktree_path || !layout.is_valid() {
+            return Ok(None);
+        }
+
+        Ok(Some(layout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> TerminalLayout {
+        TerminalLayout::new(PathBuf::from("/tmp/worktree"))
+    }
+
+    #[test]
+    fn test_tabs_add_rename_close() {
+        let mut layout = layout();
+        let pane = layout.add_tab(None);
+        assert_eq!(layout.tabs().len(), 2);
+        assert_eq!(layout.active_tab_index(), 1);
+        assert_eq!(layout.active_tab().name, "Terminal 2");
+        assert_eq!(layout.focused_pane(), pane);
+
+        assert!(layout.rename_tab(1, "  dev server "));
+        assert_eq!(layout.tabs()[1].name, "dev server");
+        assert!(!layout.rename_tab(1, "   "));
+        assert!(!layout.rename_tab(5, "tests"));
+
+        layout.previous_tab();
+        assert_eq!(layout.active_tab_index(), 0);
+        layout.previous_tab();
+        assert_eq!(layout.active_tab_index(), 1);
+
+        layout.close_tab(1);
+        assert_eq!(layout.tabs().len(), 1);
+        assert_eq!(layout.active_tab_index(), 0);
+
+        // The last tab is replaced rather than leaving no terminal at all
+        layout.close_tab(0);
+        assert_eq!(layout.tabs().len(), 1);
+        assert_eq!(layout.all_panes(), vec![2]);
+    }
+
+    #[test]
+    fn test_split_and_close_panes() {
+        let mut layout = layout();
+        let right = layout.split_focused(SplitDirection::Vertical);
+        let bottom_right = layout.split_focused(SplitDirection::Horizontal);
+        assert_eq!(layout.all_panes(), vec![0, right, bottom_right]);
+        assert_eq!(layout.focused_pane(), bottom_right);
+
+        let rects = layout.active_tab().root.rects(PaneRect::FULL);
+        assert_eq!(
+            rects[0].1,
+            PaneRect {
+                width: 0.5,
+                ..PaneRect::FULL
+            }
+        );
+        assert_eq!(rects[2].1.y, 0.5);
+        assert_eq!(rects[2].1.x, 0.5);
+
+        // The sibling takes over the space and focus moves to the
+        // preceding pane
+        layout.close_pane(bottom_right);
+        assert_eq!(layout.all_panes(), vec![0, right]);
+        assert_eq!(layout.focused_pane(), right);
+
+        layout.close_pane(0);
+        assert_eq!(layout.active_tab().root, PaneNode::Pane { id: right });
+
+        // Closing the only pane of a tab closes the tab
+        layout.add_tab(None);
+        let pane = layout.focused_pane();
+        layout.close_pane(pane);
+        assert_eq!(layout.tabs().len(), 1);
+    }
+
+    #[test]
+    fn test_focus_movement() {
+        let mut layout = layout();
+        let right = layout.split_focused(SplitDirection::Vertical);
+        let bottom_right = layout.split_focused(SplitDirection::Horizontal);
+
+        assert!(layout.focus_direction(FocusDirection::Up));
+        assert_eq!(layout.focused_pane(), right);
+        assert!(layout.focus_direction(FocusDirection::Left));
+        assert_eq!(layout.focused_pane(), 0);
+        assert!(!layout.focus_direction(FocusDirection::Left));
+        assert!(!layout.focus_direction(FocusDirection::Up));
+        assert!(layout.focus_direction(FocusDirection::Right));
+
+        layout.focus_pane(bottom_right);
+        layout.focus_next();
+        assert_eq!(layout.focused_pane(), 0);
+        layout.focus_previous();
+        assert_eq!(layout.focused_pane(), bottom_right);
+    }
+
+    #[test]
+    fn test_layout_serialization_roundtrip() {
+        let mut layout = layout();
+        layout.split_focused(SplitDirection::Vertical);
+        layout.add_tab(Some("tests".to_string()));
+
+        let json = serde_json::to_string(&layout).unwrap();
+        let restored: TerminalLayout = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, layout);
+        assert!(restored.is_valid());
+
+        // Layouts referring to panes that don't exist are rejected
+        let mut broken = restored;
+        broken.tabs[0].focused = 42;
+        assert!(!broken.is_valid());
+    }
+}
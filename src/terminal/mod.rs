@@ -1,10 +1,16 @@
+pub mod builtin_glyphs;
 pub mod decorative;
 pub mod event_loop;
 pub mod events;
 pub mod handle;
+pub mod keys;
+pub mod layout;
+pub mod mouse;
+pub mod paste;
 pub mod pty;
 pub mod render;
+pub mod search;
 pub mod session;
 
 pub use pty::PtyProcess;
-pub use session::TerminalSession;
+pub use session::{SharedTerminalSession, TerminalSession};
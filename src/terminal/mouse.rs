@@ -0,0 +1,195 @@
+//! Encoding of mouse events for applications that enable mouse tracking.
+//!
+//! Applications such as htop, lazygit or vim turn on reporting with the
+//! xterm private modes: 1000 for clicks, 1002 for drags and 1003 for all
+//! motion. Reports use the SGR format (mode 1006) when enabled, the UTF-8
+//! extended coordinates (mode 1005), or the original X10 byte encoding.
+
+use alacritty_terminal::term::TermMode;
+
+use crate::terminal::keys::KeyModifiers;
+
+/// Button a mouse report is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    WheelUp,
+    WheelDown,
+    /// Motion without any button held
+    None,
+}
+
+impl MouseButton {
+    fn code(self) -> u8 {
+        match self {
+            MouseButton::Left => 0,
+            MouseButton::Middle => 1,
+            MouseButton::Right => 2,
+            MouseButton::None => 3,
+            MouseButton::WheelUp => 64,
+            MouseButton::WheelDown => 65,
+        }
+    }
+}
+
+/// What happened to the button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Press,
+    Release,
+    Motion,
+}
+
+/// A mouse event at a cell of the viewport
+#[derive(Debug, Clone, Copy)]
+pub struct MouseReport {
+    pub button: MouseButton,
+    pub action: MouseAction,
+    pub modifiers: KeyModifiers,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Whether the application asked for mouse events
+pub fn is_reporting(mode: TermMode) -> bool {
+    mode.intersects(TermMode::MOUSE_MODE)
+}
+
+/// Encodes a mouse event for the application, or None if the active modes
+/// don't ask for it or its position can't be represented
+pub fn encode_mouse(report: &MouseReport, mode: TermMode) -> Option<Vec<u8>> {
+    if !is_reporting(mode) {
+        return None;
+    }
+    if report.action == MouseAction::Motion {
+        let dragging = report.button != MouseButton::None;
+        let wanted = mode.contains(TermMode::MOUSE_MOTION)
+            || (dragging && mode.contains(TermMode::MOUSE_DRAG));
+        if !wanted {
+            return None;
+        }
+    }
+    // The wheel has no release
+    let is_wheel = matches!(report.button, MouseButton::WheelUp | MouseButton::WheelDown);
+    if is_wheel && report.action == MouseAction::Release {
+        return None;
+    }
+
+    let mods = report.modifiers;
+    let mut code = report.button.code();
+    if report.action == MouseAction::Motion {
+        code += 32;
+    }
+    code += 4 * mods.shift as u8 + 8 * mods.alt as u8 + 16 * mods.control as u8;
+
+    if mode.contains(TermMode::SGR_MOUSE) {
+        let terminator = if report.action == MouseAction::Release {
+            'm'
+        } else {
+            'M'
+        };
+        return Some(
+            format!(
+                "\x1b[<{};{};{}{}",
+                code,
+                report.col + 1,
+                report.row + 1,
+                terminator
+            )
+            .into_bytes(),
+        );
+    }
+
+    // The older encodings can't tell which button was released
+    if report.action == MouseAction::Release {
+        code = (code & !0b11) | 3;
+    }
+
+    let mut bytes = b"\x1b[M".to_vec();
+    bytes.push(32 + code);
+    if mode.contains(TermMode::UTF8_MOUSE) {
+        for position in [report.col, report.row] {
+            let value = u32::try_from(position + 33).ok().filter(|v| *v < 2048)?;
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(char::from_u32(value)?.encode_utf8(&mut buffer).as_bytes());
+        }
+    } else {
+        for position in [report.col, report.row] {
+            bytes.push(u8::try_from(position + 33).ok()?);
+        }
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(button: MouseButton, action: MouseAction, row: usize, col: usize) -> MouseReport {
+        MouseReport {
+            button,
+            action,
+            modifiers: KeyModifiers::default(),
+            row,
+            col,
+        }
+    }
+
+    #[test]
+    fn test_nothing_is_reported_without_tracking() {
+        let press = report(MouseButton::Left, MouseAction::Press, 0, 0);
+        assert_eq!(encode_mouse(&press, TermMode::default()), None);
+    }
+
+    #[test]
+    fn test_sgr_encoding() {
+        let mode = TermMode::MOUSE_REPORT_CLICK | TermMode::SGR_MOUSE;
+        let press = report(MouseButton::Left, MouseAction::Press, 4, 9);
+        assert_eq!(encode_mouse(&press, mode).unwrap(), b"\x1b[<0;10;5M");
+
+        let release = report(MouseButton::Right, MouseAction::Release, 4, 9);
+        assert_eq!(encode_mouse(&release, mode).unwrap(), b"\x1b[<2;10;5m");
+
+        let mut wheel = report(MouseButton::WheelDown, MouseAction::Press, 0, 0);
+        wheel.modifiers.control = true;
+        assert_eq!(encode_mouse(&wheel, mode).unwrap(), b"\x1b[<81;1;1M");
+    }
+
+    #[test]
+    fn test_motion_follows_tracking_mode() {
+        let drag = report(MouseButton::Left, MouseAction::Motion, 1, 1);
+        let hover = report(MouseButton::None, MouseAction::Motion, 1, 1);
+
+        let clicks = TermMode::MOUSE_REPORT_CLICK | TermMode::SGR_MOUSE;
+        assert_eq!(encode_mouse(&drag, clicks), None);
+
+        let drags = TermMode::MOUSE_DRAG | TermMode::SGR_MOUSE;
+        assert_eq!(encode_mouse(&drag, drags).unwrap(), b"\x1b[<32;2;2M");
+        assert_eq!(encode_mouse(&hover, drags), None);
+
+        let motion = TermMode::MOUSE_MOTION | TermMode::SGR_MOUSE;
+        assert_eq!(encode_mouse(&hover, motion).unwrap(), b"\x1b[<35;2;2M");
+    }
+
+    #[test]
+    fn test_x10_and_utf8_encoding() {
+        let press = report(MouseButton::Middle, MouseAction::Press, 2, 3);
+        let x10 = TermMode::MOUSE_REPORT_CLICK;
+        assert_eq!(encode_mouse(&press, x10).unwrap(), b"\x1b[M!$#");
+
+        let release = report(MouseButton::Middle, MouseAction::Release, 2, 3);
+        assert_eq!(encode_mouse(&release, x10).unwrap(), b"\x1b[M#$#");
+
+        // Columns past 222 don't fit in a byte
+        let far = report(MouseButton::Left, MouseAction::Press, 0, 300);
+        assert_eq!(encode_mouse(&far, x10), None);
+
+        let utf8 = x10 | TermMode::UTF8_MOUSE;
+        let mut expected = b"\x1b[M ".to_vec();
+        expected.extend_from_slice("\u{14d}!".as_bytes());
+        assert_eq!(encode_mouse(&far, utf8).unwrap(), expected);
+    }
+}
@@ -0,0 +1,59 @@
+//! Preparing clipboard text for the PTY.
+//!
+//! Applications that enable bracketed paste (mode 2004) get the text wrapped
+//! in `ESC[200~ … ESC[201~`, so a shell or agent prompt can take the whole
+//! text as one input instead of running each line as it arrives. Control
+//! characters are removed so pasted text can't end the bracket early or
+//! send escape sequences of its own.
+
+use alacritty_terminal::term::TermMode;
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Bytes to write to the PTY for pasted text
+pub fn paste_bytes(text: &str, mode: TermMode) -> Vec<u8> {
+    // Line breaks are sent as carriage returns, like the Enter key
+    let text = text.replace("\r\n", "\r").replace('\n', "\r");
+    let filtered: String = text
+        .chars()
+        .filter(|&c| !c.is_control() || c == '\t' || c == '\r')
+        .collect();
+
+    if mode.contains(TermMode::BRACKETED_PASTE) {
+        [PASTE_START, filtered.as_bytes(), PASTE_END].concat()
+    } else {
+        filtered.into_bytes()
+    }
+}
+
+/// Whether pasting the text would run lines one by one because the
+/// application didn't enable bracketed paste
+pub fn runs_lines(text: &str, mode: TermMode) -> bool {
+    !mode.contains(TermMode::BRACKETED_PASTE) && text.contains(['\n', '\r'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_paste_converts_line_breaks() {
+        let mode = TermMode::default();
+        assert_eq!(paste_bytes("ls\n", mode), b"ls\r");
+        assert_eq!(paste_bytes("a\r\nb\tc", mode), b"a\rb\tc");
+        assert!(runs_lines("a\nb", mode));
+        assert!(!runs_lines("just one line", mode));
+    }
+
+    #[test]
+    fn test_bracketed_paste_filters_control_characters() {
+        let mode = TermMode::BRACKETED_PASTE;
+        assert_eq!(paste_bytes("one\ntwo", mode), b"\x1b[200~one\rtwo\x1b[201~");
+
+        // An embedded end marker can't close the bracket early
+        let bytes = paste_bytes("x\x1b[201~rm -rf ~\x03\u{9b}", mode);
+        assert_eq!(bytes, b"\x1b[200~x[201~rm -rf ~\x1b[201~");
+        assert!(!runs_lines("a\nb", mode));
+    }
+}
@@ -5,15 +5,20 @@ use nix::pty::{Winsize, openpty};
 use nix::sys::signal::{Signal, kill};
 use nix::sys::wait::{WaitPidFlag, WaitStatus, waitpid};
 use nix::unistd::{ForkResult, Pid, close, dup2, execvp, fork, read, setsid, write};
+use std::cell::Cell;
 use std::ffi::CString;
 use std::os::unix::io::{AsRawFd, BorrowedFd, RawFd};
 use std::path::Path;
 
+use crate::terminal::events::ExitStatus;
+
 /// Represents a PTY (pseudo-terminal) process.
 /// Manages the master file descriptor and the shell process lifecycle.
 pub struct PtyProcess {
     master_fd: RawFd,
     child_pid: Pid,
+    /// Status of the child once reaped, as waitpid only reports it once
+    exit_status: Cell<Option<ExitStatus>>,
 }
 
 impl PtyProcess {
@@ -65,6 +70,7 @@ impl PtyProcess {
                 Ok(PtyProcess {
                     master_fd,
                     child_pid: child,
+                    exit_status: Cell::new(None),
                 })
             }
             ForkResult::Child => {
@@ -164,11 +170,23 @@ impl PtyProcess {
 
     /// Checks if the child process is still alive.
     pub fn is_alive(&self) -> bool {
-        match waitpid(self.child_pid, Some(WaitPidFlag::WNOHANG)) {
-            Ok(WaitStatus::StillAlive) => true,
-            Ok(_) => false,  // Process has exited
-            Err(_) => false, // Error checking status
+        self.exit_status().is_none()
+    }
+
+    /// Returns how the child process ended, or None while it is running.
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        if let Some(status) = self.exit_status.get() {
+            return Some(status);
         }
+
+        let status = match waitpid(self.child_pid, Some(WaitPidFlag::WNOHANG)) {
+            Ok(WaitStatus::Exited(_, code)) => ExitStatus::Exited(code),
+            Ok(WaitStatus::Signaled(_, signal, _)) => ExitStatus::Signaled(signal),
+            Ok(_) => return None, // Still running
+            Err(_) => ExitStatus::Unknown,
+        };
+        self.exit_status.set(Some(status));
+        Some(status)
     }
 
     /// Returns the raw file descriptor for the PTY master.
@@ -186,12 +204,10 @@ impl Drop for PtyProcess {
 
         // Wait for child process to terminate (with timeout)
         for _ in 0..10 {
-            match waitpid(self.child_pid, Some(WaitPidFlag::WNOHANG)) {
-                Ok(WaitStatus::StillAlive) => {
-                    std::thread::sleep(std::time::Duration::from_millis(10));
-                }
-                _ => break,
+            if !self.is_alive() {
+                break;
             }
+            std::thread::sleep(std::time::Duration::from_millis(10));
         }
 
         // Force kill if still alive
@@ -250,6 +266,28 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_pty_exit_status() {
+        let temp_dir = std::env::temp_dir();
+        let mut pty =
+            PtyProcess::spawn(&temp_dir, Some("/bin/sh".to_string())).expect("Failed to spawn PTY");
+        assert_eq!(pty.exit_status(), None);
+
+        pty.write(b"exit 3\n").expect("Failed to write to PTY");
+        let mut status = None;
+        for _ in 0..50 {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            status = pty.exit_status();
+            if status.is_some() {
+                break;
+            }
+        }
+        assert_eq!(status, Some(ExitStatus::Exited(3)));
+        // The status is kept after the child has been reaped
+        assert_eq!(pty.exit_status(), Some(ExitStatus::Exited(3)));
+        assert!(!pty.is_alive());
+    }
+
     #[test]
     fn test_pty_is_alive() {
         let temp_dir = std::env::temp_dir();
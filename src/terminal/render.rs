@@ -3,13 +3,24 @@
 //! This module provides batching and rendering primitives to reduce draw calls
 //! when rendering terminal content. Instead of rendering each cell individually,
 //! cells with identical styling are grouped into batched text runs.
+//!
+//! Runs are kept per row by `RenderCache`, which uses the terminal's damage
+//! tracking to rebuild only the rows that changed since the last frame.
+
+use std::sync::Arc;
 
 use alacritty_terminal::index::{Column, Line, Point};
-use alacritty_terminal::term::cell::Flags;
-use alacritty_terminal::term::Term;
+use alacritty_terminal::selection::SelectionRange;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::term::search::Match;
+use alacritty_terminal::term::{point_to_viewport, viewport_to_point, Term, TermDamage};
 use alacritty_terminal::vte::ansi::NamedColor;
 
+pub use alacritty_terminal::vte::ansi::CursorShape;
+
 use crate::terminal::decorative::is_decorative_character;
+use crate::terminal::search::TerminalSearch;
 use crate::terminal::session::EventProxy;
 
 /// RGBA color representation
@@ -36,6 +47,34 @@ impl Rgba {
     }
 }
 
+/// How text is underlined, chosen with `SGR 4` and its subparameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Underline {
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+impl Underline {
+    fn from_flags(flags: Flags) -> Option<Self> {
+        if flags.contains(Flags::DOUBLE_UNDERLINE) {
+            Some(Underline::Double)
+        } else if flags.contains(Flags::UNDERCURL) {
+            Some(Underline::Curly)
+        } else if flags.contains(Flags::DOTTED_UNDERLINE) {
+            Some(Underline::Dotted)
+        } else if flags.contains(Flags::DASHED_UNDERLINE) {
+            Some(Underline::Dashed)
+        } else if flags.contains(Flags::UNDERLINE) {
+            Some(Underline::Single)
+        } else {
+            None
+        }
+    }
+}
+
 /// Style attributes for a cell or text run
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CellStyle {
@@ -43,7 +82,9 @@ pub struct CellStyle {
     pub bg: Rgba,
     pub bold: bool,
     pub italic: bool,
-    pub underline: bool,
+    pub underline: Option<Underline>,
+    /// Color of the underline set with `SGR 58`; the text color otherwise
+    pub underline_color: Option<Rgba>,
     pub strikethrough: bool,
     /// Whether this is a decorative character (Powerline, box drawing, etc.)
     pub is_decorative: bool,
@@ -56,7 +97,8 @@ impl Default for CellStyle {
             bg: Rgba::from_rgb(0, 0, 0),
             bold: false,
             italic: false,
-            underline: false,
+            underline: None,
+            underline_color: None,
             strikethrough: false,
             is_decorative: false,
         }
@@ -64,7 +106,7 @@ impl Default for CellStyle {
 }
 
 /// A batched text run - multiple adjacent cells with identical styling
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BatchedTextRun {
     /// Row index (0-based)
     pub row: u16,
@@ -79,7 +121,7 @@ pub struct BatchedTextRun {
 }
 
 /// A background rectangle to render
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BackgroundRect {
     /// Row index
     pub row: u16,
@@ -91,19 +133,97 @@ pub struct BackgroundRect {
     pub color: Rgba,
 }
 
-/// Complete renderable content for a terminal frame
-#[derive(Debug, Clone)]
-pub struct RenderableContent {
+/// Text runs and backgrounds of one row of the viewport
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineRuns {
     /// Batched text runs for efficient text rendering
     pub text_runs: Vec<BatchedTextRun>,
     /// Background rectangles (merged for efficiency)
     pub backgrounds: Vec<BackgroundRect>,
+}
+
+/// Complete renderable content for a terminal frame
+#[derive(Debug, Clone)]
+pub struct RenderableContent {
+    /// Runs of each row of the viewport. A row that didn't change since the
+    /// previous frame of a `RenderCache` shares its allocation with it.
+    pub lines: Vec<Arc<LineRuns>>,
     /// Cursor position (row, col)
     pub cursor: (u16, u16),
+    /// Whether the cursor is inside the viewport
+    pub cursor_visible: bool,
+    /// Shape of the cursor as requested with DECSCUSR, or hidden
+    pub cursor_shape: CursorShape,
+    /// Whether the application asked for a blinking cursor
+    pub cursor_blinking: bool,
+    /// Whether the cursor is on a double-width character
+    pub cursor_wide: bool,
     /// Grid dimensions (rows, cols)
     pub size: (u16, u16),
+    /// Lines between the bottom of the viewport and the latest output
+    pub display_offset: usize,
+    /// Lines in the scrollback above the screen
+    pub history_size: usize,
+}
+
+impl RenderableContent {
+    /// Text runs of all rows, from the top
+    pub fn text_runs(&self) -> impl Iterator<Item = &BatchedTextRun> {
+        self.lines.iter().flat_map(|line| line.text_runs.iter())
+    }
+
+    /// Background rectangles of all rows, from the top
+    pub fn backgrounds(&self) -> impl Iterator<Item = &BackgroundRect> {
+        self.lines.iter().flat_map(|line| line.backgrounds.iter())
+    }
+
+    /// Position and height of the scrollbar thumb as fractions of the track,
+    /// or None when there is no scrollback
+    pub fn scrollbar_thumb(&self) -> Option<(f32, f32)> {
+        if self.history_size == 0 {
+            return None;
+        }
+
+        let total = (self.history_size + self.size.0 as usize) as f32;
+        let first_visible = self.history_size.saturating_sub(self.display_offset) as f32;
+        Some((first_visible / total, self.size.0 as f32 / total))
+    }
+
+    /// Display offset that centers the viewport on a position of the
+    /// scrollbar track, given as a fraction of its height
+    pub fn display_offset_at(&self, fraction: f32) -> usize {
+        let rows = self.size.0 as f32;
+        let total = self.history_size as f32 + rows;
+        let first_visible = (fraction.clamp(0.0, 1.0) * total - rows / 2.0).round();
+        let first_visible = first_visible.clamp(0.0, self.history_size as f32) as usize;
+        self.history_size - first_visible
+    }
 }
 
+/// Background of selected cells
+pub const SELECTION_BG: Rgba = Rgba {
+    r: 0x26,
+    g: 0x4f,
+    b: 0x78,
+    a: 255,
+};
+
+/// Background of search matches
+pub const MATCH_BG: Rgba = Rgba {
+    r: 0x61,
+    g: 0x4d,
+    b: 0x1b,
+    a: 255,
+};
+
+/// Background of the focused search match
+pub const FOCUSED_MATCH_BG: Rgba = Rgba {
+    r: 0xa8,
+    g: 0x6a,
+    b: 0x00,
+    a: 255,
+};
+
 /// Default terminal color palette (basic 16 colors)
 fn named_color_to_rgba(color: NamedColor) -> Rgba {
     match color {
@@ -179,59 +299,178 @@ fn alacritty_color_to_rgba(color: alacritty_terminal::vte::ansi::Color) -> Rgba
     }
 }
 
+/// What a frame shows besides the grid cells, which the terminal's damage
+/// tracking doesn't cover
+#[derive(Debug, Clone, PartialEq)]
+struct FrameInputs {
+    display_offset: usize,
+    selection: Option<SelectionRange>,
+    /// Search matches in the viewport, with whether each is focused
+    search_matches: Vec<(Match, bool)>,
+}
+
+impl FrameInputs {
+    fn new(term: &Term<EventProxy>, rows: u16, search: Option<&TerminalSearch>) -> Self {
+        let display_offset = term.grid().display_offset();
+        let top = Line(0) - display_offset;
+        FrameInputs {
+            display_offset,
+            selection: term.selection.as_ref().and_then(|s| s.to_range(term)),
+            search_matches: search
+                .map(|search| search.matches_in(top, top + (rows as i32 - 1)))
+                .unwrap_or_default(),
+        }
+    }
+}
+
 /// Batch terminal cells into text runs for efficient rendering.
 ///
 /// This function iterates through the terminal grid and groups adjacent cells
 /// with identical styling into single text runs, dramatically reducing the
-/// number of draw calls needed.
-pub fn batch_cells(term: &Term<EventProxy>, rows: u16, cols: u16) -> RenderableContent {
+/// number of draw calls needed. It rebuilds every row; `RenderCache` only
+/// rebuilds the rows that changed.
+pub fn batch_cells(
+    term: &Term<EventProxy>,
+    rows: u16,
+    cols: u16,
+    search: Option<&TerminalSearch>,
+) -> RenderableContent {
+    let inputs = FrameInputs::new(term, rows, search);
+    let lines = (0..rows)
+        .map(|row| Arc::new(batch_line(term, &inputs, row, cols)))
+        .collect();
+    renderable_content(term, lines, rows, cols)
+}
+
+/// Keeps the runs of each row between frames and rebuilds only the rows
+/// the terminal reports as damaged.
+///
+/// Full-screen applications tend to redraw every cell on each update, most
+/// of which doesn't change anything on screen, so damaged rows are compared
+/// with the cells they were built from first. Rows that didn't change keep
+/// their previous allocation, so a view can tell which rows it has to paint
+/// again by comparing pointers.
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    lines: Vec<CachedLine>,
+    cols: u16,
+    inputs: Option<FrameInputs>,
+}
+
+/// Runs of a row with the cells they were built from
+#[derive(Debug, Default)]
+struct CachedLine {
+    cells: Vec<Cell>,
+    runs: Arc<LineRuns>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Brings the cached rows up to date with the terminal and returns the
+    /// frame. Consumes the terminal's damage.
+    pub fn update(
+        &mut self,
+        term: &mut Term<EventProxy>,
+        rows: u16,
+        cols: u16,
+        search: Option<&TerminalSearch>,
+    ) -> RenderableContent {
+        let inputs = FrameInputs::new(term, rows, search);
+        // A new size, scroll position, selection or search changes rows the
+        // terminal doesn't know about
+        let rebuild_all = self.lines.len() != rows as usize
+            || self.cols != cols
+            || self.inputs.as_ref() != Some(&inputs);
+
+        let damaged: Vec<usize> = match term.damage() {
+            TermDamage::Partial(lines) if !rebuild_all => lines
+                .map(|damage| damage.line)
+                .filter(|&line| line < rows as usize)
+                .collect(),
+            _ => (0..rows as usize).collect(),
+        };
+        term.reset_damage();
+
+        self.lines.resize_with(rows as usize, Default::default);
+        for row in damaged {
+            let grid_line = Line(row as i32) - inputs.display_offset;
+            let cells = &term.grid()[grid_line][..];
+            let line = &mut self.lines[row];
+            if !rebuild_all && line.cells == cells {
+                continue;
+            }
+
+            let runs = batch_line(term, &inputs, row as u16, cols);
+            line.cells.clear();
+            line.cells.extend_from_slice(cells);
+            if *line.runs != runs {
+                line.runs = Arc::new(runs);
+            }
+        }
+        self.cols = cols;
+        self.inputs = Some(inputs);
+
+        let lines = self.lines.iter().map(|line| line.runs.clone()).collect();
+        renderable_content(term, lines, rows, cols)
+    }
+}
+
+/// Batches one row of the viewport.
+///
+/// Selected cells get the selection background and search matches are
+/// highlighted, the focused match more strongly. A wide character always
+/// gets a run of its own spanning both of its cells, so the glyph is never
+/// followed by the blank spacer cell.
+fn batch_line(term: &Term<EventProxy>, inputs: &FrameInputs, row: u16, cols: u16) -> LineRuns {
     let grid = term.grid();
     let mut text_runs = Vec::new();
     let mut backgrounds = Vec::new();
 
     let default_bg = Rgba::from_rgb(0, 0, 0);
 
-    for row in 0..rows {
-        let line_idx = Line(row as i32);
-        let mut current_run: Option<BatchedTextRun> = None;
-        let mut current_bg: Option<BackgroundRect> = None;
-
-        for col in 0..cols {
-            let point = Point::new(line_idx, Column(col as usize));
-            let cell = &grid[point];
-
-            let ch = cell.c;
-            let fg = alacritty_color_to_rgba(cell.fg);
-            let bg = alacritty_color_to_rgba(cell.bg);
-
-            let is_decorative = is_decorative_character(ch);
-            let flags = cell.flags;
-
-            let style = CellStyle {
-                fg,
-                bg,
-                bold: flags.contains(Flags::BOLD),
-                italic: flags.contains(Flags::ITALIC),
-                underline: flags.contains(Flags::UNDERLINE),
-                strikethrough: flags.contains(Flags::STRIKEOUT),
-                is_decorative,
-            };
-
-            // Handle background batching
-            if bg != default_bg {
-                if let Some(ref mut bg_rect) = current_bg {
-                    if bg_rect.color == bg {
-                        bg_rect.end_col = col + 1;
-                    } else {
-                        backgrounds.push(bg_rect.clone());
-                        current_bg = Some(BackgroundRect {
-                            row,
-                            start_col: col,
-                            end_col: col + 1,
-                            color: bg,
-                        });
-                    }
+    let mut current_run: Option<BatchedTextRun> = None;
+    let mut current_bg: Option<BackgroundRect> = None;
+
+    for col in 0..cols {
+        let viewport_point = Point::new(row as usize, Column(col as usize));
+        let point = viewport_to_point(inputs.display_offset, viewport_point);
+        let cell = &grid[point];
+        let flags = cell.flags;
+
+        let ch = cell.c;
+        let fg = alacritty_color_to_rgba(cell.fg);
+        let selected = inputs.selection.is_some_and(|range| range.contains(point));
+        let search_match = inputs.search_matches.iter().find(|(m, _)| m.contains(&point));
+        let bg = match search_match {
+            _ if selected => SELECTION_BG,
+            Some((_, true)) => FOCUSED_MATCH_BG,
+            Some((_, false)) => MATCH_BG,
+            None => alacritty_color_to_rgba(cell.bg),
+        };
+
+        let is_decorative = is_decorative_character(ch);
+
+        let style = CellStyle {
+            fg,
+            bg,
+            bold: flags.contains(Flags::BOLD),
+            italic: flags.contains(Flags::ITALIC),
+            underline: Underline::from_flags(flags),
+            underline_color: cell.underline_color().map(alacritty_color_to_rgba),
+            strikethrough: flags.contains(Flags::STRIKEOUT),
+            is_decorative,
+        };
+
+        // Handle background batching
+        if bg != default_bg {
+            if let Some(ref mut bg_rect) = current_bg {
+                if bg_rect.color == bg {
+                    bg_rect.end_col = col + 1;
                 } else {
+                    backgrounds.push(bg_rect.clone());
                     current_bg = Some(BackgroundRect {
                         row,
                         start_col: col,
@@ -239,37 +478,59 @@ pub fn batch_cells(term: &Term<EventProxy>, rows: u16, cols: u16) -> RenderableC
                         color: bg,
                     });
                 }
-            } else if let Some(bg_rect) = current_bg.take() {
-                backgrounds.push(bg_rect);
+            } else {
+                current_bg = Some(BackgroundRect {
+                    row,
+                    start_col: col,
+                    end_col: col + 1,
+                    color: bg,
+                });
             }
+        } else if let Some(bg_rect) = current_bg.take() {
+            backgrounds.push(bg_rect);
+        }
 
-            // Handle text run batching
-            // We only batch if styles match (excluding background, handled separately)
-            let text_style = CellStyle {
-                bg: default_bg, // Normalize bg for text comparison
-                ..style
-            };
+        // Handle text run batching
+        // We only batch if styles match (excluding background, handled separately)
+        let text_style = CellStyle {
+            bg: default_bg, // Normalize bg for text comparison
+            ..style
+        };
+
+        // The spacer after a wide character belongs to the character's run
+        if flags.contains(Flags::WIDE_CHAR_SPACER) {
+            if let Some(mut run) = current_run.take() {
+                run.cell_count += 1;
+                text_runs.push(run);
+            }
+            continue;
+        }
 
-            if let Some(ref mut run) = current_run {
-                let run_style = CellStyle {
-                    bg: default_bg,
-                    ..run.style
-                };
+        if flags.contains(Flags::WIDE_CHAR) {
+            if let Some(run) = current_run.take() {
+                text_runs.push(run);
+            }
+            current_run = Some(BatchedTextRun {
+                row,
+                start_col: col,
+                text: ch.to_string(),
+                style,
+                cell_count: 1,
+            });
+            continue;
+        }
 
-                if run_style == text_style {
-                    run.text.push(ch);
-                    run.cell_count += 1;
-                } else {
-                    text_runs.push(run.clone());
-                    current_run = Some(BatchedTextRun {
-                        row,
-                        start_col: col,
-                        text: ch.to_string(),
-                        style,
-                        cell_count: 1,
-                    });
-                }
+        if let Some(ref mut run) = current_run {
+            let run_style = CellStyle {
+                bg: default_bg,
+                ..run.style
+            };
+
+            if run_style == text_style {
+                run.text.push(ch);
+                run.cell_count += 1;
             } else {
+                text_runs.push(run.clone());
                 current_run = Some(BatchedTextRun {
                     row,
                     start_col: col,
@@ -278,26 +539,62 @@ pub fn batch_cells(term: &Term<EventProxy>, rows: u16, cols: u16) -> RenderableC
                     cell_count: 1,
                 });
             }
-        }
-
-        // Flush remaining run and background for this row
-        if let Some(run) = current_run.take() {
-            text_runs.push(run);
-        }
-        if let Some(bg_rect) = current_bg.take() {
-            backgrounds.push(bg_rect);
+        } else {
+            current_run = Some(BatchedTextRun {
+                row,
+                start_col: col,
+                text: ch.to_string(),
+                style,
+                cell_count: 1,
+            });
         }
     }
 
-    // Get cursor position
-    let cursor_point = term.renderable_content().cursor.point;
-    let cursor = (cursor_point.line.0 as u16, cursor_point.column.0 as u16);
+    // Flush remaining run and background for this row
+    if let Some(run) = current_run.take() {
+        text_runs.push(run);
+    }
+    if let Some(bg_rect) = current_bg.take() {
+        backgrounds.push(bg_rect);
+    }
 
-    RenderableContent {
+    LineRuns {
         text_runs,
         backgrounds,
+    }
+}
+
+/// Assembles a frame from its rows and the cursor
+fn renderable_content(
+    term: &Term<EventProxy>,
+    lines: Vec<Arc<LineRuns>>,
+    rows: u16,
+    cols: u16,
+) -> RenderableContent {
+    let grid = term.grid();
+    let display_offset = grid.display_offset();
+
+    // Get cursor position within the viewport; it is off screen while
+    // scrolled back far enough
+    let term_cursor = term.renderable_content().cursor;
+    let cursor_viewport = point_to_viewport(display_offset, term_cursor.point)
+        .filter(|point| point.line < rows as usize);
+    let cursor = cursor_viewport
+        .map(|point| (point.line as u16, point.column.0 as u16))
+        .unwrap_or((0, 0));
+    let cursor_wide = grid[term_cursor.point].flags.contains(Flags::WIDE_CHAR);
+
+    RenderableContent {
+        lines,
         cursor,
+        cursor_visible: cursor_viewport.is_some(),
+        // Hidden while the application turned the cursor off (DECTCEM)
+        cursor_shape: term_cursor.shape,
+        cursor_blinking: term.cursor_style().blinking,
+        cursor_wide,
         size: (rows, cols),
+        display_offset,
+        history_size: grid.history_size(),
     }
 }
 
@@ -333,7 +630,284 @@ mod tests {
         assert_eq!(style.bg, Rgba::from_rgb(0, 0, 0));
         assert!(!style.bold);
         assert!(!style.italic);
-        assert!(!style.underline);
+        assert_eq!(style.underline, None);
+    }
+
+    fn term_with(text: &str, rows: usize, cols: usize) -> Term<EventProxy> {
+        use alacritty_terminal::term::test::TermSize;
+        use alacritty_terminal::term::Config;
+        use alacritty_terminal::vte::ansi::Processor;
+
+        let mut term = Term::new(Config::default(), &TermSize::new(cols, rows), EventProxy::new());
+        Processor::<alacritty_terminal::vte::ansi::StdSyncHandler>::new()
+            .advance(&mut term, text.as_bytes());
+        term
+    }
+
+    #[test]
+    fn test_wide_chars_get_their_own_run() {
+        let term = term_with("ab漢c", 2, 10);
+        let content = batch_cells(&term, 2, 10, None);
+        let runs: Vec<_> = content
+            .text_runs()
+            .filter(|run| run.row == 0)
+            .map(|run| (run.start_col, run.text.as_str(), run.cell_count))
+            .collect();
+
+        assert_eq!(runs[0], (0, "ab", 2));
+        assert_eq!(runs[1], (2, "漢", 2));
+        assert!(runs[2].1.starts_with('c'));
+        assert_eq!(runs[2].0, 4);
+    }
+
+    #[test]
+    fn test_underline_styles_and_colors() {
+        let term = term_with(
+            "\x1b[4ma\x1b[4:2mb\x1b[4:3;58;2;255;0;0mc\x1b[4:4;59md\x1b[24me",
+            2,
+            10,
+        );
+        let content = batch_cells(&term, 2, 10, None);
+        let styles: Vec<_> = content
+            .text_runs()
+            .take(5)
+            .map(|run| (run.style.underline, run.style.underline_color))
+            .collect();
+
+        let red = Some(Rgba::from_rgb(255, 0, 0));
+        assert_eq!(
+            styles,
+            vec![
+                (Some(Underline::Single), None),
+                (Some(Underline::Double), None),
+                (Some(Underline::Curly), red),
+                (Some(Underline::Dotted), None),
+                (None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cursor_shape_follows_decscusr() {
+        let content = batch_cells(&term_with("", 2, 10), 2, 10, None);
+        assert_eq!(content.cursor_shape, CursorShape::Block);
+        assert!(!content.cursor_blinking);
+
+        let content = batch_cells(&term_with("\x1b[5 q", 2, 10), 2, 10, None);
+        assert_eq!(content.cursor_shape, CursorShape::Beam);
+        assert!(content.cursor_blinking);
+
+        let content = batch_cells(&term_with("\x1b[4 q", 2, 10), 2, 10, None);
+        assert_eq!(content.cursor_shape, CursorShape::Underline);
+        assert!(!content.cursor_blinking);
+
+        let content = batch_cells(&term_with("\x1b[?25l", 2, 10), 2, 10, None);
+        assert_eq!(content.cursor_shape, CursorShape::Hidden);
+
+        // The cursor covers both cells of a wide character under it
+        let content = batch_cells(&term_with("漢\x1b[1;1H", 2, 10), 2, 10, None);
+        assert!(content.cursor_wide);
+    }
+
+    #[test]
+    fn test_selection_is_highlighted() {
+        use alacritty_terminal::index::{Line, Side};
+        use alacritty_terminal::selection::{Selection, SelectionType};
+
+        let mut term = term_with("hello world", 2, 20);
+        let mut selection = Selection::new(
+            SelectionType::Simple,
+            Point::new(Line(0), Column(6)),
+            Side::Left,
+        );
+        selection.update(Point::new(Line(0), Column(10)), Side::Right);
+        term.selection = Some(selection);
+
+        let content = batch_cells(&term, 2, 20, None);
+        let highlighted: Vec<_> = content
+            .backgrounds()
+            .filter(|bg| bg.color == SELECTION_BG)
+            .map(|bg| (bg.row, bg.start_col, bg.end_col))
+            .collect();
+        assert_eq!(highlighted, vec![(0, 6, 11)]);
+    }
+
+    #[test]
+    fn test_search_matches_are_highlighted() {
+        use crate::terminal::search::SearchQuery;
+
+        let term = term_with("foo bar foo", 2, 20);
+        let mut search = TerminalSearch::new(&SearchQuery {
+            text: "foo".to_string(),
+            ..SearchQuery::default()
+        })
+        .unwrap();
+        search.update_matches(&term);
+        search.focus_next();
+
+        let content = batch_cells(&term, 2, 20, Some(&search));
+        let highlighted: Vec<_> = content
+            .backgrounds()
+            .filter(|bg| bg.color == MATCH_BG || bg.color == FOCUSED_MATCH_BG)
+            .map(|bg| (bg.start_col, bg.end_col, bg.color == FOCUSED_MATCH_BG))
+            .collect();
+        assert_eq!(highlighted, vec![(0, 3, true), (8, 11, false)]);
+    }
+
+    #[test]
+    fn test_render_cache_rebuilds_only_damaged_rows() {
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+
+        let mut term = term_with("one\r\ntwo\r\nthree", 4, 10);
+        let mut parser = Processor::<StdSyncHandler>::new();
+        let mut cache = RenderCache::new();
+        let first = cache.update(&mut term, 4, 10, None);
+
+        // Rewrite the first row with what it already shows and change the third
+        parser.advance(&mut term, b"\x1b[1;1Hone\x1b[3;1HTHREE");
+        let second = cache.update(&mut term, 4, 10, None);
+        assert!(Arc::ptr_eq(&first.lines[0], &second.lines[0]));
+        assert!(Arc::ptr_eq(&first.lines[1], &second.lines[1]));
+        assert!(!Arc::ptr_eq(&first.lines[2], &second.lines[2]));
+        assert_eq!(second.lines[2].text_runs[0].text.trim_end(), "THREE");
+
+        // The cached frame matches a full rebuild
+        let full = batch_cells(&term, 4, 10, None);
+        assert_eq!(second.lines, full.lines);
+        assert_eq!(second.cursor, full.cursor);
+    }
+
+    #[test]
+    fn test_render_cache_follows_selection_and_scrolling() {
+        use alacritty_terminal::grid::Scroll;
+        use alacritty_terminal::index::Side;
+        use alacritty_terminal::selection::{Selection, SelectionType};
+
+        let text: String = (0..10).map(|i| format!("line {}\r\n", i)).collect();
+        let mut term = term_with(&text, 4, 10);
+        let mut cache = RenderCache::new();
+        cache.update(&mut term, 4, 10, None);
+
+        // Selecting doesn't damage the terminal, but changes the rows
+        let mut selection = Selection::new(
+            SelectionType::Simple,
+            Point::new(Line(0), Column(0)),
+            Side::Left,
+        );
+        selection.update(Point::new(Line(0), Column(3)), Side::Right);
+        term.selection = Some(selection);
+        let selected = cache.update(&mut term, 4, 10, None);
+        assert_eq!(selected.lines, batch_cells(&term, 4, 10, None).lines);
+        assert!(selected.backgrounds().any(|bg| bg.color == SELECTION_BG));
+
+        term.selection = None;
+        term.scroll_display(Scroll::Delta(3));
+        let scrolled = cache.update(&mut term, 4, 10, None);
+        assert_eq!(scrolled.display_offset, 3);
+        assert_eq!(scrolled.lines, batch_cells(&term, 4, 10, None).lines);
+    }
+
+    /// Feeds a large log through the parser in PTY-sized chunks and
+    /// compares rebuilding every row after each chunk with the render
+    /// cache, then does the same for a full-screen application redrawing
+    /// a mostly unchanged screen. Run with
+    /// `cargo test --release bench_ -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_render_cat_large_log() {
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+        use std::time::{Duration, Instant};
+
+        const ROWS: u16 = 50;
+        const COLS: u16 = 200;
+
+        fn run(
+            label: &str,
+            chunks: &[Vec<u8>],
+            mut render: impl FnMut(&mut Term<EventProxy>) -> RenderableContent,
+        ) -> Duration {
+            let mut term = term_with("", ROWS as usize, COLS as usize);
+            let mut parser = Processor::<StdSyncHandler>::new();
+            let mut rendering = Duration::ZERO;
+            for chunk in chunks {
+                parser.advance(&mut term, chunk);
+                let start = Instant::now();
+                std::hint::black_box(render(&mut term));
+                rendering += start.elapsed();
+            }
+            println!("{:<40} {:>10.2?} for {} frames", label, rendering, chunks.len());
+            rendering
+        }
+
+        // `cat` of a colored log, arriving in 4 KiB reads
+        let mut log = Vec::new();
+        for i in 0..50_000 {
+            log.extend_from_slice(
+                format!(
+                    "2024-05-01T12:{:02}:{:02}Z \x1b[32mINFO\x1b[0m worker-{} processed request \
+                     {} in {}ms \x1b[2m(cache hit)\x1b[0m\r\n",
+                    i / 60 % 60,
+                    i % 60,
+                    i % 8,
+                    i,
+                    i % 250
+                )
+                .as_bytes(),
+            );
+        }
+        let cat: Vec<Vec<u8>> = log.chunks(4096).map(<[u8]>::to_vec).collect();
+
+        // A full-screen application repainting every row, of which only
+        // the status line changes
+        let tui: Vec<Vec<u8>> = (0..2_000)
+            .map(|frame| {
+                let mut screen = b"\x1b[H".to_vec();
+                for row in 0..ROWS - 1 {
+                    let line = format!("\x1b[44m│ task {:>3} \x1b[0m {:─<180}│\r\n", row, "");
+                    screen.extend_from_slice(line.as_bytes());
+                }
+                screen.extend_from_slice(format!("\x1b[7m frame {} \x1b[0m", frame).as_bytes());
+                screen
+            })
+            .collect();
+
+        let full_cat = run("cat, rebuilding every row", &cat, |term| {
+            batch_cells(term, ROWS, COLS, None)
+        });
+        let mut cache = RenderCache::new();
+        let cached_cat = run("cat, render cache", &cat, |term| {
+            cache.update(term, ROWS, COLS, None)
+        });
+        let full_tui = run("full-screen redraw, rebuilding every row", &tui, |term| {
+            batch_cells(term, ROWS, COLS, None)
+        });
+        let mut cache = RenderCache::new();
+        let cached_tui = run("full-screen redraw, render cache", &tui, |term| {
+            cache.update(term, ROWS, COLS, None)
+        });
+
+        println!(
+            "speedup: cat {:.1}x, full-screen redraw {:.1}x",
+            full_cat.as_secs_f64() / cached_cat.as_secs_f64(),
+            full_tui.as_secs_f64() / cached_tui.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn test_scrollbar_thumb() {
+        let mut content = batch_cells(&term_with("", 10, 10), 10, 10, None);
+        assert_eq!(content.scrollbar_thumb(), None);
+
+        content.history_size = 30;
+        // Following the output, the thumb sits at the bottom
+        assert_eq!(content.scrollbar_thumb(), Some((0.75, 0.25)));
+        content.display_offset = 30;
+        assert_eq!(content.scrollbar_thumb(), Some((0.0, 0.25)));
+
+        // Clicking the track centers the viewport on that position
+        assert_eq!(content.display_offset_at(0.0), 30);
+        assert_eq!(content.display_offset_at(1.0), 0);
+        assert_eq!(content.display_offset_at(0.5), 15);
     }
 
     #[test]
@@ -0,0 +1,235 @@
+//! Search through the terminal grid, including the scrollback.
+//!
+//! Matches are found with alacritty_terminal's `RegexSearch` over every line
+//! of the grid history, so a search can reach output that scrolled out of
+//! view long ago.
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
+use anyhow::{Result, anyhow};
+
+/// Stop collecting matches after this many, to bound the work done per update
+const MAX_MATCHES: usize = 10_000;
+
+/// What to search for
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    /// Match letter case exactly
+    pub case_sensitive: bool,
+    /// Treat the text as a regular expression rather than literal text
+    pub regex: bool,
+}
+
+impl SearchQuery {
+    /// Pattern handed to the regex engine
+    pub fn pattern(&self) -> String {
+        let text = if self.regex {
+            self.text.clone()
+        } else {
+            escape_regex(&self.text)
+        };
+
+        // The engine is case-insensitive unless the pattern has uppercase
+        // letters; the flag makes the toggle apply either way
+        if self.case_sensitive {
+            format!("(?-i){}", text)
+        } else {
+            format!("(?i){}", text)
+        }
+    }
+}
+
+/// Escape characters with a special meaning in regular expressions
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// An active search with its matches, ordered from the top of the history
+pub struct TerminalSearch {
+    regex: RegexSearch,
+    matches: Vec<Match>,
+    focused: Option<usize>,
+}
+
+impl TerminalSearch {
+    /// Compile a query, failing on invalid regular expressions
+    pub fn new(query: &SearchQuery) -> Result<Self> {
+        let regex = RegexSearch::new(&query.pattern())
+            .map_err(|e| anyhow!("Invalid search pattern: {}", e))?;
+
+        Ok(TerminalSearch {
+            regex,
+            matches: Vec::new(),
+            focused: None,
+        })
+    }
+
+    /// Find all matches in the grid again, e.g. after new output arrived
+    pub fn update_matches<T>(&mut self, term: &Term<T>) {
+        let start = Point::new(term.topmost_line(), Column(0));
+        let end = Point::new(term.bottommost_line(), term.last_column());
+
+        self.matches = RegexIter::new(start, end, Direction::Right, term, &mut self.regex)
+            .take(MAX_MATCHES)
+            .collect();
+
+        self.focused = match self.focused {
+            _ if self.matches.is_empty() => None,
+            Some(index) => Some(index.min(self.matches.len() - 1)),
+            None => None,
+        };
+    }
+
+    /// All matches from the top of the history down
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    /// Index of the focused match
+    pub fn focused_index(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// The focused match
+    pub fn focused_match(&self) -> Option<&Match> {
+        self.focused.and_then(|index| self.matches.get(index))
+    }
+
+    /// Focus the last match starting at or above `line`, falling back to
+    /// the first match below it
+    pub fn focus_nearest_above(&mut self, line: Line) {
+        self.focused = self
+            .matches
+            .iter()
+            .rposition(|m| m.start().line <= line)
+            .or(if self.matches.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+    }
+
+    /// Focus the next match further down, wrapping around to the top
+    pub fn focus_next(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        self.focused = Some(match self.focused {
+            Some(index) => (index + 1) % self.matches.len(),
+            None => 0,
+        });
+    }
+
+    /// Focus the previous match further up, wrapping around to the bottom
+    pub fn focus_previous(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() - 1;
+        self.focused = Some(match self.focused {
+            Some(0) | None => last,
+            Some(index) => index - 1,
+        });
+    }
+
+    /// Matches overlapping the lines `top..=bottom`, with whether each is
+    /// the focused one
+    pub fn matches_in(&self, top: Line, bottom: Line) -> Vec<(Match, bool)> {
+        // Matches are ordered, so skip straight to the first candidate
+        let first = self.matches.partition_point(|m| m.end().line < top);
+        self.matches[first..]
+            .iter()
+            .enumerate()
+            .take_while(|(_, m)| m.start().line <= bottom)
+            .map(|(offset, m)| (m.clone(), self.focused == Some(first + offset)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::session::EventProxy;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+
+    fn term_with(text: &str, rows: usize, cols: usize) -> Term<EventProxy> {
+        let mut term = Term::new(
+            Config::default(),
+            &TermSize::new(cols, rows),
+            EventProxy::new(),
+        );
+        Processor::<StdSyncHandler>::new().advance(&mut term, text.as_bytes());
+        term
+    }
+
+    fn query(text: &str, case_sensitive: bool, regex: bool) -> SearchQuery {
+        SearchQuery {
+            text: text.to_string(),
+            case_sensitive,
+            regex,
+        }
+    }
+
+    #[test]
+    fn test_pattern_escaping_and_case() {
+        assert_eq!(query("a.b", false, false).pattern(), "(?i)a\\.b");
+        assert_eq!(query("a.b", true, true).pattern(), "(?-i)a.b");
+        assert!(TerminalSearch::new(&query("(unclosed", false, true)).is_err());
+        assert!(TerminalSearch::new(&query("(unclosed", false, false)).is_ok());
+    }
+
+    #[test]
+    fn test_matches_include_scrollback() {
+        let mut text = String::from("Error: first\r\n");
+        for i in 0..20 {
+            text.push_str(&format!("line {}\r\n", i));
+        }
+        text.push_str("error: second\r\n");
+        let term = term_with(&text, 5, 20);
+
+        let mut search = TerminalSearch::new(&query("error", false, false)).unwrap();
+        search.update_matches(&term);
+        assert_eq!(search.matches().len(), 2);
+        assert!(search.matches()[0].start().line < Line(0));
+
+        let mut search = TerminalSearch::new(&query("Error", true, false)).unwrap();
+        search.update_matches(&term);
+        assert_eq!(search.matches().len(), 1);
+
+        let mut search = TerminalSearch::new(&query(r"line 1\d", false, true)).unwrap();
+        search.update_matches(&term);
+        assert_eq!(search.matches().len(), 10);
+    }
+
+    #[test]
+    fn test_focus_navigation_wraps() {
+        let term = term_with("foo\r\nbar foo\r\nfoo\r\n", 5, 20);
+        let mut search = TerminalSearch::new(&query("foo", false, false)).unwrap();
+        search.update_matches(&term);
+        assert_eq!(search.matches().len(), 3);
+
+        search.focus_nearest_above(Line(1));
+        assert_eq!(search.focused_index(), Some(1));
+        search.focus_next();
+        search.focus_next();
+        assert_eq!(search.focused_index(), Some(0));
+        search.focus_previous();
+        assert_eq!(search.focused_index(), Some(2));
+
+        let visible = search.matches_in(Line(1), Line(2));
+        assert_eq!(visible.len(), 2);
+        assert!(visible[1].1);
+    }
+}
@@ -1,7 +1,10 @@
 use alacritty_terminal::event::{Event, EventListener};
-use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Point, Side};
+use alacritty_terminal::selection::{Selection, SelectionType};
+use alacritty_terminal::term::cell::Flags;
 use alacritty_terminal::term::Config as TermConfig;
-use alacritty_terminal::term::Term;
+use alacritty_terminal::term::{viewport_to_point, Term, TermMode};
 use alacritty_terminal::term::test::TermSize;
 use alacritty_terminal::vte::ansi::Processor;
 use anyhow::{Context, Result};
@@ -16,8 +19,11 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use crate::terminal::event_loop::{start_event_loop, EventLoopHandle};
-use crate::terminal::events::TerminalEvent;
+use crate::terminal::events::{ExitStatus, TerminalEvent};
 use crate::terminal::handle::TerminalHandle;
+use crate::terminal::paste;
+use crate::terminal::render::RenderCache;
+use crate::terminal::search::{SearchQuery, TerminalSearch};
 use crate::terminal::PtyProcess;
 
 /// Session state for persistence across app restarts
@@ -61,6 +67,13 @@ impl SessionState {
         format!("{:x}.json", hasher.finish())
     }
 
+    /// Returns the path of the terminal layout saved next to the session state
+    pub(crate) fn layout_file(worktree_path: &Path) -> Result<PathBuf> {
+        let filename = Self::session_filename(worktree_path);
+        let stem = filename.trim_end_matches(".json");
+        Ok(Self::sessions_dir()?.join(format!("{}.layout.json", stem)))
+    }
+
     /// Saves the session state to disk
     pub fn save(&self) -> Result<()> {
         let sessions_dir = Self::sessions_dir()?;
@@ -179,6 +192,11 @@ impl EventListener for EventProxy {
     }
 }
 
+/// A terminal session shared between its owner and the views attached to it.
+/// The session outlives any single view, so output and scrollback survive
+/// switching between worktrees.
+pub type SharedTerminalSession = Arc<Mutex<TerminalSession>>;
+
 /// Represents a terminal session for a specific worktree.
 /// Wraps a PTY process and integrates with Alacritty's terminal emulator.
 pub struct TerminalSession {
@@ -188,13 +206,15 @@ pub struct TerminalSession {
     _event_loop_handle: EventLoopHandle,
     /// Receiver for events from the PTY background thread
     event_rx: Receiver<TerminalEvent>,
+    /// Notified when events are waiting in `event_rx`
+    notify_rx: async_channel::Receiver<()>,
     /// Alacritty terminal emulator
     term: Term<EventProxy>,
     /// Current grid size (rows, cols)
     grid_size: (u16, u16),
     /// Path to the worktree
     worktree_path: PathBuf,
-    #[allow(dead_code)]
+    /// Receives events from the emulator, such as replies to queries
     event_proxy: EventProxy,
     /// ANSI parser for processing PTY output
     parser: Processor,
@@ -202,6 +222,14 @@ pub struct TerminalSession {
     has_activity: bool,
     /// Flag to track if the PTY process is still alive
     is_process_alive: bool,
+    /// How the PTY process ended, once it has
+    exit_status: Option<ExitStatus>,
+    /// Bumped whenever output is processed, so views can tell their cache is stale
+    output_generation: u64,
+    /// Active scrollback search, kept up to date as output arrives
+    search: Option<TerminalSearch>,
+    /// Rows rendered for the last frame
+    render_cache: RenderCache,
 }
 
 impl TerminalSession {
@@ -229,9 +257,11 @@ impl TerminalSession {
         pty.resize(rows, cols)?;
 
         // Start the async event loop for this PTY
-        let event_loop_handle = start_event_loop(pty);
+        let event_loop_handle =
+            start_event_loop(pty).context("Failed to start PTY event loop")?;
         let terminal_handle = event_loop_handle.terminal_handle.clone();
         let event_rx = event_loop_handle.event_rx.clone();
+        let notify_rx = event_loop_handle.notify_rx.clone();
 
         // Create event proxy
         let event_proxy = EventProxy::new();
@@ -239,7 +269,11 @@ impl TerminalSession {
         // Create Alacritty terminal with specified dimensions
         let term_size = TermSize::new(cols as usize, rows as usize);
 
-        let term_config = TermConfig::default();
+        // Kitty keyboard flags only take effect once an application pushes them
+        let term_config = TermConfig {
+            kitty_keyboard: true,
+            ..TermConfig::default()
+        };
         let term = Term::new(term_config, &term_size, event_proxy.clone());
 
         // Create ANSI parser
@@ -249,6 +283,7 @@ impl TerminalSession {
             terminal_handle,
             _event_loop_handle: event_loop_handle,
             event_rx,
+            notify_rx,
             term,
             grid_size: (rows, cols),
             worktree_path,
@@ -256,6 +291,10 @@ impl TerminalSession {
             parser,
             has_activity: false,
             is_process_alive: true,
+            exit_status: None,
+            output_generation: 0,
+            search: None,
+            render_cache: RenderCache::new(),
         };
 
         // Restore saved state if available
@@ -266,6 +305,16 @@ impl TerminalSession {
         Ok(session)
     }
 
+    /// Creates a new terminal session wrapped for sharing between views.
+    pub fn new_shared(
+        worktree_path: PathBuf,
+        shell: Option<String>,
+        rows: u16,
+        cols: u16,
+    ) -> Result<SharedTerminalSession> {
+        Self::new(worktree_path, shell, rows, cols).map(|session| Arc::new(Mutex::new(session)))
+    }
+
     /// Writes input data to the terminal.
     /// This is now non-blocking - input is sent to the background thread.
     pub fn write_input(&mut self, data: &[u8]) -> Result<()> {
@@ -275,8 +324,17 @@ impl TerminalSession {
             .map_err(|e| anyhow::anyhow!("Failed to send input to PTY: {}", e))
     }
 
+    /// Pastes text into the terminal, bracketed if the application asked
+    /// for it, and brings the latest output back into view.
+    pub fn paste(&mut self, text: &str) -> Result<()> {
+        self.scroll_to_bottom();
+        let data = paste::paste_bytes(text, self.mode());
+        self.write_input(&data)
+    }
+
     /// Processes pending events from the PTY background thread.
     /// Returns true if new content was processed, or None if process exited.
+    /// Output that arrived before the exit is processed either way.
     pub fn process_events(&mut self) -> Option<bool> {
         let mut has_new_content = false;
 
@@ -288,10 +346,11 @@ impl TerminalSession {
                     self.parser.advance(&mut self.term, &data);
                     has_new_content = true;
                 }
-                TerminalEvent::ProcessExited(_exit_code) => {
+                TerminalEvent::ProcessExited(status) => {
                     // Process has exited
                     self.is_process_alive = false;
-                    return None;
+                    self.exit_status = Some(status);
+                    break;
                 }
                 TerminalEvent::Resized { rows, cols } => {
                     // Update our stored grid size
@@ -300,15 +359,57 @@ impl TerminalSession {
             }
         }
 
+        // Answer queries such as device attributes or the kitty keyboard mode
+        for event in self.event_proxy.drain_events() {
+            if let Event::PtyWrite(reply) = event {
+                let _ = self.terminal_handle.send_input(reply.into_bytes());
+            }
+        }
+
+        if has_new_content {
+            self.output_generation += 1;
+            if let Some(search) = self.search.as_mut() {
+                search.update_matches(&self.term);
+            }
+        }
+
+        if !self.is_process_alive {
+            return None;
+        }
         Some(has_new_content)
     }
 
+    /// Returns a counter that changes whenever new output has been processed
+    pub fn output_generation(&self) -> u64 {
+        self.output_generation
+    }
+
+    /// Returns the terminal modes set by the running application, such as
+    /// application cursor keys or kitty keyboard flags.
+    pub fn mode(&self) -> TermMode {
+        *self.term.mode()
+    }
+
+    /// Returns the handle for sending data straight to the PTY, bypassing
+    /// the activity tracking of `write_input`.
+    pub fn terminal_handle(&self) -> &TerminalHandle {
+        &self.terminal_handle
+    }
+
     /// Returns the event receiver for async event handling.
     /// Used by TerminalView to subscribe to events.
     pub fn event_receiver(&self) -> &Receiver<TerminalEvent> {
         &self.event_rx
     }
 
+    /// Returns a channel that receives a notification whenever the PTY has
+    /// produced events for `process_events`. Waiting on it lets a view
+    /// render new output as it arrives instead of polling. The channel
+    /// closes when the session shuts down.
+    pub fn output_notifications(&self) -> async_channel::Receiver<()> {
+        self.notify_rx.clone()
+    }
+
     /// Resizes the terminal.
     /// This sends a resize command to the PTY background thread.
     pub fn resize(&mut self, rows: u16, cols: u16) -> Result<()> {
@@ -361,9 +462,170 @@ impl TerminalSession {
     }
 
     /// Gets batched renderable content for efficient rendering.
-    /// This batches adjacent cells with the same style into text runs.
-    pub fn get_renderable_content(&self) -> crate::terminal::render::RenderableContent {
-        crate::terminal::render::batch_cells(&self.term, self.grid_size.0, self.grid_size.1)
+    /// This batches adjacent cells with the same style into text runs,
+    /// rebuilding only the rows that changed since the last call.
+    pub fn get_renderable_content(&mut self) -> crate::terminal::render::RenderableContent {
+        self.render_cache.update(
+            &mut self.term,
+            self.grid_size.0,
+            self.grid_size.1,
+            self.search.as_ref(),
+        )
+    }
+
+    /// Scrolls the viewport into the scrollback by `lines`; negative values
+    /// scroll back towards the latest output.
+    pub fn scroll_lines(&mut self, lines: i32) {
+        self.term.scroll_display(Scroll::Delta(lines));
+    }
+
+    /// Scrolls the viewport up by one screen.
+    pub fn scroll_page_up(&mut self) {
+        self.term.scroll_display(Scroll::PageUp);
+    }
+
+    /// Scrolls the viewport down by one screen.
+    pub fn scroll_page_down(&mut self) {
+        self.term.scroll_display(Scroll::PageDown);
+    }
+
+    /// Scrolls the viewport to the oldest line in the scrollback.
+    pub fn scroll_to_top(&mut self) {
+        self.term.scroll_display(Scroll::Top);
+    }
+
+    /// Scrolls the viewport back to the latest output.
+    pub fn scroll_to_bottom(&mut self) {
+        self.term.scroll_display(Scroll::Bottom);
+    }
+
+    /// Scrolls so that the viewport ends `display_offset` lines above the
+    /// latest output.
+    pub fn scroll_to_offset(&mut self, display_offset: usize) {
+        let current = self.term.grid().display_offset() as i32;
+        self.term.scroll_display(Scroll::Delta(display_offset as i32 - current));
+    }
+
+    /// Number of lines between the bottom of the viewport and the latest
+    /// output; zero while the viewport follows the output.
+    pub fn display_offset(&self) -> usize {
+        self.term.grid().display_offset()
+    }
+
+    /// Converts a viewport cell into a grid point, taking the scrollback
+    /// position into account. Points on the trailing half of a wide
+    /// character are moved onto the character itself.
+    fn selection_point(&self, row: usize, col: usize, side: Side) -> (Point, Side) {
+        let row = row.min(self.term.screen_lines().saturating_sub(1));
+        let col = col.min(self.term.columns().saturating_sub(1));
+        let display_offset = self.term.grid().display_offset();
+        let point = viewport_to_point(display_offset, Point::new(row, Column(col)));
+
+        let cell = &self.term.grid()[point];
+        if cell.flags.contains(Flags::WIDE_CHAR_SPACER) && col > 0 {
+            (Point::new(point.line, Column(col - 1)), Side::Right)
+        } else {
+            (point, side)
+        }
+    }
+
+    /// Starts a new selection at a viewport cell.
+    pub fn start_selection(&mut self, ty: SelectionType, row: usize, col: usize, side: Side) {
+        let (point, side) = self.selection_point(row, col, side);
+        self.term.selection = Some(Selection::new(ty, point, side));
+    }
+
+    /// Extends the current selection to a viewport cell.
+    pub fn update_selection(&mut self, row: usize, col: usize, side: Side) {
+        let (point, side) = self.selection_point(row, col, side);
+        if let Some(selection) = self.term.selection.as_mut() {
+            selection.update(point, side);
+        }
+    }
+
+    /// Clears the current selection.
+    pub fn clear_selection(&mut self) {
+        self.term.selection = None;
+    }
+
+    /// Checks if there is a non-empty selection.
+    pub fn has_selection(&self) -> bool {
+        self.term
+            .selection
+            .as_ref()
+            .is_some_and(|selection| !selection.is_empty())
+    }
+
+    /// Gets the selected text, including lines that scrolled into history.
+    pub fn selection_text(&self) -> Option<String> {
+        self.term.selection_to_string().filter(|text| !text.is_empty())
+    }
+
+    /// Searches the grid and its history, focusing the match closest above
+    /// the bottom of the viewport. Returns the number of matches.
+    pub fn start_search(&mut self, query: &SearchQuery) -> Result<usize> {
+        let mut search = TerminalSearch::new(query)?;
+        search.update_matches(&self.term);
+
+        let last_row = Point::new(self.term.screen_lines() - 1, Column(0));
+        let viewport_bottom = viewport_to_point(self.display_offset(), last_row);
+        search.focus_nearest_above(viewport_bottom.line);
+
+        let count = search.matches().len();
+        self.search = Some(search);
+        self.scroll_to_focused_match();
+        Ok(count)
+    }
+
+    /// Ends the search and removes its highlights.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Focuses the next match further down.
+    pub fn search_next(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            search.focus_next();
+            self.scroll_to_focused_match();
+        }
+    }
+
+    /// Focuses the previous match further up.
+    pub fn search_previous(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            search.focus_previous();
+            self.scroll_to_focused_match();
+        }
+    }
+
+    /// Gets the 1-based position of the focused match and the match count.
+    pub fn search_status(&self) -> Option<(Option<usize>, usize)> {
+        self.search.as_ref().map(|search| {
+            (
+                search.focused_index().map(|index| index + 1),
+                search.matches().len(),
+            )
+        })
+    }
+
+    /// Scrolls the focused match into the middle of the viewport unless it
+    /// is already visible.
+    fn scroll_to_focused_match(&mut self) {
+        let Some(line) = self
+            .search
+            .as_ref()
+            .and_then(|search| search.focused_match())
+            .map(|m| m.start().line.0)
+        else {
+            return;
+        };
+
+        let rows = self.term.screen_lines() as i32;
+        let top = -(self.display_offset() as i32);
+        if line < top || line >= top + rows {
+            let offset = (rows / 2 - line).clamp(0, self.term.grid().history_size() as i32);
+            self.scroll_to_offset(offset as usize);
+        }
     }
 
     /// Checks if the terminal session is still alive.
@@ -371,6 +633,11 @@ impl TerminalSession {
         self.is_process_alive
     }
 
+    /// Returns how the shell process ended, or None while it is running.
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        self.exit_status
+    }
+
     /// Kills the terminal session.
     /// Sends shutdown command to the background thread.
     pub fn kill(&mut self) -> Result<()> {
@@ -507,6 +774,163 @@ mod tests {
         assert!(result.unwrap(), "Should have received output");
     }
 
+    #[test]
+    fn test_shared_session_keeps_output_between_views() {
+        let temp_dir = std::env::temp_dir();
+        let session = TerminalSession::new_shared(temp_dir, Some("/bin/sh".to_string()), 24, 80)
+            .expect("Failed to create terminal session");
+
+        // First view writes a command and goes away
+        let first_view = Arc::clone(&session);
+        first_view
+            .lock()
+            .unwrap()
+            .write_input(b"echo still-here\n")
+            .expect("Failed to write input");
+        drop(first_view);
+
+        // Output is processed in the background while no view is attached
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert!(session.lock().unwrap().process_events().is_some());
+
+        // A view attached later sees the same terminal contents
+        let second_view = Arc::clone(&session);
+        let content = second_view.lock().unwrap().get_visible_content();
+        assert!(content.iter().any(|line| line.contains("still-here")));
+    }
+
+    #[test]
+    fn test_terminal_session_tracks_keyboard_modes() {
+        let temp_dir = std::env::temp_dir();
+        let mut session = TerminalSession::new(temp_dir, Some("/bin/sh".to_string()), 24, 80)
+            .expect("Failed to create terminal session");
+        assert!(!session.mode().contains(TermMode::APP_CURSOR));
+
+        // Enable application cursor keys and push kitty keyboard flags
+        session
+            .write_input(b"printf '\\033[?1h\\033[>1u'\n")
+            .expect("Failed to write input");
+        let expected = TermMode::APP_CURSOR | TermMode::DISAMBIGUATE_ESC_CODES;
+        for _ in 0..50 {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            session.process_events();
+            if session.mode().contains(expected) {
+                break;
+            }
+        }
+        assert!(session.mode().contains(expected));
+    }
+
+    #[test]
+    fn test_terminal_session_selection() {
+        let temp_dir = std::env::temp_dir();
+        let mut session = TerminalSession::new(temp_dir, Some("/bin/sh".to_string()), 24, 80)
+            .expect("Failed to create terminal session");
+
+        session
+            .write_input(b"printf '\\n%s %s\\n' alpha beta\n")
+            .expect("Failed to write input");
+        // Wait for the shell to print the line, which may take a while under load.
+        // The leading newline keeps a late prompt off the output line.
+        let mut row = None;
+        for _ in 0..50 {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            session.process_events();
+            row = session
+                .get_visible_content()
+                .iter()
+                .position(|line| line.trim_end() == "alpha beta");
+            if row.is_some() {
+                break;
+            }
+        }
+        let row = row.expect("Output line should be visible");
+
+        // Double click selects the word under the pointer
+        session.start_selection(SelectionType::Semantic, row, 1, Side::Left);
+        assert_eq!(session.selection_text().as_deref(), Some("alpha"));
+
+        // Triple click selects the whole line
+        session.start_selection(SelectionType::Lines, row, 1, Side::Left);
+        assert_eq!(session.selection_text().as_deref(), Some("alpha beta\n"));
+
+        // Dragging extends a simple selection
+        session.start_selection(SelectionType::Simple, row, 2, Side::Left);
+        session.update_selection(row, 7, Side::Right);
+        assert_eq!(session.selection_text().as_deref(), Some("pha be"));
+
+        session.clear_selection();
+        assert!(!session.has_selection());
+        assert_eq!(session.selection_text(), None);
+    }
+
+    #[test]
+    fn test_terminal_session_scrollback() {
+        let temp_dir = std::env::temp_dir();
+        let mut session = TerminalSession::new(temp_dir, Some("/bin/sh".to_string()), 24, 80)
+            .expect("Failed to create terminal session");
+
+        session
+            .write_input(b"i=0; while [ $i -lt 100 ]; do echo line-$i; i=$((i+1)); done\n")
+            .expect("Failed to write input");
+        for _ in 0..50 {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            session.process_events();
+            if session.get_visible_content().iter().any(|line| line.starts_with("line-99")) {
+                break;
+            }
+        }
+        let history = session.term.grid().history_size();
+        assert!(history >= 70, "Output should have scrolled into history");
+
+        session.scroll_lines(10);
+        assert_eq!(session.display_offset(), 10);
+        session.scroll_page_up();
+        assert_eq!(session.display_offset(), 10 + 24);
+        session.scroll_to_offset(5);
+        assert_eq!(session.display_offset(), 5);
+        session.scroll_to_top();
+        assert_eq!(session.display_offset(), history);
+
+        // The rendered content follows the viewport
+        let content = session.get_renderable_content();
+        assert_eq!(content.display_offset, history);
+        assert_eq!(content.history_size, history);
+        assert!(!content.cursor_visible);
+
+        session.scroll_to_bottom();
+        assert_eq!(session.display_offset(), 0);
+    }
+
+    #[test]
+    fn test_terminal_session_reports_exit_status() {
+        let temp_dir = std::env::temp_dir();
+        let mut session = TerminalSession::new(temp_dir, Some("/bin/sh".to_string()), 24, 80)
+            .expect("Failed to create terminal session");
+
+        session
+            .write_input(b"printf '\\nlast-%s\\n' words; exit 5\n")
+            .expect("Failed to write input");
+        let mut exited = false;
+        for _ in 0..100 {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            if session.process_events().is_none() {
+                exited = true;
+                break;
+            }
+        }
+
+        assert!(exited, "Shell should have exited");
+        assert!(!session.is_alive());
+        assert_eq!(session.exit_status(), Some(ExitStatus::Exited(5)));
+        // Output written just before exiting is not lost. The leading newline
+        // keeps a late prompt off the output line.
+        assert!(session
+            .get_visible_content()
+            .iter()
+            .any(|line| line.trim_end() == "last-words"));
+    }
+
     #[test]
     fn test_terminal_session_resize() {
         let temp_dir = std::env::temp_dir();
@@ -0,0 +1,334 @@
+use crate::ui::theme::Theme;
+use crate::workspace::bootstrap::{self, BootstrapCancel, BootstrapConfig, BootstrapEvent};
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use std::path::PathBuf;
+use std::thread;
+
+/// Monospace font used for command output
+const LOG_FONT: &str = "JetBrains Mono";
+
+/// Progress of a worktree bootstrap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootstrapStatus {
+    Running,
+    Succeeded,
+    Failed(String),
+}
+
+/// Emitted once the worktree can be handed to the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BootstrapReady;
+
+/// Message sent from the bootstrap thread to the view
+enum BootstrapMessage {
+    Event(BootstrapEvent),
+    Finished(Result<(), String>),
+}
+
+/// A line of the bootstrap log
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LogLine {
+    Step(String),
+    Command(String),
+    Output(String),
+}
+
+/// Log panel showing the bootstrap of a new worktree
+///
+/// Copies files, runs the setup commands of `.maestro.json` on a background
+/// thread and streams their output. The worktree is only handed over once
+/// everything succeeded or the user chose to continue after a failure.
+pub struct BootstrapView {
+    root_path: PathBuf,
+    worktree_path: PathBuf,
+    config: BootstrapConfig,
+    lines: Vec<LogLine>,
+    status: BootstrapStatus,
+    /// Stops the bootstrap thread of the current run
+    cancel: BootstrapCancel,
+    theme: Theme,
+}
+
+impl EventEmitter<BootstrapReady> for BootstrapView {}
+
+impl BootstrapView {
+    /// Start bootstrapping a worktree
+    ///
+    /// # Arguments
+    ///
+    /// * `root_path` - Path to the root worktree files are copied from
+    /// * `worktree_path` - Path to the new worktree
+    /// * `config` - Bootstrap steps from `.maestro.json`
+    /// * `cx` - GPUI context
+    pub fn new(
+        root_path: PathBuf,
+        worktree_path: PathBuf,
+        config: BootstrapConfig,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut view = Self {
+            root_path,
+            worktree_path,
+            config,
+            lines: Vec::new(),
+            status: BootstrapStatus::Running,
+            cancel: BootstrapCancel::default(),
+            theme: Theme::new(),
+        };
+        view.start(cx);
+        view
+    }
+
+    /// Current progress
+    pub fn status(&self) -> &BootstrapStatus {
+        &self.status
+    }
+
+    /// Stop the bootstrap, terminating the running setup command
+    ///
+    /// Used when the worktree is removed, so no command keeps running in a
+    /// deleted directory.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Run the bootstrap on its own thread and stream its progress
+    fn start(&mut self, cx: &mut Context<Self>) {
+        self.lines.clear();
+        self.status = BootstrapStatus::Running;
+        self.cancel = BootstrapCancel::default();
+
+        let (tx, rx) = async_channel::unbounded();
+        let root_path = self.root_path.clone();
+        let worktree_path = self.worktree_path.clone();
+        let config = self.config.clone();
+        let cancel = self.cancel.clone();
+
+        thread::spawn(move || {
+            let result =
+                bootstrap::run_bootstrap(&root_path, &worktree_path, &config, &cancel, |event| {
+                    let _ = tx.try_send(BootstrapMessage::Event(event));
+                });
+            let _ = tx.try_send(BootstrapMessage::Finished(
+                result.map_err(|e| format!("{:#}", e)),
+            ));
+        });
+
+        cx.spawn(async move |this, cx| {
+            while let Ok(message) = rx.recv().await {
+                // Output that arrived together is applied in one update
+                let mut messages = vec![message];
+                while let Ok(message) = rx.try_recv() {
+                    messages.push(message);
+                }
+                let running = this.update(cx, |view, cx| view.apply_messages(messages, cx));
+                if !matches!(running, Ok(true)) {
+                    return;
+                }
+            }
+            // The thread ended without reporting a result
+            let _ = this.update(cx, |view, cx| {
+                view.finish(Err("Bootstrap stopped unexpectedly".to_string()), cx)
+            });
+        })
+        .detach();
+    }
+
+    /// Apply messages from the bootstrap thread, returning whether bootstrap
+    /// is still running
+    fn apply_messages(&mut self, messages: Vec<BootstrapMessage>, cx: &mut Context<Self>) -> bool {
+        for message in messages {
+            match message {
+                BootstrapMessage::Event(event) => self.lines.push(log_line(event)),
+                BootstrapMessage::Finished(result) => {
+                    self.finish(result, cx);
+                    return false;
+                }
+            }
+        }
+
+        cx.notify();
+        true
+    }
+
+    fn finish(&mut self, result: Result<(), String>, cx: &mut Context<Self>) {
+        match result {
+            Ok(()) => {
+                println!("Bootstrapped worktree: {}", self.worktree_path.display());
+                self.status = BootstrapStatus::Succeeded;
+                cx.emit(BootstrapReady);
+            }
+            Err(e) => {
+                eprintln!(
+                    "Bootstrap failed for {}: {}",
+                    self.worktree_path.display(),
+                    e
+                );
+                self.status = BootstrapStatus::Failed(e);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Run the bootstrap again after a failure
+    fn handle_retry(&mut self, cx: &mut Context<Self>) {
+        if self.status == BootstrapStatus::Running {
+            return;
+        }
+        self.start(cx);
+        cx.notify();
+    }
+
+    /// Hand the worktree over despite the failure
+    fn handle_continue(&mut self, cx: &mut Context<Self>) {
+        cx.emit(BootstrapReady);
+    }
+
+    fn render_header(&self) -> Div {
+        let (title, color) = match &self.status {
+            BootstrapStatus::Running => ("Setting up worktree…", self.theme.text_primary),
+            BootstrapStatus::Succeeded => ("Worktree ready", self.theme.accent),
+            BootstrapStatus::Failed(_) => ("Worktree setup failed", self.theme.error),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .px_4()
+            .py_3()
+            .bg(self.theme.bg_surface)
+            .border_b_1()
+            .border_color(self.theme.border_subtle)
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(color)
+                    .child(title),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .child(self.worktree_path.display().to_string()),
+            )
+    }
+
+    fn render_log(&self) -> Stateful<Div> {
+        div()
+            .id("bootstrap-log")
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .overflow_y_scroll()
+            .px_4()
+            .py_2()
+            .font_family(LOG_FONT)
+            .text_xs()
+            .children(self.lines.iter().map(|line| {
+                let (text, color) = match line {
+                    LogLine::Step(text) => (text.clone(), self.theme.text_secondary),
+                    LogLine::Command(command) => (format!("$ {}", command), self.theme.accent),
+                    LogLine::Output(text) => (text.clone(), self.theme.text_primary),
+                };
+                div().text_color(color).child(text)
+            }))
+    }
+
+    fn render_failure(&self, error: &str, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .px_4()
+            .py_3()
+            .border_t_1()
+            .border_color(self.theme.border_subtle)
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(hsla(0.0, 0.7, 0.6, 1.0))
+                    .child(error.to_string()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .child(self.render_button("Retry", true, cx, |view, cx| view.handle_retry(cx)))
+                    .child(
+                        self.render_button("Open terminal anyway", false, cx, |view, cx| {
+                            view.handle_continue(cx)
+                        }),
+                    ),
+            )
+    }
+
+    fn render_button(
+        &self,
+        label: &'static str,
+        primary: bool,
+        cx: &mut Context<Self>,
+        on_click: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+    ) -> Div {
+        div()
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .text_xs()
+            .cursor_pointer()
+            .when(primary, |this| {
+                this.bg(self.theme.accent)
+                    .text_color(self.theme.bg_primary)
+                    .hover(|style| style.bg(self.theme.accent_hover))
+            })
+            .when(!primary, |this| {
+                this.border_1()
+                    .border_color(self.theme.border_subtle)
+                    .text_color(self.theme.text_secondary)
+                    .hover(|style| style.bg(self.theme.bg_hover))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |view, _event: &MouseDownEvent, _window, cx| {
+                    on_click(view, cx);
+                }),
+            )
+            .child(label)
+    }
+}
+
+impl Render for BootstrapView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let failure = match &self.status {
+            BootstrapStatus::Failed(error) => Some(error.clone()),
+            _ => None,
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(self.theme.bg_primary)
+            .child(self.render_header())
+            .child(self.render_log())
+            .when_some(failure, |this, error| {
+                this.child(self.render_failure(&error, cx))
+            })
+    }
+}
+
+fn log_line(event: BootstrapEvent) -> LogLine {
+    match event {
+        BootstrapEvent::Copied(path) => LogLine::Step(format!("Copied {}", path.display())),
+        BootstrapEvent::Linked(path) => LogLine::Step(format!("Linked {}", path.display())),
+        BootstrapEvent::Skipped { path, reason } => {
+            LogLine::Step(format!("Skipped {}: {}", path.display(), reason))
+        }
+        BootstrapEvent::CommandStarted(command) => LogLine::Command(command),
+        BootstrapEvent::Output(text) => LogLine::Output(text),
+    }
+}
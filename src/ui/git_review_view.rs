@@ -0,0 +1,1516 @@
+use crate::git::Worktree;
+use crate::git::commit::{self, CommitConfig, CommitOutcome, CommitRequest};
+use crate::git::diff::{self, DiffHunk, DiffLine, FileChange, WorktreeChanges};
+use crate::git::staging;
+use crate::ui::theme::Theme;
+use crate::ui::workspace_watcher::WorkspaceWatcher;
+use crate::workspace::watcher::WatchEvent;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use std::ops::Range;
+use std::path::{Component, Path};
+
+/// Monospace font used for diff content
+const DIFF_FONT: &str = "JetBrains Mono";
+
+/// Width of the changed-file list
+const FILE_LIST_WIDTH: f32 = 300.0;
+
+/// Minimum height of the commit message box
+const COMMIT_MESSAGE_MIN_HEIGHT: f32 = 72.0;
+
+/// How the diff of the selected file is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffViewMode {
+    Unified,
+    SideBySide,
+}
+
+impl DiffViewMode {
+    /// The other mode, used by the toggle button
+    pub fn toggled(self) -> Self {
+        match self {
+            DiffViewMode::Unified => DiffViewMode::SideBySide,
+            DiffViewMode::SideBySide => DiffViewMode::Unified,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            DiffViewMode::Unified => "Unified",
+            DiffViewMode::SideBySide => "Side by side",
+        }
+    }
+}
+
+/// Identifies a file in either the staged or unstaged list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileSelection {
+    pub staged: bool,
+    pub index: usize,
+}
+
+/// What a staging button does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StagingAction {
+    Stage,
+    Unstage,
+    Discard,
+}
+
+impl StagingAction {
+    fn label(self) -> &'static str {
+        match self {
+            StagingAction::Stage => "Stage",
+            StagingAction::Unstage => "Unstage",
+            StagingAction::Discard => "Discard",
+        }
+    }
+}
+
+/// Part of a file a staging action applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StagingScope {
+    File,
+    Hunk(usize),
+    /// A range of `DiffHunk::lines` within one hunk
+    Lines {
+        hunk: usize,
+        lines: Range<usize>,
+    },
+}
+
+/// Lines picked in the unified diff, from the clicked line to the line
+/// shift-clicked last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineSelection {
+    pub hunk: usize,
+    pub anchor: usize,
+    pub head: usize,
+}
+
+impl LineSelection {
+    /// Selected indexes into `DiffHunk::lines`
+    pub fn lines(&self) -> Range<usize> {
+        self.anchor.min(self.head)..self.anchor.max(self.head) + 1
+    }
+
+    /// Whether a line of the diff is selected
+    pub fn contains(&self, hunk: usize, line: usize) -> bool {
+        self.hunk == hunk && self.lines().contains(&line)
+    }
+}
+
+/// Run a staging action on part of a changed file
+fn apply_staging(
+    worktree: &Worktree,
+    change: &FileChange,
+    action: StagingAction,
+    scope: StagingScope,
+) -> anyhow::Result<()> {
+    match (action, scope) {
+        (StagingAction::Stage, StagingScope::File) => staging::stage_file(worktree, change),
+        (StagingAction::Unstage, StagingScope::File) => staging::unstage_file(worktree, change),
+        (StagingAction::Discard, StagingScope::File) => staging::discard_file(worktree, change),
+        (StagingAction::Stage, StagingScope::Hunk(hunk)) => {
+            staging::stage_hunk(worktree, change, hunk)
+        }
+        (StagingAction::Unstage, StagingScope::Hunk(hunk)) => {
+            staging::unstage_hunk(worktree, change, hunk)
+        }
+        (StagingAction::Discard, StagingScope::Hunk(hunk)) => {
+            staging::discard_hunk(worktree, change, hunk)
+        }
+        (StagingAction::Stage, StagingScope::Lines { hunk, lines }) => {
+            staging::stage_lines(worktree, change, hunk, lines)
+        }
+        (StagingAction::Unstage, StagingScope::Lines { hunk, lines }) => {
+            staging::unstage_lines(worktree, change, hunk, lines)
+        }
+        (StagingAction::Discard, StagingScope::Lines { hunk, lines }) => {
+            staging::discard_lines(worktree, change, hunk, lines)
+        }
+    }
+}
+
+/// Result of the last commit attempt, shown below the composer
+#[derive(Debug, Clone, PartialEq)]
+enum CommitFeedback {
+    Committed(CommitOutcome),
+    Failed(String),
+}
+
+/// A row of the changed-file tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileTreeEntry {
+    /// A directory header
+    Directory { name: String, depth: usize },
+    /// A changed file, pointing back into the change list
+    File {
+        name: String,
+        depth: usize,
+        index: usize,
+    },
+}
+
+/// A row of the side-by-side diff: old line on the left, new line on the right
+#[derive(Debug, Clone, PartialEq)]
+pub struct SideBySideRow<'a> {
+    pub left: Option<&'a DiffLine>,
+    pub right: Option<&'a DiffLine>,
+}
+
+/// Group a list of changed files into a directory tree, sorted by path
+///
+/// Directory headers are emitted once per directory, followed by the files and
+/// subdirectories they contain.
+pub fn build_file_tree(changes: &[FileChange]) -> Vec<FileTreeEntry> {
+    let mut sorted: Vec<(usize, &FileChange)> = changes.iter().enumerate().collect();
+    sorted.sort_by(|a, b| a.1.path.cmp(&b.1.path));
+
+    let mut entries = Vec::new();
+    let mut open_dirs: Vec<String> = Vec::new();
+
+    for (index, change) in sorted {
+        let dirs: Vec<String> = change
+            .path
+            .parent()
+            .map(path_components)
+            .unwrap_or_default();
+
+        // Keep the directories shared with the previous file, open the rest
+        let shared = open_dirs
+            .iter()
+            .zip(dirs.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        open_dirs.truncate(shared);
+
+        for dir in dirs.iter().skip(shared) {
+            entries.push(FileTreeEntry::Directory {
+                name: dir.clone(),
+                depth: open_dirs.len(),
+            });
+            open_dirs.push(dir.clone());
+        }
+
+        let name = change
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| change.path.display().to_string());
+
+        entries.push(FileTreeEntry::File {
+            name,
+            depth: open_dirs.len(),
+            index,
+        });
+    }
+
+    entries
+}
+
+fn path_components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Pair the lines of a hunk for side-by-side display
+///
+/// Context lines appear on both sides. A run of removals followed by a run of
+/// additions is zipped together so that replaced lines line up.
+pub fn side_by_side_rows(hunk: &DiffHunk) -> Vec<SideBySideRow<'_>> {
+    let mut rows = Vec::with_capacity(hunk.lines.len());
+    let mut removed: Vec<&DiffLine> = Vec::new();
+    let mut added: Vec<&DiffLine> = Vec::new();
+
+    fn flush<'a>(
+        rows: &mut Vec<SideBySideRow<'a>>,
+        removed: &mut Vec<&'a DiffLine>,
+        added: &mut Vec<&'a DiffLine>,
+    ) {
+        let count = removed.len().max(added.len());
+        for i in 0..count {
+            rows.push(SideBySideRow {
+                left: removed.get(i).copied(),
+                right: added.get(i).copied(),
+            });
+        }
+        removed.clear();
+        added.clear();
+    }
+
+    for line in &hunk.lines {
+        if line.is_deletion() {
+            // A removal after additions starts a new replacement block
+            if !added.is_empty() {
+                flush(&mut rows, &mut removed, &mut added);
+            }
+            removed.push(line);
+        } else if line.is_addition() {
+            added.push(line);
+        } else if line.is_context() {
+            flush(&mut rows, &mut removed, &mut added);
+            rows.push(SideBySideRow {
+                left: Some(line),
+                right: Some(line),
+            });
+        }
+        // End-of-file newline markers are not shown
+    }
+    flush(&mut rows, &mut removed, &mut added);
+
+    rows
+}
+
+/// Git review tab: changed-file tree on the left, diff of the selected file on the right
+///
+/// Files, hunks and lines picked in the unified diff can be staged, unstaged
+/// or discarded from the file rows and hunk headers.
+pub struct GitReviewView {
+    worktree: Worktree,
+    changes: WorktreeChanges,
+    selected: Option<FileSelection>,
+    view_mode: DiffViewMode,
+    error: Option<String>,
+    /// Whether changes are being loaded in the background
+    reloading: bool,
+    /// Whether another reload was requested while one was running
+    reload_queued: bool,
+    // Staging state
+    line_selection: Option<LineSelection>,
+    /// Discard waiting for a second click, since it can't be undone
+    pending_discard: Option<(FileSelection, StagingScope)>,
+    staging: bool,
+    staging_error: Option<String>,
+    // Commit composer state
+    commit_message: String,
+    amend: bool,
+    committing: bool,
+    commit_config: Option<CommitConfig>,
+    commit_feedback: Option<CommitFeedback>,
+    message_focus: FocusHandle,
+    theme: Theme,
+    _watch_subscription: Subscription,
+}
+
+impl GitReviewView {
+    /// Create a review view for a worktree and load its changes
+    ///
+    /// Changes are reloaded whenever `watcher` reports a change in the worktree.
+    pub fn new(
+        worktree: Worktree,
+        watcher: &Entity<WorkspaceWatcher>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let watch_subscription = cx.subscribe(watcher, |view, _watcher, event, cx| {
+            if let WatchEvent::WorktreeChanged(path) = event
+                && *path == view.worktree.path
+            {
+                view.refresh(cx);
+            }
+        });
+
+        let mut view = Self {
+            worktree,
+            changes: WorktreeChanges::default(),
+            selected: None,
+            view_mode: DiffViewMode::Unified,
+            error: None,
+            reloading: false,
+            reload_queued: false,
+            line_selection: None,
+            pending_discard: None,
+            staging: false,
+            staging_error: None,
+            commit_message: String::new(),
+            amend: false,
+            committing: false,
+            commit_config: None,
+            commit_feedback: None,
+            message_focus: cx.focus_handle(),
+            theme: Theme::new(),
+            _watch_subscription: watch_subscription,
+        };
+        view.reload_changes(cx);
+        view
+    }
+
+    /// The worktree this view is reviewing
+    pub fn worktree(&self) -> &Worktree {
+        &self.worktree
+    }
+
+    /// Reload changes from git and re-render
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.reload_changes(cx);
+    }
+
+    /// Switch between unified and side-by-side diffs
+    pub fn toggle_view_mode(&mut self, cx: &mut Context<Self>) {
+        self.view_mode = self.view_mode.toggled();
+        cx.notify();
+    }
+
+    /// Reload changes on the background executor, so diffing a large
+    /// worktree doesn't stall rendering
+    ///
+    /// Reloads requested while one is running are coalesced into a single
+    /// follow-up.
+    fn reload_changes(&mut self, cx: &mut Context<Self>) {
+        if self.reloading {
+            self.reload_queued = true;
+            return;
+        }
+        self.reloading = true;
+
+        let worktree = self.worktree.clone();
+        let task = cx.background_executor().spawn(async move {
+            // Config errors surface when committing, so only the signing hint is lost here
+            let config = commit::read_commit_config(&worktree).ok();
+            (diff::get_changes(&worktree), config)
+        });
+
+        cx.spawn(async move |this, cx| {
+            let (changes, config) = task.await;
+            let _ = this.update(cx, |view, cx| view.finish_reload(changes, config, cx));
+        })
+        .detach();
+    }
+
+    /// Apply reloaded changes, keeping the selected file selected if it still has changes
+    fn finish_reload(
+        &mut self,
+        changes: anyhow::Result<WorktreeChanges>,
+        commit_config: Option<CommitConfig>,
+        cx: &mut Context<Self>,
+    ) {
+        self.reloading = false;
+        let previous_path = self.selected_change().map(|c| (c.staged, c.path.clone()));
+
+        let changes = match changes {
+            Ok(changes) => {
+                self.error = None;
+                changes
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to load changes: {}", e));
+                WorktreeChanges::default()
+            }
+        };
+        // Line numbers and file indexes of a pick no longer apply
+        if changes != self.changes {
+            self.line_selection = None;
+            self.pending_discard = None;
+        }
+        self.changes = changes;
+
+        self.selected = previous_path
+            .and_then(|(staged, path)| {
+                self.changes_for(staged)
+                    .iter()
+                    .position(|c| c.path == path)
+                    .map(|index| FileSelection { staged, index })
+            })
+            .or_else(|| self.first_file());
+        self.commit_config = commit_config;
+        cx.notify();
+
+        if self.reload_queued {
+            self.reload_queued = false;
+            self.reload_changes(cx);
+        }
+    }
+
+    fn first_file(&self) -> Option<FileSelection> {
+        if !self.changes.unstaged.is_empty() {
+            Some(FileSelection {
+                staged: false,
+                index: 0,
+            })
+        } else if !self.changes.staged.is_empty() {
+            Some(FileSelection {
+                staged: true,
+                index: 0,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn changes_for(&self, staged: bool) -> &[FileChange] {
+        if staged {
+            &self.changes.staged
+        } else {
+            &self.changes.unstaged
+        }
+    }
+
+    /// The currently selected file change
+    fn selected_change(&self) -> Option<&FileChange> {
+        let selection = self.selected?;
+        self.changes_for(selection.staged).get(selection.index)
+    }
+
+    /// Handle a click on a file in the tree
+    fn select_file(&mut self, selection: FileSelection, cx: &mut Context<Self>) {
+        if self.selected != Some(selection) {
+            self.line_selection = None;
+            self.pending_discard = None;
+        }
+        self.selected = Some(selection);
+        cx.notify();
+    }
+
+    /// Handle a click on a diff line, extending the pick with shift
+    fn select_line(&mut self, hunk: usize, line: usize, extend: bool, cx: &mut Context<Self>) {
+        self.line_selection = match self.line_selection {
+            Some(selection) if extend && selection.hunk == hunk => Some(LineSelection {
+                head: line,
+                ..selection
+            }),
+            // Clicking the only picked line again clears the pick
+            Some(selection) if selection.lines() == (line..line + 1) && selection.hunk == hunk => {
+                None
+            }
+            _ => Some(LineSelection {
+                hunk,
+                anchor: line,
+                head: line,
+            }),
+        };
+        self.pending_discard = None;
+        cx.notify();
+    }
+
+    /// Stage, unstage or discard part of a file in the background, then
+    /// reload the changes
+    ///
+    /// Discarding only runs on the second click of the same button.
+    fn handle_staging(
+        &mut self,
+        selection: FileSelection,
+        action: StagingAction,
+        scope: StagingScope,
+        cx: &mut Context<Self>,
+    ) {
+        if self.staging {
+            return;
+        }
+        let Some(change) = self
+            .changes_for(selection.staged)
+            .get(selection.index)
+            .cloned()
+        else {
+            return;
+        };
+
+        if action == StagingAction::Discard {
+            let target = (selection, scope.clone());
+            if self.pending_discard.as_ref() != Some(&target) {
+                self.pending_discard = Some(target);
+                cx.notify();
+                return;
+            }
+        }
+
+        self.pending_discard = None;
+        self.staging = true;
+        self.staging_error = None;
+        cx.notify();
+
+        let worktree = self.worktree.clone();
+        let task = cx
+            .background_executor()
+            .spawn(async move { apply_staging(&worktree, &change, action, scope) });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            let _ = this.update(cx, |view, cx| view.finish_staging(result, cx));
+        })
+        .detach();
+    }
+
+    /// Show a staging error and reload the changes
+    fn finish_staging(&mut self, result: anyhow::Result<()>, cx: &mut Context<Self>) {
+        self.staging = false;
+        match result {
+            Ok(()) => self.line_selection = None,
+            Err(e) => self.staging_error = Some(format!("{:#}", e)),
+        }
+        self.reload_changes(cx);
+        cx.notify();
+    }
+
+    /// Toggle amending HEAD, prefilling the message with the HEAD message
+    fn toggle_amend(&mut self, cx: &mut Context<Self>) {
+        self.amend = !self.amend;
+        if self.amend && self.commit_message.trim().is_empty() {
+            match commit::head_commit_message(&self.worktree) {
+                Ok(Some(message)) => self.commit_message = message,
+                Ok(None) => {}
+                Err(e) => self.commit_feedback = Some(CommitFeedback::Failed(e.to_string())),
+            }
+        }
+        cx.notify();
+    }
+
+    /// Commit the index in the background so slow hooks don't block the UI
+    fn handle_commit(&mut self, cx: &mut Context<Self>) {
+        if self.committing {
+            return;
+        }
+
+        self.committing = true;
+        self.commit_feedback = None;
+        cx.notify();
+
+        let worktree = self.worktree.clone();
+        let request = CommitRequest {
+            message: self.commit_message.clone(),
+            amend: self.amend,
+        };
+        let task = cx
+            .background_executor()
+            .spawn(async move { commit::create_commit(&worktree, &request) });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            let _ = this.update(cx, |view, cx| view.finish_commit(result, cx));
+        })
+        .detach();
+    }
+
+    /// Show the commit result and reload changes after a successful commit
+    fn finish_commit(&mut self, result: anyhow::Result<CommitOutcome>, cx: &mut Context<Self>) {
+        self.committing = false;
+        match result {
+            Ok(outcome) => {
+                self.commit_message.clear();
+                self.amend = false;
+                self.commit_feedback = Some(CommitFeedback::Committed(outcome));
+                self.reload_changes(cx);
+            }
+            Err(e) => {
+                self.commit_feedback = Some(CommitFeedback::Failed(e.to_string()));
+            }
+        }
+        cx.notify();
+    }
+
+    /// Handle typing in the commit message box
+    fn handle_message_key(
+        &mut self,
+        event: &KeyDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+
+        match keystroke.key.as_str() {
+            "enter" if keystroke.modifiers.secondary() => {
+                self.handle_commit(cx);
+                return;
+            }
+            "enter" => self.commit_message.push('\n'),
+            "backspace" => {
+                self.commit_message.pop();
+            }
+            _ => {
+                if keystroke.modifiers.control || keystroke.modifiers.platform {
+                    return;
+                }
+                let Some(text) = keystroke.key_char.as_ref() else {
+                    return;
+                };
+                self.commit_message.push_str(text);
+            }
+        }
+
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    /// Render the left pane: file tree above the commit composer
+    fn render_left_pane(&self, window: &Window, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .flex_col()
+            .w(px(FILE_LIST_WIDTH))
+            .h_full()
+            .flex_shrink_0()
+            .bg(self.theme.bg_surface)
+            .border_r_1()
+            .border_color(self.theme.border_subtle)
+            .when_some(self.staging_error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .px_4()
+                        .py_2()
+                        .text_xs()
+                        .text_color(self.theme.error)
+                        .child(error),
+                )
+            })
+            .child(self.render_file_list(cx))
+            .child(self.render_commit_composer(window, cx))
+    }
+
+    /// Render the staged and unstaged sections of the file tree
+    fn render_file_list(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .id("review-file-list")
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_h_0()
+            .overflow_y_scroll()
+            .py_2()
+            .child(self.render_file_section("STAGED", true, cx))
+            .child(self.render_file_section("CHANGES", false, cx))
+    }
+
+    /// Render the commit message box, amend toggle and commit button
+    fn render_commit_composer(&self, window: &Window, cx: &mut Context<Self>) -> Div {
+        let is_focused = self.message_focus.is_focused(window);
+        let can_commit = !self.committing
+            && !self.commit_message.trim().is_empty()
+            && (self.amend || !self.changes.staged.is_empty());
+
+        let message = if self.commit_message.is_empty() {
+            div()
+                .text_color(self.theme.text_tertiary)
+                .child("Commit message (CMD+ENTER to commit)")
+        } else {
+            let mut lines = div().flex().flex_col().text_color(self.theme.text_primary);
+            let line_count = self.commit_message.split('\n').count();
+            for (idx, line) in self.commit_message.split('\n').enumerate() {
+                let is_last = idx + 1 == line_count;
+                let text = if is_last && is_focused {
+                    format!("{}▏", line)
+                } else {
+                    line.to_string()
+                };
+                // Keep empty lines at full height
+                lines = lines.child(div().min_h(px(16.0)).child(text));
+            }
+            lines
+        };
+
+        let signing_hint = self
+            .commit_config
+            .as_ref()
+            .filter(|config| config.sign)
+            .map(|config| format!("Signed ({})", config.signing_format.label()));
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_2()
+            .border_t_1()
+            .border_color(self.theme.border_subtle)
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .px_2()
+                    .child("COMMIT"),
+            )
+            .child(
+                div()
+                    .id("commit-message")
+                    .min_h(px(COMMIT_MESSAGE_MIN_HEIGHT))
+                    .max_h(px(COMMIT_MESSAGE_MIN_HEIGHT * 3.0))
+                    .overflow_y_scroll()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .bg(self.theme.bg_primary)
+                    .border_1()
+                    .border_color(if is_focused {
+                        self.theme.accent
+                    } else {
+                        self.theme.border_subtle
+                    })
+                    .text_sm()
+                    .cursor_text()
+                    .track_focus(&self.message_focus)
+                    .on_key_down(cx.listener(Self::handle_message_key))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event: &MouseDownEvent, window, cx| {
+                            this.message_focus.focus(window);
+                            cx.notify();
+                        }),
+                    )
+                    .child(message),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(self.theme.bg_hover))
+                                    .text_xs()
+                                    .text_color(if self.amend {
+                                        self.theme.text_primary
+                                    } else {
+                                        self.theme.text_secondary
+                                    })
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(
+                                            |this, _event: &MouseDownEvent, _window, cx| {
+                                                this.toggle_amend(cx);
+                                            },
+                                        ),
+                                    )
+                                    .child(if self.amend { "☑ Amend" } else { "☐ Amend" }),
+                            )
+                            .when_some(signing_hint, |this, hint| {
+                                this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(self.theme.text_tertiary)
+                                        .child(hint),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .text_xs()
+                            .when(can_commit, |this| {
+                                this.bg(self.theme.accent)
+                                    .text_color(self.theme.bg_primary)
+                                    .cursor_pointer()
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(
+                                            |this, _event: &MouseDownEvent, _window, cx| {
+                                                this.handle_commit(cx);
+                                            },
+                                        ),
+                                    )
+                            })
+                            .when(!can_commit, |this| {
+                                this.bg(self.theme.bg_hover)
+                                    .text_color(self.theme.text_tertiary)
+                            })
+                            .child(if self.committing {
+                                "Committing…"
+                            } else if self.amend {
+                                "Amend"
+                            } else {
+                                "Commit"
+                            }),
+                    ),
+            )
+            .when_some(self.commit_feedback.clone(), |this, feedback| {
+                this.child(self.render_commit_feedback(feedback))
+            })
+    }
+
+    /// Render the result of the last commit, including hook output
+    fn render_commit_feedback(&self, feedback: CommitFeedback) -> Div {
+        let (title, title_color, output) = match feedback {
+            CommitFeedback::Committed(outcome) => (
+                format!("Committed {} {}", outcome.short_id(), outcome.summary),
+                self.theme.accent,
+                outcome.output,
+            ),
+            CommitFeedback::Failed(error) => {
+                // The first line is the error, the rest is git and hook output
+                let (title, output) = match error.split_once('\n') {
+                    Some((title, output)) => (title.to_string(), output.to_string()),
+                    None => (error, String::new()),
+                };
+                (title, self.theme.error, output)
+            }
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .px_2()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(title_color)
+                    .truncate()
+                    .child(title),
+            )
+            .when(!output.trim().is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .p_2()
+                        .rounded_md()
+                        .bg(self.theme.bg_primary)
+                        .font_family(DIFF_FONT)
+                        .text_xs()
+                        .text_color(self.theme.text_secondary)
+                        .children(output.lines().map(|line| line.to_string())),
+                )
+            })
+    }
+
+    /// Render one section (staged or unstaged) of the file tree
+    fn render_file_section(&self, title: &str, staged: bool, cx: &mut Context<Self>) -> Div {
+        let changes = self.changes_for(staged);
+
+        let mut section = div().flex().flex_col().px_2().mb_2().child(
+            div()
+                .text_xs()
+                .text_color(self.theme.text_tertiary)
+                .px_2()
+                .mb_1()
+                .child(format!("{} ({})", title, changes.len())),
+        );
+
+        for entry in build_file_tree(changes) {
+            section = section.child(match entry {
+                FileTreeEntry::Directory { name, depth } => div()
+                    .pl(px(8.0 + depth as f32 * 12.0))
+                    .py(px(2.0))
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .child(format!("{}/", name)),
+                FileTreeEntry::File { name, depth, index } => {
+                    self.render_file_item(&changes[index], name, depth, staged, index, cx)
+                }
+            });
+        }
+
+        section
+    }
+
+    /// Render a single changed file row
+    fn render_file_item(
+        &self,
+        change: &FileChange,
+        name: String,
+        depth: usize,
+        staged: bool,
+        index: usize,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let selection = FileSelection { staged, index };
+        let is_selected = self.selected == Some(selection);
+        let (added, removed) = change.line_stats();
+
+        let label = match &change.old_path {
+            Some(old_path) => format!(
+                "{} ← {}",
+                name,
+                old_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            ),
+            None => name,
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_2()
+            .pl(px(8.0 + depth as f32 * 12.0))
+            .pr_2()
+            .py(px(2.0))
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |this| this.bg(self.theme.bg_selected))
+            .hover(|style| {
+                if !is_selected {
+                    style.bg(self.theme.bg_hover)
+                } else {
+                    style
+                }
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                    this.select_file(selection, cx);
+                }),
+            )
+            .child(
+                div()
+                    .w(px(12.0))
+                    .text_xs()
+                    .text_color(self.theme.accent)
+                    .child(change.status.short_code().to_string()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .truncate()
+                    .text_sm()
+                    .text_color(if is_selected {
+                        self.theme.text_primary
+                    } else {
+                        self.theme.text_secondary
+                    })
+                    .child(label),
+            )
+            .when(added > 0, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(self.theme.diff_added_text)
+                        .child(format!("+{}", added)),
+                )
+            })
+            .when(removed > 0, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(self.theme.diff_removed_text)
+                        .child(format!("-{}", removed)),
+                )
+            })
+            .child(self.render_staging_buttons(selection, StagingScope::File, cx))
+    }
+
+    /// Render the staging buttons for part of a file: stage or discard for
+    /// unstaged changes, unstage for staged ones
+    fn render_staging_buttons(
+        &self,
+        selection: FileSelection,
+        scope: StagingScope,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let actions: &[StagingAction] = if selection.staged {
+            &[StagingAction::Unstage]
+        } else {
+            &[StagingAction::Discard, StagingAction::Stage]
+        };
+
+        div().flex().flex_row().flex_shrink_0().gap_1().children(
+            actions
+                .iter()
+                .map(|&action| self.render_staging_button(selection, action, scope.clone(), cx)),
+        )
+    }
+
+    fn render_staging_button(
+        &self,
+        selection: FileSelection,
+        action: StagingAction,
+        scope: StagingScope,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let confirming = action == StagingAction::Discard
+            && self.pending_discard.as_ref() == Some(&(selection, scope.clone()));
+        let label = match &scope {
+            _ if confirming => "Confirm discard".to_string(),
+            StagingScope::File => action.label().to_string(),
+            StagingScope::Hunk(_) => format!("{} hunk", action.label()),
+            StagingScope::Lines { .. } => format!("{} lines", action.label()),
+        };
+
+        div()
+            .px_2()
+            .rounded_md()
+            .text_xs()
+            .text_color(if confirming {
+                self.theme.error
+            } else {
+                self.theme.text_secondary
+            })
+            .when(!self.staging, |this| {
+                this.cursor_pointer()
+                    .hover(|style| style.bg(self.theme.bg_hover))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                    // Don't select the file row the button sits in
+                    cx.stop_propagation();
+                    this.handle_staging(selection, action, scope.clone(), cx);
+                }),
+            )
+            .child(label)
+    }
+
+    /// Render the right pane with the diff of the selected file
+    fn render_diff_pane(&self, cx: &mut Context<Self>) -> Div {
+        let container = div().flex().flex_col().flex_1().min_w_0().h_full();
+
+        let Some(change) = self.selected_change() else {
+            let message = if self.changes.is_empty() {
+                "No changes in this worktree"
+            } else {
+                "Select a file to see its changes"
+            };
+            return container.items_center().justify_center().child(
+                div()
+                    .text_sm()
+                    .text_color(self.theme.text_secondary)
+                    .child(message),
+            );
+        };
+
+        let mut body = div()
+            .id("review-diff-pane")
+            .flex()
+            .flex_col()
+            .flex_1()
+            .overflow_y_scroll()
+            .font_family(DIFF_FONT)
+            .text_sm();
+
+        if change.is_binary {
+            body = body.child(
+                div()
+                    .p_4()
+                    .text_color(self.theme.text_secondary)
+                    .child("Binary file changed"),
+            );
+        } else if change.hunks.is_empty() {
+            body = body.child(
+                div()
+                    .p_4()
+                    .text_color(self.theme.text_secondary)
+                    .child("No content changes"),
+            );
+        } else {
+            let selection = self.selected.unwrap_or(FileSelection {
+                staged: change.staged,
+                index: 0,
+            });
+            for (hunk_index, hunk) in change.hunks.iter().enumerate() {
+                body = body.child(self.render_hunk_header(hunk, hunk_index, selection, cx));
+                body = match self.view_mode {
+                    DiffViewMode::Unified => body.children(
+                        hunk.lines
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, line)| {
+                                line.is_addition() || line.is_deletion() || line.is_context()
+                            })
+                            .map(|(line_index, line)| {
+                                self.render_unified_line(line, hunk_index, line_index, cx)
+                            })
+                            .collect::<Vec<_>>(),
+                    ),
+                    DiffViewMode::SideBySide => body.children(
+                        side_by_side_rows(hunk)
+                            .into_iter()
+                            .map(|row| self.render_side_by_side_row(&row)),
+                    ),
+                };
+            }
+        }
+
+        container
+            .child(self.render_diff_header(change, cx))
+            .child(body)
+    }
+
+    /// Render the header above the diff with the file path and the mode toggle
+    fn render_diff_header(&self, change: &FileChange, cx: &mut Context<Self>) -> Div {
+        let path_label = match &change.old_path {
+            Some(old_path) => format!("{} → {}", old_path.display(), change.path.display()),
+            None => change.path.display().to_string(),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .px_4()
+            .py_2()
+            .border_b_1()
+            .border_color(self.theme.border_subtle)
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_2()
+                    .min_w_0()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(self.theme.text_primary)
+                            .truncate()
+                            .child(path_label),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(self.theme.text_tertiary)
+                            .child(if change.staged { "staged" } else { "unstaged" }),
+                    ),
+            )
+            .child(
+                div()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(self.theme.bg_hover)
+                    .cursor_pointer()
+                    .hover(|style| style.bg(self.theme.bg_selected))
+                    .text_xs()
+                    .text_color(self.theme.text_primary)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                            this.toggle_view_mode(cx);
+                        }),
+                    )
+                    .child(self.view_mode.toggled().label()),
+            )
+    }
+
+    /// Render a hunk header row with buttons for the hunk, or for the lines
+    /// picked in it
+    fn render_hunk_header(
+        &self,
+        hunk: &DiffHunk,
+        hunk_index: usize,
+        selection: FileSelection,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let scope = match self.line_selection {
+            Some(picked) if picked.hunk == hunk_index => StagingScope::Lines {
+                hunk: hunk_index,
+                lines: picked.lines(),
+            },
+            _ => StagingScope::Hunk(hunk_index),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .px_4()
+            .py_1()
+            .bg(self.theme.diff_hunk_header_bg)
+            .text_color(self.theme.text_secondary)
+            .whitespace_nowrap()
+            .child(div().min_w_0().truncate().child(hunk.header.clone()))
+            .child(self.render_staging_buttons(selection, scope, cx))
+    }
+
+    /// Render a line number gutter cell
+    fn render_line_number(&self, lineno: Option<u32>) -> Div {
+        div()
+            .w(px(48.0))
+            .flex_shrink_0()
+            .pr_2()
+            .text_right()
+            .text_color(self.theme.text_tertiary)
+            .child(lineno.map(|n| n.to_string()).unwrap_or_default())
+    }
+
+    /// Background and text colors for a diff line
+    fn line_colors(&self, line: &DiffLine) -> (Option<Hsla>, Hsla) {
+        if line.is_addition() {
+            (Some(self.theme.diff_added_bg), self.theme.diff_added_text)
+        } else if line.is_deletion() {
+            (
+                Some(self.theme.diff_removed_bg),
+                self.theme.diff_removed_text,
+            )
+        } else {
+            (None, self.theme.text_secondary)
+        }
+    }
+
+    /// Render a line in unified mode: both gutters, origin and content
+    ///
+    /// Clicking picks the line for staging; shift-click extends the pick.
+    fn render_unified_line(
+        &self,
+        line: &DiffLine,
+        hunk_index: usize,
+        line_index: usize,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let (bg, text_color) = self.line_colors(line);
+        let is_picked = self
+            .line_selection
+            .is_some_and(|picked| picked.contains(hunk_index, line_index));
+        let bg = if is_picked {
+            Some(self.theme.bg_selected)
+        } else {
+            bg
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .w_full()
+            .cursor_pointer()
+            .when_some(bg, |this, bg| this.bg(bg))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event: &MouseDownEvent, _window, cx| {
+                    this.select_line(hunk_index, line_index, event.modifiers.shift, cx);
+                }),
+            )
+            .child(self.render_line_number(line.old_lineno))
+            .child(self.render_line_number(line.new_lineno))
+            .child(
+                div()
+                    .w(px(16.0))
+                    .flex_shrink_0()
+                    .text_color(text_color)
+                    .child(line.origin.to_string()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .whitespace_nowrap()
+                    .text_color(text_color)
+                    .child(line.content.clone()),
+            )
+    }
+
+    /// Render one half of a side-by-side row
+    fn render_side_cell(&self, line: Option<&DiffLine>, is_old_side: bool) -> Div {
+        let cell = div().flex().flex_row().w_1_2().min_w_0().overflow_hidden();
+
+        let Some(line) = line else {
+            return cell.bg(self.theme.bg_surface);
+        };
+
+        let (bg, text_color) = if line.is_context() {
+            (None, self.theme.text_secondary)
+        } else {
+            self.line_colors(line)
+        };
+        let lineno = if is_old_side {
+            line.old_lineno
+        } else {
+            line.new_lineno
+        };
+
+        cell.when_some(bg, |this, bg| this.bg(bg))
+            .child(self.render_line_number(lineno))
+            .child(
+                div()
+                    .flex_1()
+                    .whitespace_nowrap()
+                    .text_color(text_color)
+                    .child(line.content.clone()),
+            )
+    }
+
+    /// Render a side-by-side row with old content on the left and new on the right
+    fn render_side_by_side_row(&self, row: &SideBySideRow<'_>) -> Div {
+        div()
+            .flex()
+            .flex_row()
+            .w_full()
+            .child(self.render_side_cell(row.left, true))
+            .child(div().w(px(1.0)).h_full().bg(self.theme.border_subtle))
+            .child(self.render_side_cell(row.right, false))
+    }
+
+    /// Render the error state
+    fn render_error(&self, error: String) -> Div {
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .items_center()
+            .justify_center()
+            .gap_2()
+            .child(
+                div()
+                    .text_lg()
+                    .text_color(self.theme.text_primary)
+                    .child("Git Error"),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(self.theme.error)
+                    .px_8()
+                    .child(error),
+            )
+    }
+}
+
+impl Render for GitReviewView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if let Some(error) = self.error.clone() {
+            return div()
+                .flex()
+                .size_full()
+                .bg(self.theme.bg_primary)
+                .child(self.render_error(error));
+        }
+
+        div()
+            .flex()
+            .flex_row()
+            .size_full()
+            .bg(self.theme.bg_primary)
+            .child(self.render_left_pane(window, cx))
+            .child(self.render_diff_pane(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ChangeStatus;
+    use std::path::PathBuf;
+
+    fn file_change(path: &str) -> FileChange {
+        FileChange {
+            path: PathBuf::from(path),
+            old_path: None,
+            status: ChangeStatus::Modified,
+            hunks: vec![],
+            staged: false,
+            is_binary: false,
+        }
+    }
+
+    fn line(origin: char, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            raw: format!("{}\n", content).into_bytes(),
+            old_lineno: None,
+            new_lineno: None,
+        }
+    }
+
+    #[test]
+    fn test_line_selection_spans_both_directions() {
+        let selection = LineSelection {
+            hunk: 1,
+            anchor: 5,
+            head: 2,
+        };
+        assert_eq!(selection.lines(), 2..6);
+        assert!(selection.contains(1, 2) && selection.contains(1, 5));
+        assert!(!selection.contains(1, 6) && !selection.contains(0, 3));
+    }
+
+    #[test]
+    fn test_diff_view_mode_toggle() {
+        assert_eq!(DiffViewMode::Unified.toggled(), DiffViewMode::SideBySide);
+        assert_eq!(DiffViewMode::SideBySide.toggled(), DiffViewMode::Unified);
+    }
+
+    #[test]
+    fn test_build_file_tree_groups_directories() {
+        let changes = vec![
+            file_change("src/ui/sidebar.rs"),
+            file_change("README.md"),
+            file_change("src/main.rs"),
+            file_change("src/ui/theme.rs"),
+        ];
+
+        let tree = build_file_tree(&changes);
+
+        assert_eq!(
+            tree,
+            vec![
+                FileTreeEntry::File {
+                    name: "README.md".to_string(),
+                    depth: 0,
+                    index: 1,
+                },
+                FileTreeEntry::Directory {
+                    name: "src".to_string(),
+                    depth: 0,
+                },
+                FileTreeEntry::File {
+                    name: "main.rs".to_string(),
+                    depth: 1,
+                    index: 2,
+                },
+                FileTreeEntry::Directory {
+                    name: "ui".to_string(),
+                    depth: 1,
+                },
+                FileTreeEntry::File {
+                    name: "sidebar.rs".to_string(),
+                    depth: 2,
+                    index: 0,
+                },
+                FileTreeEntry::File {
+                    name: "theme.rs".to_string(),
+                    depth: 2,
+                    index: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_rows_pairs_replacements() {
+        let hunk = DiffHunk {
+            header: "@@ -1,4 +1,4 @@".to_string(),
+            old_start: 1,
+            old_lines: 4,
+            new_start: 1,
+            new_lines: 4,
+            lines: vec![
+                line(' ', "a"),
+                line('-', "b"),
+                line('-', "c"),
+                line('+', "B"),
+                line(' ', "d"),
+                line('+', "e"),
+            ],
+        };
+
+        let rows = side_by_side_rows(&hunk);
+        let contents: Vec<(Option<&str>, Option<&str>)> = rows
+            .iter()
+            .map(|r| {
+                (
+                    r.left.map(|l| l.content.as_str()),
+                    r.right.map(|l| l.content.as_str()),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            contents,
+            vec![
+                (Some("a"), Some("a")),
+                (Some("b"), Some("B")),
+                (Some("c"), None),
+                (Some("d"), Some("d")),
+                (None, Some("e")),
+            ]
+        );
+    }
+}
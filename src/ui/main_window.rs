@@ -1,29 +1,122 @@
-use crate::git::{Worktree, get_repository_name, list_worktrees, worktree};
-use crate::terminal::TerminalSession;
-use crate::ui::terminal_view::TerminalView;
+use crate::git::worktree::{GitRef, RefKind, WorktreeSource};
+use crate::git::{Worktree, WorktreeStatus, get_repository_name, list_worktrees, worktree};
+use crate::settings::{
+    Settings, TerminalSettings, WorktreeLocation, WorktreeRoot, load_settings, save_settings,
+};
+use crate::ui::bootstrap_view::{BootstrapReady, BootstrapStatus, BootstrapView};
+use crate::ui::git_review_view::GitReviewView;
+use crate::ui::status_service::WorktreeStatusService;
+use crate::ui::terminal_layout_view::TerminalLayoutView;
 use crate::ui::theme::Theme;
+use crate::ui::tooltip::TextTooltip;
+use crate::ui::workspace_watcher::WorkspaceWatcher;
+use crate::workspace::bootstrap::{REPO_CONFIG_FILE, load_repo_config};
+use crate::workspace::watcher::WatchEvent;
 use gpui::prelude::FluentBuilder;
 use gpui::*;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+/// Tabs of the feature view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureTab {
+    Terminal,
+    GitReview,
+}
+
+/// What the inline creation dialog creates the worktree from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreationMode {
+    /// A new branch from HEAD or a picked base ref
+    NewBranch,
+    /// An existing local or remote-tracking branch
+    ExistingBranch,
+    /// A detached checkout of a branch, tag or commit
+    Detached,
+}
+
+impl CreationMode {
+    fn label(self) -> &'static str {
+        match self {
+            CreationMode::NewBranch => "New branch",
+            CreationMode::ExistingBranch => "Existing",
+            CreationMode::Detached => "Detached",
+        }
+    }
+
+    fn input_label(self) -> &'static str {
+        match self {
+            CreationMode::NewBranch => "New branch name:",
+            CreationMode::ExistingBranch => "Branch to check out:",
+            CreationMode::Detached => "Branch, tag or commit:",
+        }
+    }
+
+    fn placeholder(self) -> &'static str {
+        match self {
+            CreationMode::NewBranch => "feature/my-branch",
+            CreationMode::ExistingBranch => "origin/feature/my-branch",
+            CreationMode::Detached => "v1.0.0",
+        }
+    }
+}
+
+/// Directory name templates offered in the settings panel
+const WORKTREE_NAME_TEMPLATES: [&str; 2] = ["{branch}", "{repo}-{branch}"];
+
+/// A worktree removal waiting for confirmation in the sidebar
+struct PendingRemoval {
+    worktree: Worktree,
+    /// Files with uncommitted changes, None if the status could not be read
+    uncommitted_changes: Option<usize>,
+    delete_branch: bool,
+    error: Option<SharedString>,
+}
+
+/// A worktree lock waiting for its reason in the sidebar
+struct PendingLock {
+    worktree_path: PathBuf,
+    reason: String,
+    error: Option<SharedString>,
+}
+
 /// Main application window that orchestrates the sidebar and feature view
 pub struct MainWindow {
     workspace_path: PathBuf,
     workspace_name: SharedString,
     worktrees: Vec<Worktree>,
     selected_worktree_index: usize,
+    settings: Settings,
     theme: Theme,
     error_message: Option<String>,
     // Dialog state
     show_create_dialog: bool,
     dialog_branch_name: SharedString,
     dialog_error: Option<SharedString>,
+    dialog_mode: CreationMode,
+    dialog_base_ref: Option<String>,
+    dialog_refs: Vec<GitRef>,
     focus_handle: FocusHandle,
-    // Terminal session management
-    terminal_sessions: HashMap<PathBuf, Entity<TerminalSession>>,
-    active_terminal_view: Option<Entity<TerminalView>>,
-    terminal_error: Option<String>,
+    // Worktree context actions
+    context_menu_index: Option<usize>,
+    pending_removal: Option<PendingRemoval>,
+    pending_lock: Option<PendingLock>,
+    sidebar_notice: Option<SharedString>,
+    // Settings panel
+    show_settings_panel: bool,
+    relocation_candidates: usize,
+    // Background status of every worktree
+    status_service: Entity<WorktreeStatusService>,
+    // File changes in all worktrees
+    watcher: Entity<WorkspaceWatcher>,
+    // Terminal sessions, one per worktree, attached to a view while selected
+    terminal_layouts: HashMap<PathBuf, Entity<TerminalLayoutView>>,
+    active_terminal_view: Option<Entity<TerminalLayoutView>>,
+    // Worktrees still being set up from .maestro.json
+    bootstraps: HashMap<PathBuf, Entity<BootstrapView>>,
+    // Feature view tabs
+    active_tab: FeatureTab,
+    active_review_view: Option<Entity<GitReviewView>>,
 }
 
 impl MainWindow {
@@ -50,20 +143,48 @@ impl MainWindow {
             return Err("No worktrees found in repository".to_string());
         }
 
+        let worktree_paths: Vec<PathBuf> = worktrees.iter().map(|wt| wt.path.clone()).collect();
+        let status_service = cx.new(|cx| WorktreeStatusService::new(worktree_paths.clone(), cx));
+        cx.observe(&status_service, |_this, _service, cx| cx.notify())
+            .detach();
+
+        let watcher = cx.new(|cx| WorkspaceWatcher::new(worktree_paths, cx));
+        cx.subscribe(&watcher, |this, _watcher, event: &WatchEvent, cx| {
+            this.handle_watch_event(event, cx);
+        })
+        .detach();
+
         Ok(Self {
             workspace_path,
             workspace_name: workspace_name.into(),
             worktrees,
             selected_worktree_index: 0,
+            settings: load_settings().unwrap_or_else(|e| {
+                eprintln!("Failed to load settings: {}", e);
+                Settings::default()
+            }),
             theme: Theme::new(),
             error_message: None,
             show_create_dialog: false,
             dialog_branch_name: "".into(),
             dialog_error: None,
+            dialog_mode: CreationMode::NewBranch,
+            dialog_base_ref: None,
+            dialog_refs: Vec::new(),
             focus_handle: cx.focus_handle(),
-            terminal_sessions: HashMap::new(),
+            context_menu_index: None,
+            pending_removal: None,
+            pending_lock: None,
+            sidebar_notice: None,
+            show_settings_panel: false,
+            relocation_candidates: 0,
+            status_service,
+            watcher,
+            terminal_layouts: HashMap::new(),
             active_terminal_view: None,
-            terminal_error: None,
+            bootstraps: HashMap::new(),
+            active_tab: FeatureTab::Terminal,
+            active_review_view: None,
         })
     }
 
@@ -80,15 +201,29 @@ impl MainWindow {
             workspace_name: workspace_name.into(),
             worktrees: vec![],
             selected_worktree_index: 0,
+            settings: Settings::default(),
             theme: Theme::new(),
             error_message: Some(error),
             show_create_dialog: false,
             dialog_branch_name: "".into(),
             dialog_error: None,
+            dialog_mode: CreationMode::NewBranch,
+            dialog_base_ref: None,
+            dialog_refs: Vec::new(),
             focus_handle: cx.focus_handle(),
-            terminal_sessions: HashMap::new(),
+            context_menu_index: None,
+            pending_removal: None,
+            pending_lock: None,
+            sidebar_notice: None,
+            show_settings_panel: false,
+            relocation_candidates: 0,
+            status_service: cx.new(|cx| WorktreeStatusService::new(Vec::new(), cx)),
+            watcher: cx.new(|cx| WorkspaceWatcher::new(Vec::new(), cx)),
+            terminal_layouts: HashMap::new(),
             active_terminal_view: None,
-            terminal_error: None,
+            bootstraps: HashMap::new(),
+            active_tab: FeatureTab::Terminal,
+            active_review_view: None,
         }
     }
 
@@ -97,74 +232,41 @@ impl MainWindow {
         self.worktrees.get(self.selected_worktree_index)
     }
 
-    /// Get or create a terminal session for the given worktree path
-    fn get_or_create_terminal_session(
-        &mut self,
-        worktree_path: &PathBuf,
-        cx: &mut Context<Self>,
-    ) -> Result<Entity<TerminalSession>, String> {
-        // Check if session already exists
-        if let Some(session) = self.terminal_sessions.get(worktree_path) {
-            return Ok(session.clone());
-        }
-
-        // Create new terminal session
-        let path = worktree_path.clone();
-        match TerminalSession::new(path.clone(), None, 24, 80) {
-            Ok(session) => {
-                let entity = cx.new(|_cx| session);
-                self.terminal_sessions.insert(path, entity.clone());
-                Ok(entity)
-            }
-            Err(e) => Err(format!("Failed to create terminal session: {}", e)),
-        }
+    /// Show the terminals of the given worktree, restoring their layout the
+    /// first time the worktree is opened
+    fn switch_terminal_for_worktree(&mut self, worktree_path: &PathBuf, cx: &mut Context<Self>) {
+        let settings = self.settings.terminal.clone();
+        let layout_view = self
+            .terminal_layouts
+            .entry(worktree_path.clone())
+            .or_insert_with(|| {
+                let path = worktree_path.clone();
+                cx.new(|cx| TerminalLayoutView::new(path, settings, cx))
+            })
+            .clone();
+        self.active_terminal_view = Some(layout_view);
     }
 
-    /// Create or switch to the terminal view for the given worktree
-    fn switch_terminal_for_worktree(&mut self, worktree_path: &PathBuf, cx: &mut Context<Self>) {
-        // Clear any previous error
-        self.terminal_error = None;
-
-        // Get or create the terminal session
-        match self.get_or_create_terminal_session(worktree_path, cx) {
-            Ok(session_entity) => {
-                // Create a new terminal view with the session
-                let terminal_view = cx.new(|cx| {
-                    // Get the session from the entity
-                    let _session = session_entity.read(cx);
-                    // Create a new session for the view (we need to create a new one since TerminalSession doesn't implement Clone)
-                    match TerminalSession::new(worktree_path.clone(), None, 24, 80) {
-                        Ok(new_session) => TerminalView::new(new_session, cx),
-                        Err(_) => {
-                            // Fallback: create with temp dir
-                            let fallback_session =
-                                TerminalSession::new(std::env::temp_dir(), None, 24, 80)
-                                    .expect("Failed to create fallback terminal session");
-                            TerminalView::new(fallback_session, cx)
-                        }
-                    }
-                });
-                self.active_terminal_view = Some(terminal_view);
-            }
-            Err(e) => {
-                self.terminal_error = Some(e);
+    /// Stop the terminals of a worktree that was removed or moved
+    fn drop_terminals(&mut self, worktree_path: &PathBuf, cx: &mut Context<Self>) {
+        if let Some(layout_view) = self.terminal_layouts.remove(worktree_path) {
+            if self.active_terminal_view.as_ref() == Some(&layout_view) {
                 self.active_terminal_view = None;
             }
+            layout_view.update(cx, |layout_view, _cx| layout_view.kill_all());
         }
     }
 
     /// Handle worktree selection
     fn handle_worktree_click(&mut self, idx: usize, cx: &mut Context<Self>) {
+        self.context_menu_index = None;
+
         if idx < self.worktrees.len() {
             // Save current session state before switching
             if let Some(current_worktree) = self.selected_worktree() {
                 let current_path = current_worktree.path.clone();
-                if let Some(session_entity) = self.terminal_sessions.get(&current_path) {
-                    session_entity.update(cx, |session, _cx| {
-                        if let Err(e) = session.save_state() {
-                            eprintln!("Failed to save session state: {}", e);
-                        }
-                    });
+                if let Some(layout_view) = self.terminal_layouts.get(&current_path) {
+                    layout_view.read(cx).save_state();
                 }
             }
 
@@ -176,22 +278,391 @@ impl MainWindow {
                 self.worktrees[idx].branch
             );
 
-            // Switch to the terminal for this worktree
-            self.switch_terminal_for_worktree(&worktree_path, cx);
+            // Switch to the terminal for this worktree, unless it is still being set up
+            if self.bootstraps.contains_key(&worktree_path) {
+                self.active_terminal_view = None;
+            } else {
+                self.switch_terminal_for_worktree(&worktree_path, cx);
+            }
+
+            // Point the review tab at the new worktree
+            if self.active_tab == FeatureTab::GitReview {
+                self.switch_review_for_selected_worktree(cx);
+            } else {
+                self.active_review_view = None;
+            }
+
+            cx.notify();
+        }
+    }
+
+    /// Create or refresh the git review view for the selected worktree
+    fn switch_review_for_selected_worktree(&mut self, cx: &mut Context<Self>) {
+        let Some(worktree) = self.selected_worktree().cloned() else {
+            self.active_review_view = None;
+            return;
+        };
+
+        // Reuse the existing view when it already shows this worktree
+        if let Some(review_view) = &self.active_review_view
+            && review_view.read(cx).worktree().path == worktree.path
+        {
+            review_view.update(cx, |view, cx| view.refresh(cx));
+            return;
+        }
+
+        let watcher = self.watcher.clone();
+        self.active_review_view = Some(cx.new(|cx| GitReviewView::new(worktree, &watcher, cx)));
+    }
+
+    /// Handle a click on one of the feature view tabs
+    fn handle_tab_click(&mut self, tab: FeatureTab, cx: &mut Context<Self>) {
+        self.active_tab = tab;
+
+        if tab == FeatureTab::GitReview {
+            self.switch_review_for_selected_worktree(cx);
+        }
+
+        cx.notify();
+    }
+
+    /// Open the context actions of a worktree
+    fn handle_worktree_right_click(&mut self, idx: usize, cx: &mut Context<Self>) {
+        self.context_menu_index = if self.context_menu_index == Some(idx) {
+            None
+        } else {
+            Some(idx)
+        };
+        self.pending_removal = None;
+        self.pending_lock = None;
+        cx.notify();
+    }
+
+    /// Ask for confirmation before removing a worktree
+    fn handle_remove_request(&mut self, idx: usize, cx: &mut Context<Self>) {
+        self.context_menu_index = None;
+
+        let Some(worktree) = self.worktrees.get(idx).cloned() else {
+            return;
+        };
+
+        if worktree.is_locked {
+            self.sidebar_notice = Some("Unlock the worktree before removing it".into());
+            cx.notify();
+            return;
+        }
+
+        let uncommitted_changes = match worktree::count_uncommitted_changes(&worktree.path) {
+            Ok(count) => Some(count),
+            Err(e) => {
+                eprintln!("Failed to read worktree status: {}", e);
+                None
+            }
+        };
+
+        self.pending_removal = Some(PendingRemoval {
+            worktree,
+            uncommitted_changes,
+            delete_branch: false,
+            error: None,
+        });
+        cx.notify();
+    }
+
+    /// Toggle deleting the branch together with the pending worktree removal
+    fn toggle_delete_branch(&mut self, cx: &mut Context<Self>) {
+        if let Some(pending) = &mut self.pending_removal {
+            pending.delete_branch = !pending.delete_branch;
+            cx.notify();
+        }
+    }
+
+    /// Ask for an optional reason before locking a worktree
+    fn handle_lock_request(&mut self, idx: usize, cx: &mut Context<Self>) {
+        self.context_menu_index = None;
+
+        if let Some(worktree) = self.worktrees.get(idx) {
+            self.pending_lock = Some(PendingLock {
+                worktree_path: worktree.path.clone(),
+                reason: String::new(),
+                error: None,
+            });
+        }
+        cx.notify();
+    }
+
+    /// Lock the worktree awaiting its reason
+    fn handle_lock_confirm(&mut self, cx: &mut Context<Self>) {
+        let Some(pending) = &mut self.pending_lock else {
+            return;
+        };
+
+        if let Err(e) = worktree::lock_worktree(
+            &self.workspace_path,
+            &pending.worktree_path,
+            Some(pending.reason.as_str()),
+        ) {
+            pending.error = Some(e.to_string().into());
+            cx.notify();
+            return;
+        }
+
+        println!("Locked worktree: {}", pending.worktree_path.display());
+        self.pending_lock = None;
+        self.refresh_worktrees(cx);
+    }
+
+    /// Cancel the pending worktree lock
+    fn handle_lock_cancel(&mut self, cx: &mut Context<Self>) {
+        self.pending_lock = None;
+        cx.notify();
+    }
+
+    /// Unlock a worktree from its context actions
+    fn handle_unlock(&mut self, idx: usize, cx: &mut Context<Self>) {
+        self.context_menu_index = None;
+
+        let Some(worktree) = self.worktrees.get(idx) else {
+            return;
+        };
+
+        match worktree::unlock_worktree(&self.workspace_path, &worktree.path) {
+            Ok(()) => {
+                println!("Unlocked worktree: {}", worktree.path.display());
+                self.sidebar_notice = None;
+                self.refresh_worktrees(cx);
+            }
+            Err(e) => {
+                self.sidebar_notice = Some(e.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    /// Cancel the pending worktree removal
+    fn handle_remove_cancel(&mut self, cx: &mut Context<Self>) {
+        self.pending_removal = None;
+        cx.notify();
+    }
+
+    /// Remove the worktree awaiting confirmation, forcing it if it has changes
+    fn handle_remove_confirm(&mut self, cx: &mut Context<Self>) {
+        let Some(pending) = &self.pending_removal else {
+            return;
+        };
+
+        let worktree = pending.worktree.clone();
+        // Unknown status is treated as dirty; the user already confirmed
+        let force = pending.uncommitted_changes != Some(0);
+        let delete_branch = pending.delete_branch && !worktree.is_detached;
+
+        // Stop setup commands first so they don't write into the directory
+        // being deleted; a failed removal leaves a retryable bootstrap
+        if let Some(bootstrap) = self.bootstraps.get(&worktree.path) {
+            bootstrap.read(cx).cancel();
+        }
 
+        if let Err(e) = worktree::remove_worktree(&self.workspace_path, &worktree.path, force) {
+            if let Some(pending) = &mut self.pending_removal {
+                pending.error = Some(e.to_string().into());
+            }
             cx.notify();
+            return;
+        }
+
+        println!("Removed worktree: {}", worktree.path.display());
+        self.pending_removal = None;
+        self.sidebar_notice = None;
+
+        self.bootstraps.remove(&worktree.path);
+
+        // Stop the terminal sessions of the removed worktree
+        self.drop_terminals(&worktree.path, cx);
+
+        if delete_branch {
+            match worktree::delete_branch_if_merged(&self.workspace_path, &worktree.branch) {
+                Ok(true) => println!("Deleted branch: {}", worktree.branch),
+                Ok(false) => {
+                    self.sidebar_notice =
+                        Some(format!("Kept branch '{}': it is not merged", worktree.branch).into());
+                }
+                Err(e) => self.sidebar_notice = Some(e.to_string().into()),
+            }
+        }
+
+        let selected_path = self.selected_worktree().map(|wt| wt.path.clone());
+        self.refresh_worktrees(cx);
+
+        // Keep the selection on the same worktree, falling back to the root
+        match selected_path {
+            Some(path) if path == worktree.path => self.handle_worktree_click(0, cx),
+            Some(path) => {
+                if let Some(idx) = self.worktrees.iter().position(|wt| wt.path == path) {
+                    self.selected_worktree_index = idx;
+                }
+            }
+            None => {}
+        }
+
+        cx.notify();
+    }
+
+    /// Prune worktrees whose directories were deleted outside the app
+    fn handle_prune_worktrees(&mut self, cx: &mut Context<Self>) {
+        self.context_menu_index = None;
+
+        self.sidebar_notice = Some(match worktree::prune_worktrees(&self.workspace_path) {
+            Ok(pruned) if pruned.is_empty() => "No stale worktrees to prune".into(),
+            Ok(pruned) => format!("Pruned {} stale worktree(s)", pruned.len()).into(),
+            Err(e) => e.to_string().into(),
+        });
+
+        self.refresh_worktrees(cx);
+        cx.notify();
+    }
+
+    /// Toggle the settings panel in the sidebar
+    fn handle_settings_button_click(&mut self, cx: &mut Context<Self>) {
+        self.show_settings_panel = !self.show_settings_panel;
+        if self.show_settings_panel {
+            self.update_relocation_candidates();
+        }
+        cx.notify();
+    }
+
+    /// Count worktrees that are outside the configured location
+    fn update_relocation_candidates(&mut self) {
+        self.relocation_candidates = match worktree::worktrees_to_relocate(
+            &self.workspace_path,
+            &self.settings.worktree_location,
+        ) {
+            Ok(candidates) => candidates.len(),
+            Err(e) => {
+                eprintln!("Failed to check worktree locations: {}", e);
+                0
+            }
+        };
+    }
+
+    /// Change and persist where new worktrees are created
+    fn update_worktree_location(&mut self, location: WorktreeLocation, cx: &mut Context<Self>) {
+        self.settings.worktree_location = location.clone();
+
+        // Reload before saving so changes made elsewhere are kept
+        let result = load_settings().and_then(|mut stored| {
+            stored.worktree_location = location;
+            save_settings(&stored)
+        });
+        if let Err(e) = result {
+            self.sidebar_notice = Some(format!("Failed to save settings: {}", e).into());
+        }
+
+        self.update_relocation_candidates();
+        cx.notify();
+    }
+
+    /// Change and persist the terminal settings, applying them to open terminals
+    fn update_terminal_settings(&mut self, terminal: TerminalSettings, cx: &mut Context<Self>) {
+        self.settings.terminal = terminal.clone();
+
+        // Reload before saving so changes made elsewhere are kept
+        let result = load_settings().and_then(|mut stored| {
+            stored.terminal = terminal.clone();
+            save_settings(&stored)
+        });
+        if let Err(e) = result {
+            self.sidebar_notice = Some(format!("Failed to save settings: {}", e).into());
+        }
+
+        for layout_view in self.terminal_layouts.values() {
+            let terminal = terminal.clone();
+            layout_view.update(cx, |layout_view, cx| layout_view.set_settings(terminal, cx));
+        }
+        cx.notify();
+    }
+
+    /// Move existing worktrees into the configured location
+    fn handle_relocate_worktrees(&mut self, cx: &mut Context<Self>) {
+        let report = match worktree::relocate_worktrees(
+            &self.workspace_path,
+            &self.settings.worktree_location,
+        ) {
+            Ok(report) => report,
+            Err(e) => {
+                self.sidebar_notice = Some(e.to_string().into());
+                cx.notify();
+                return;
+            }
+        };
+
+        let selected_path = self.selected_worktree().map(|wt| wt.path.clone());
+        let mut new_selected_path = selected_path.clone();
+
+        // Terminal sessions run inside the old directories, so stop them
+        for (old_path, new_path) in &report.moved {
+            println!(
+                "Moved worktree: {} -> {}",
+                old_path.display(),
+                new_path.display()
+            );
+            self.drop_terminals(old_path, cx);
+            if selected_path.as_ref() == Some(old_path) {
+                new_selected_path = Some(new_path.clone());
+            }
+        }
+
+        self.sidebar_notice = Some(if report.failed.is_empty() {
+            format!("Moved {} worktree(s)", report.moved.len()).into()
+        } else {
+            format!(
+                "Moved {} worktree(s), {} failed: {}",
+                report.moved.len(),
+                report.failed.len(),
+                report
+                    .failed
+                    .iter()
+                    .map(|(path, error)| format!("{} ({})", path.display(), error))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .into()
+        });
+
+        self.refresh_worktrees(cx);
+        self.update_relocation_candidates();
+
+        if new_selected_path != selected_path {
+            if let Some(idx) = self
+                .worktrees
+                .iter()
+                .position(|wt| Some(&wt.path) == new_selected_path.as_ref())
+            {
+                self.handle_worktree_click(idx, cx);
+            }
         }
+
+        cx.notify();
     }
 
     /// Refresh the list of worktrees from git
     fn refresh_worktrees(&mut self, cx: &mut Context<Self>) {
         match list_worktrees(&self.workspace_path) {
             Ok(worktrees) => {
+                let selected_path = self.selected_worktree().map(|wt| wt.path.clone());
                 self.worktrees = worktrees;
-                // Keep selection valid
-                if self.selected_worktree_index >= self.worktrees.len() {
-                    self.selected_worktree_index = 0;
-                }
+                // Keep the same worktree selected, falling back to the root
+                self.selected_worktree_index = selected_path
+                    .and_then(|path| self.worktrees.iter().position(|wt| wt.path == path))
+                    .unwrap_or(0);
+
+                let worktree_paths: Vec<PathBuf> =
+                    self.worktrees.iter().map(|wt| wt.path.clone()).collect();
+                self.watcher.update(cx, |watcher, _cx| {
+                    watcher.set_worktrees(worktree_paths.clone());
+                });
+                self.status_service.update(cx, |service, cx| {
+                    service.set_worktrees(worktree_paths, cx);
+                });
                 cx.notify();
             }
             Err(e) => {
@@ -200,62 +671,211 @@ impl MainWindow {
         }
     }
 
+    /// React to file changes reported by the workspace watcher
+    fn handle_watch_event(&mut self, event: &WatchEvent, cx: &mut Context<Self>) {
+        match event {
+            WatchEvent::WorktreeListChanged => {
+                let selected_path = self.selected_worktree().map(|wt| wt.path.clone());
+                self.refresh_worktrees(cx);
+
+                // The selected worktree was removed outside the app
+                if selected_path
+                    .is_some_and(|path| !self.worktrees.iter().any(|wt| wt.path == path))
+                {
+                    self.handle_worktree_click(0, cx);
+                }
+            }
+            WatchEvent::WorktreeChanged(_) | WatchEvent::RefsChanged => {
+                self.status_service
+                    .update(cx, |service, cx| service.refresh(cx));
+            }
+        }
+    }
+
     /// Handle worktree creation
     fn handle_create_worktree(
         &mut self,
-        branch_name: String,
+        source: WorktreeSource,
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         // Validate and create worktree
-        match worktree::validate_branch_name(&branch_name) {
-            Ok(_) => {
-                match worktree::create_worktree(&self.workspace_path, &branch_name, None) {
-                    Ok(new_worktree) => {
-                        println!(
-                            "Created worktree: {} ({})",
-                            new_worktree.path.display(),
-                            new_worktree.branch
-                        );
-
-                        // Hide dialog
-                        self.show_create_dialog = false;
-                        self.dialog_branch_name = "".into();
-                        self.dialog_error = None;
-
-                        // Refresh worktree list
-                        self.refresh_worktrees(cx);
-
-                        // Select the newly created worktree
-                        if let Some(idx) = self
-                            .worktrees
-                            .iter()
-                            .position(|wt| wt.path == new_worktree.path)
-                        {
-                            self.selected_worktree_index = idx;
-                            cx.notify();
-                        }
-                    }
-                    Err(e) => {
-                        // Show error in dialog
-                        self.dialog_error = Some(e.to_string().into());
-                        cx.notify();
-                    }
+        match worktree::create_worktree_from(
+            &self.workspace_path,
+            &source,
+            None,
+            &self.settings.worktree_location,
+        ) {
+            Ok(new_worktree) => {
+                println!(
+                    "Created worktree: {} ({})",
+                    new_worktree.path.display(),
+                    new_worktree.branch
+                );
+
+                // Hide dialog
+                self.show_create_dialog = false;
+                self.dialog_branch_name = "".into();
+                self.dialog_error = None;
+
+                // Refresh worktree list
+                self.refresh_worktrees(cx);
+
+                // Prepare the worktree before its terminal is opened
+                self.start_bootstrap(&new_worktree.path, cx);
+
+                // Select the newly created worktree
+                if let Some(idx) = self
+                    .worktrees
+                    .iter()
+                    .position(|wt| wt.path == new_worktree.path)
+                {
+                    self.handle_worktree_click(idx, cx);
                 }
             }
             Err(e) => {
-                // Show validation error in dialog
+                // Show error in dialog
                 self.dialog_error = Some(e.to_string().into());
                 cx.notify();
             }
         }
     }
 
+    /// Run the `.maestro.json` bootstrap of the root worktree in a new worktree
+    ///
+    /// Does nothing when the repository has no bootstrap steps. The terminal of
+    /// the worktree is opened once the bootstrap is done.
+    fn start_bootstrap(&mut self, worktree_path: &PathBuf, cx: &mut Context<Self>) {
+        let Some(root_path) = self.worktrees.first().map(|wt| wt.path.clone()) else {
+            return;
+        };
+
+        let config = match load_repo_config(&root_path) {
+            Ok(config) => config.bootstrap,
+            Err(e) => {
+                eprintln!("Failed to load {}: {:#}", REPO_CONFIG_FILE, e);
+                self.sidebar_notice = Some(format!("Skipped worktree setup: {:#}", e).into());
+                return;
+            }
+        };
+
+        if config.is_empty() {
+            return;
+        }
+
+        let path = worktree_path.clone();
+        let view = cx.new(|cx| BootstrapView::new(root_path, path.clone(), config, cx));
+
+        // Keep the sidebar badge in sync with the bootstrap progress
+        cx.observe(&view, |_this, _view, cx| cx.notify()).detach();
+        cx.subscribe(&view, move |this, _view, _event: &BootstrapReady, cx| {
+            this.handle_bootstrap_ready(&path, cx);
+        })
+        .detach();
+
+        self.bootstraps.insert(worktree_path.clone(), view);
+    }
+
+    /// Hand a bootstrapped worktree over to its terminal
+    fn handle_bootstrap_ready(&mut self, worktree_path: &PathBuf, cx: &mut Context<Self>) {
+        self.bootstraps.remove(worktree_path);
+
+        if self
+            .selected_worktree()
+            .is_some_and(|wt| &wt.path == worktree_path)
+        {
+            self.switch_terminal_for_worktree(worktree_path, cx);
+        }
+
+        cx.notify();
+    }
+
+    /// Build the worktree source from the dialog input
+    fn dialog_worktree_source(&self) -> Result<WorktreeSource, SharedString> {
+        let input = self.dialog_branch_name.trim().to_string();
+
+        match self.dialog_mode {
+            CreationMode::NewBranch if input.is_empty() => {
+                Err("Branch name cannot be empty".into())
+            }
+            CreationMode::NewBranch => Ok(WorktreeSource::NewBranch {
+                name: input,
+                base: self.dialog_base_ref.clone(),
+            }),
+            CreationMode::ExistingBranch if input.is_empty() => Err("Pick a branch".into()),
+            CreationMode::ExistingBranch => Ok(WorktreeSource::ExistingBranch(input)),
+            CreationMode::Detached if input.is_empty() => {
+                Err("Enter a branch, tag or commit".into())
+            }
+            CreationMode::Detached => Ok(WorktreeSource::Detached(input)),
+        }
+    }
+
+    /// Switch the creation dialog between new, existing and detached modes
+    fn handle_dialog_mode_click(&mut self, mode: CreationMode, cx: &mut Context<Self>) {
+        if self.dialog_mode != mode {
+            self.dialog_mode = mode;
+            self.dialog_branch_name = "".into();
+            self.dialog_error = None;
+            cx.notify();
+        }
+    }
+
+    /// Handle a click on a ref in the creation dialog's picker
+    fn handle_ref_pick(&mut self, name: Option<String>, cx: &mut Context<Self>) {
+        match self.dialog_mode {
+            CreationMode::NewBranch => self.dialog_base_ref = name,
+            CreationMode::ExistingBranch | CreationMode::Detached => {
+                self.dialog_branch_name = name.unwrap_or_default().into();
+            }
+        }
+        self.dialog_error = None;
+        cx.notify();
+    }
+
+    /// Refs shown in the picker for the current mode, filtered by the input
+    fn dialog_ref_candidates(&self) -> Vec<&GitRef> {
+        let filter = self.dialog_branch_name.to_lowercase();
+
+        self.dialog_refs
+            .iter()
+            // Tags can't be checked out as a branch
+            .filter(|r| self.dialog_mode != CreationMode::ExistingBranch || r.kind != RefKind::Tag)
+            .filter(|r| {
+                self.dialog_mode == CreationMode::NewBranch
+                    || r.name.to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+
+    /// Directory the worktree will be created in, shown in the creation dialog
+    fn creation_location_hint(&self) -> Option<String> {
+        let parent_dir = self
+            .settings
+            .worktree_location
+            .parent_dir(&self.workspace_path, &self.workspace_name)
+            .ok()?;
+        Some(format!("In: {}", parent_dir.display()))
+    }
+
+    /// Whether a local branch is checked out in one of the listed worktrees
+    fn is_branch_checked_out(&self, branch: &str) -> bool {
+        self.worktrees
+            .iter()
+            .any(|wt| !wt.is_detached && wt.branch == branch)
+    }
+
     /// Handle the create button click
     fn handle_create_button_click(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.show_create_dialog = true;
         self.dialog_branch_name = "".into();
         self.dialog_error = None;
+        self.dialog_mode = CreationMode::NewBranch;
+        self.dialog_base_ref = None;
+        self.dialog_refs = worktree::list_refs(&self.workspace_path).unwrap_or_else(|e| {
+            eprintln!("Failed to list refs: {}", e);
+            Vec::new()
+        });
         self.focus_handle.focus(window);
         cx.notify();
     }
@@ -275,21 +895,52 @@ impl MainWindow {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        // Only handle keys when in creation mode
-        if !self.show_create_dialog {
-            return;
-        }
-
         let key = event.keystroke.key.as_str();
 
-        if key == "enter" {
-            let branch_name = self.dialog_branch_name.to_string();
-            if !branch_name.trim().is_empty() {
-                self.handle_create_worktree(branch_name, window, cx);
-            } else {
-                self.dialog_error = Some("Branch name cannot be empty".into());
+        // Typing goes to the lock reason while a lock is pending
+        if let Some(pending) = &mut self.pending_lock {
+            if key == "enter" {
+                self.handle_lock_confirm(cx);
+            } else if key == "escape" {
+                self.handle_lock_cancel(cx);
+            } else if key == "backspace" {
+                pending.reason.pop();
+                pending.error = None;
+                cx.notify();
+            } else if !event.keystroke.modifiers.control
+                && !event.keystroke.modifiers.platform
+                && let Some(text) = &event.keystroke.key_char
+            {
+                // Shifted and non-ASCII characters only show up in key_char
+                pending.reason.push_str(text);
+                pending.error = None;
                 cx.notify();
             }
+            return;
+        }
+
+        // Escape also dismisses worktree context actions
+        if key == "escape" && (self.context_menu_index.is_some() || self.pending_removal.is_some())
+        {
+            self.context_menu_index = None;
+            self.pending_removal = None;
+            cx.notify();
+            return;
+        }
+
+        // Only handle keys when in creation mode
+        if !self.show_create_dialog {
+            return;
+        }
+
+        if key == "enter" {
+            match self.dialog_worktree_source() {
+                Ok(source) => self.handle_create_worktree(source, window, cx),
+                Err(error) => {
+                    self.dialog_error = Some(error);
+                    cx.notify();
+                }
+            }
         } else if key == "escape" {
             self.handle_dialog_cancel(cx);
         } else if key == "backspace" {
@@ -318,6 +969,9 @@ impl MainWindow {
             .border_r_1()
             .border_color(self.theme.border_subtle)
             .child(self.render_sidebar_header(cx))
+            .when(self.show_settings_panel, |this| {
+                this.child(self.render_settings_panel(cx))
+            })
             .child(self.render_worktree_list(cx))
     }
 
@@ -334,88 +988,542 @@ impl MainWindow {
             .border_color(self.theme.border_subtle)
             .child(
                 div()
-                    .text_base()
-                    .font_weight(FontWeight::SEMIBOLD)
-                    .text_color(self.theme.text_primary)
-                    .child(self.workspace_name.clone()),
+                    .text_base()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(self.theme.text_primary)
+                    .child(self.workspace_name.clone()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap_1()
+                    .child(self.render_create_button(cx))
+                    .child(self.render_settings_button(cx)),
+            )
+    }
+
+    /// Render the create worktree button
+    fn render_create_button(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .w(px(28.0))
+            .h(px(28.0))
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_md()
+            .text_color(self.theme.text_secondary)
+            .cursor_pointer()
+            .hover(|style| style.bg(self.theme.bg_hover))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event: &MouseDownEvent, window, cx| {
+                    this.handle_create_button_click(window, cx);
+                }),
+            )
+            .child("+")
+    }
+
+    /// Render the settings button placeholder
+    fn render_settings_button(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .w(px(28.0))
+            .h(px(28.0))
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_md()
+            .text_color(self.theme.text_secondary)
+            .cursor_pointer()
+            .hover(|style| style.bg(self.theme.bg_hover))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                    this.handle_settings_button_click(cx);
+                }),
+            )
+            .child("⚙")
+    }
+
+    /// Render the settings panel with the worktree location options
+    fn render_settings_panel(&self, cx: &mut Context<Self>) -> Div {
+        let location = &self.settings.worktree_location;
+        let parent_preview = location
+            .parent_dir(&self.workspace_path, &self.workspace_name)
+            .map(|dir| dir.join(location.dir_name(&self.workspace_name, "my-branch")))
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|e| e.to_string());
+
+        let mut panel = div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .mx_2()
+            .mt_2()
+            .px_3()
+            .py_2()
+            .bg(self.theme.bg_primary)
+            .border_1()
+            .border_color(self.theme.border_subtle)
+            .rounded_md()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .child("WORKTREE LOCATION"),
+            );
+
+        for root in WorktreeRoot::ALL {
+            let is_active = location.root == root;
+            panel = panel.child(
+                div()
+                    .px_2()
+                    .py(px(2.0))
+                    .rounded_md()
+                    .text_xs()
+                    .cursor_pointer()
+                    .text_color(if is_active {
+                        self.theme.text_primary
+                    } else {
+                        self.theme.text_secondary
+                    })
+                    .when(is_active, |this| this.bg(self.theme.bg_selected))
+                    .hover(|style| style.bg(self.theme.bg_hover))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            let mut location = this.settings.worktree_location.clone();
+                            location.root = root;
+                            this.update_worktree_location(location, cx);
+                        }),
+                    )
+                    .child(format!(
+                        "{} {}",
+                        if is_active { "●" } else { "○" },
+                        root.description()
+                    )),
+            );
+        }
+
+        panel = panel.child(
+            div()
+                .mt_1()
+                .text_xs()
+                .text_color(self.theme.text_tertiary)
+                .child("NAME TEMPLATE"),
+        );
+        panel = panel.child(div().flex().flex_row().gap_1().children(
+            WORKTREE_NAME_TEMPLATES.into_iter().map(|template| {
+                let is_active = location.name_template == template;
+                div()
+                    .px_2()
+                    .py(px(2.0))
+                    .rounded_md()
+                    .text_xs()
+                    .cursor_pointer()
+                    .text_color(if is_active {
+                        self.theme.text_primary
+                    } else {
+                        self.theme.text_secondary
+                    })
+                    .when(is_active, |this| this.bg(self.theme.bg_selected))
+                    .hover(|style| style.bg(self.theme.bg_hover))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            let mut location = this.settings.worktree_location.clone();
+                            location.name_template = template.to_string();
+                            this.update_worktree_location(location, cx);
+                        }),
+                    )
+                    .child(template)
+            }),
+        ));
+
+        panel
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .truncate()
+                    .child(parent_preview),
+            )
+            .when(self.relocation_candidates > 0, |this| {
+                this.child(
+                    div()
+                        .mt_1()
+                        .px_2()
+                        .py_1()
+                        .rounded_md()
+                        .text_xs()
+                        .text_color(self.theme.text_primary)
+                        .bg(self.theme.bg_hover)
+                        .cursor_pointer()
+                        .hover(|style| style.bg(self.theme.bg_selected))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                this.handle_relocate_worktrees(cx);
+                            }),
+                        )
+                        .child(format!(
+                            "Move {} existing worktree(s) here",
+                            self.relocation_candidates
+                        )),
+                )
+            })
+            .child(
+                div()
+                    .mt_1()
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .child("TERMINAL"),
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py(px(2.0))
+                    .rounded_md()
+                    .text_xs()
+                    .cursor_pointer()
+                    .text_color(self.theme.text_secondary)
+                    .hover(|style| style.bg(self.theme.bg_hover))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                            let mut terminal = this.settings.terminal.clone();
+                            terminal.confirm_multiline_paste = !terminal.confirm_multiline_paste;
+                            this.update_terminal_settings(terminal, cx);
+                        }),
+                    )
+                    .child(format!(
+                        "{} Confirm multi-line paste",
+                        if self.settings.terminal.confirm_multiline_paste {
+                            "☑"
+                        } else {
+                            "☐"
+                        }
+                    )),
+            )
+    }
+
+    /// Render the list of worktrees
+    fn render_worktree_list(&self, cx: &mut Context<Self>) -> Div {
+        let mut container = div().flex().flex_col().gap_1().px_2().py_2().child(
+            div()
+                .text_xs()
+                .text_color(self.theme.text_tertiary)
+                .px_2()
+                .mb_1()
+                .child("WORKTREES"),
+        );
+
+        // Show inline creation input if in creation mode
+        if self.show_create_dialog {
+            container = container.child(self.render_inline_creation_input(cx));
+        }
+
+        if let Some(notice) = self.sidebar_notice.clone() {
+            container = container.child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .text_xs()
+                    .text_color(self.theme.text_secondary)
+                    .child(notice),
+            );
+        }
+
+        for (idx, worktree) in self.worktrees.iter().enumerate() {
+            container = container.child(self.render_worktree_item(idx, worktree, cx));
+
+            if self.context_menu_index == Some(idx) {
+                container = container.child(self.render_worktree_context_menu(idx, cx));
+            }
+
+            if let Some(pending) = &self.pending_removal
+                && pending.worktree.path == worktree.path
+            {
+                container = container.child(self.render_removal_confirmation(pending, cx));
+            }
+
+            if let Some(pending) = &self.pending_lock
+                && pending.worktree_path == worktree.path
+            {
+                container = container.child(self.render_lock_input(pending, cx));
+            }
+        }
+
+        container
+    }
+
+    /// Render the context actions of a worktree
+    fn render_worktree_context_menu(&self, idx: usize, cx: &mut Context<Self>) -> Div {
+        let is_root = idx == 0;
+        let is_locked = self.worktrees.get(idx).is_some_and(|wt| wt.is_locked);
+
+        div()
+            .flex()
+            .flex_col()
+            .mx_2()
+            .mb_1()
+            .py_1()
+            .bg(self.theme.bg_primary)
+            .border_1()
+            .border_color(self.theme.border_subtle)
+            .rounded_md()
+            .when(!is_root && is_locked, |this| {
+                this.child(self.render_context_menu_item(
+                    "Unlock worktree",
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.handle_unlock(idx, cx);
+                    }),
+                ))
+            })
+            .when(!is_root && !is_locked, |this| {
+                this.child(self.render_context_menu_item(
+                    "Lock worktree…",
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.handle_lock_request(idx, cx);
+                    }),
+                ))
+                .child(self.render_context_menu_item(
+                    "Remove worktree…",
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.handle_remove_request(idx, cx);
+                    }),
+                ))
+            })
+            .child(self.render_context_menu_item(
+                "Prune stale worktrees",
+                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                    this.handle_prune_worktrees(cx);
+                }),
+            ))
+    }
+
+    /// Render a single context menu entry
+    fn render_context_menu_item(
+        &self,
+        label: &'static str,
+        listener: impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static,
+    ) -> Div {
+        div()
+            .px_3()
+            .py_1()
+            .text_sm()
+            .text_color(self.theme.text_secondary)
+            .cursor_pointer()
+            .hover(|style| {
+                style
+                    .bg(self.theme.bg_hover)
+                    .text_color(self.theme.text_primary)
+            })
+            .on_mouse_down(MouseButton::Left, listener)
+            .child(label)
+    }
+
+    /// Render the confirmation for removing a worktree
+    fn render_removal_confirmation(&self, pending: &PendingRemoval, cx: &mut Context<Self>) -> Div {
+        let error_color = hsla(0.0, 0.7, 0.6, 1.0);
+
+        let (changes_text, changes_color) = match pending.uncommitted_changes {
+            Some(0) => (
+                "No uncommitted changes".to_string(),
+                self.theme.text_secondary,
+            ),
+            Some(1) => ("1 uncommitted change will be lost".to_string(), error_color),
+            Some(count) => (
+                format!("{} uncommitted changes will be lost", count),
+                error_color,
+            ),
+            None => (
+                "Could not read status; changes may be lost".to_string(),
+                error_color,
+            ),
+        };
+        let is_dirty = pending.uncommitted_changes != Some(0);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .mx_2()
+            .mb_2()
+            .px_3()
+            .py_2()
+            .bg(self.theme.bg_primary)
+            .border_1()
+            .border_color(error_color)
+            .rounded_md()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(self.theme.text_primary)
+                    .child("Remove this worktree?"),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(changes_color)
+                    .child(changes_text),
+            )
+            .when(!pending.worktree.is_detached, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(self.theme.text_secondary)
+                        .cursor_pointer()
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                this.toggle_delete_branch(cx);
+                            }),
+                        )
+                        .child(format!(
+                            "{} Delete branch '{}' if merged",
+                            if pending.delete_branch { "☑" } else { "☐" },
+                            pending.worktree.branch
+                        )),
+                )
+            })
+            .when_some(pending.error.clone(), |this, error| {
+                this.child(div().text_xs().text_color(error_color).child(error))
+            })
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .justify_end()
+                    .gap_2()
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .text_xs()
+                            .text_color(self.theme.text_secondary)
+                            .cursor_pointer()
+                            .hover(|style| style.bg(self.theme.bg_hover))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.handle_remove_cancel(cx);
+                                }),
+                            )
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .text_xs()
+                            .bg(error_color)
+                            .text_color(self.theme.bg_primary)
+                            .cursor_pointer()
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.handle_remove_confirm(cx);
+                                }),
+                            )
+                            .child(if is_dirty { "Force remove" } else { "Remove" }),
+                    ),
+            )
+    }
+
+    /// Render the reason input for locking a worktree
+    fn render_lock_input(&self, pending: &PendingLock, cx: &mut Context<Self>) -> Div {
+        let has_reason = !pending.reason.is_empty();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .mx_2()
+            .mb_2()
+            .px_3()
+            .py_2()
+            .bg(self.theme.bg_primary)
+            .border_1()
+            .border_color(self.theme.accent)
+            .rounded_md()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(self.theme.text_tertiary)
+                    .child("Lock reason (optional)"),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if has_reason {
+                        self.theme.text_primary
+                    } else {
+                        self.theme.text_tertiary
+                    })
+                    .child(if has_reason {
+                        pending.reason.clone()
+                    } else {
+                        "e.g. on removable drive".to_string()
+                    }),
             )
+            .when_some(pending.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(hsla(0.0, 0.7, 0.6, 1.0))
+                        .child(error),
+                )
+            })
             .child(
                 div()
                     .flex()
                     .flex_row()
-                    .gap_1()
-                    .child(self.render_create_button(cx))
-                    .child(self.render_settings_button(cx)),
-            )
-    }
-
-    /// Render the create worktree button
-    fn render_create_button(&self, cx: &mut Context<Self>) -> Div {
-        div()
-            .w(px(28.0))
-            .h(px(28.0))
-            .flex()
-            .items_center()
-            .justify_center()
-            .rounded_md()
-            .text_color(self.theme.text_secondary)
-            .cursor_pointer()
-            .hover(|style| style.bg(self.theme.bg_hover))
-            .on_mouse_down(
-                MouseButton::Left,
-                cx.listener(|this, _event: &MouseDownEvent, window, cx| {
-                    this.handle_create_button_click(window, cx);
-                }),
-            )
-            .child("+")
-    }
-
-    /// Render the settings button placeholder
-    fn render_settings_button(&self, cx: &mut Context<Self>) -> Div {
-        div()
-            .w(px(28.0))
-            .h(px(28.0))
-            .flex()
-            .items_center()
-            .justify_center()
-            .rounded_md()
-            .text_color(self.theme.text_secondary)
-            .cursor_pointer()
-            .hover(|style| style.bg(self.theme.bg_hover))
-            .on_mouse_down(
-                MouseButton::Left,
-                cx.listener(|_this, _event: &MouseDownEvent, _window, _cx| {
-                    println!("Settings button clicked");
-                }),
+                    .justify_end()
+                    .gap_2()
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .text_xs()
+                            .text_color(self.theme.text_secondary)
+                            .cursor_pointer()
+                            .hover(|style| style.bg(self.theme.bg_hover))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.handle_lock_cancel(cx);
+                                }),
+                            )
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .text_xs()
+                            .bg(self.theme.accent)
+                            .text_color(self.theme.bg_primary)
+                            .cursor_pointer()
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.handle_lock_confirm(cx);
+                                }),
+                            )
+                            .child("Lock"),
+                    ),
             )
-            .child("⚙")
-    }
-
-    /// Render the list of worktrees
-    fn render_worktree_list(&self, cx: &mut Context<Self>) -> Div {
-        let mut container = div().flex().flex_col().gap_1().px_2().py_2().child(
-            div()
-                .text_xs()
-                .text_color(self.theme.text_tertiary)
-                .px_2()
-                .mb_1()
-                .child("WORKTREES"),
-        );
-
-        // Show inline creation input if in creation mode
-        if self.show_create_dialog {
-            container = container.child(self.render_inline_creation_input(cx));
-        }
-
-        for (idx, worktree) in self.worktrees.iter().enumerate() {
-            container = container.child(self.render_worktree_item(idx, worktree, cx));
-        }
-
-        container
     }
 
     /// Render inline creation input in the worktree list
-    fn render_inline_creation_input(&self, _cx: &mut Context<Self>) -> Div {
+    fn render_inline_creation_input(&self, cx: &mut Context<Self>) -> Div {
+        let mode = self.dialog_mode;
+
         div()
             .flex()
             .flex_col()
@@ -427,11 +1535,22 @@ impl MainWindow {
             .border_1()
             .border_color(self.theme.accent)
             .rounded_md()
+            .child(
+                div().flex().flex_row().gap_1().children(
+                    [
+                        CreationMode::NewBranch,
+                        CreationMode::ExistingBranch,
+                        CreationMode::Detached,
+                    ]
+                    .into_iter()
+                    .map(|m| self.render_creation_mode_tab(m, cx)),
+                ),
+            )
             .child(
                 div()
                     .text_xs()
                     .text_color(self.theme.text_secondary)
-                    .child("New branch name:"),
+                    .child(mode.input_label()),
             )
             .child(
                 div()
@@ -441,12 +1560,33 @@ impl MainWindow {
                     .text_color(self.theme.text_primary)
                     .when(self.dialog_branch_name.is_empty(), |this| {
                         this.text_color(self.theme.text_tertiary)
-                            .child("feature/my-branch")
+                            .child(mode.placeholder())
                     })
                     .when(!self.dialog_branch_name.is_empty(), |this| {
                         this.child(self.dialog_branch_name.clone())
                     }),
             )
+            .when(mode == CreationMode::NewBranch, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(self.theme.text_secondary)
+                        .child(format!(
+                            "Based on: {}",
+                            self.dialog_base_ref.as_deref().unwrap_or("HEAD")
+                        )),
+                )
+            })
+            .child(self.render_ref_picker(cx))
+            .when_some(self.creation_location_hint(), |this, hint| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(self.theme.text_tertiary)
+                        .truncate()
+                        .child(hint),
+                )
+            })
             .when_some(self.dialog_error.clone(), |this, error| {
                 this.child(
                     div()
@@ -469,8 +1609,137 @@ impl MainWindow {
             )
     }
 
+    /// Render a mode tab of the creation dialog
+    fn render_creation_mode_tab(&self, mode: CreationMode, cx: &mut Context<Self>) -> Div {
+        let is_active = self.dialog_mode == mode;
+
+        div()
+            .px_2()
+            .py(px(2.0))
+            .rounded_md()
+            .text_xs()
+            .cursor_pointer()
+            .when(is_active, |this| {
+                this.bg(self.theme.bg_selected)
+                    .text_color(self.theme.text_primary)
+            })
+            .when(!is_active, |this| {
+                this.text_color(self.theme.text_tertiary)
+                    .hover(|style| style.bg(self.theme.bg_hover))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                    this.handle_dialog_mode_click(mode, cx);
+                }),
+            )
+            .child(mode.label())
+    }
+
+    /// Render the base-ref picker (new branch) or the branch/ref suggestions
+    fn render_ref_picker(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        let mut list = div()
+            .id("creation-ref-picker")
+            .flex()
+            .flex_col()
+            .max_h(px(160.0))
+            .overflow_y_scroll();
+
+        // New branches can start from the current HEAD
+        if self.dialog_mode == CreationMode::NewBranch {
+            let is_selected = self.dialog_base_ref.is_none();
+            list = list.child(self.render_ref_item(
+                "HEAD".to_string(),
+                "current",
+                is_selected,
+                true,
+                None,
+                cx,
+            ));
+        }
+
+        for git_ref in self.dialog_ref_candidates() {
+            let kind_label = match git_ref.kind {
+                RefKind::LocalBranch => "local",
+                RefKind::RemoteBranch => "remote",
+                RefKind::Tag => "tag",
+            };
+            let is_selected = match self.dialog_mode {
+                CreationMode::NewBranch => self.dialog_base_ref.as_ref() == Some(&git_ref.name),
+                _ => *self.dialog_branch_name == *git_ref.name,
+            };
+            // A branch can only be checked out in one worktree at a time
+            let is_available = self.dialog_mode != CreationMode::ExistingBranch
+                || git_ref.kind != RefKind::LocalBranch
+                || !self.is_branch_checked_out(&git_ref.name);
+
+            list = list.child(self.render_ref_item(
+                git_ref.name.clone(),
+                if is_available {
+                    kind_label
+                } else {
+                    "checked out"
+                },
+                is_selected,
+                is_available,
+                Some(git_ref.name.clone()),
+                cx,
+            ));
+        }
+
+        list
+    }
+
+    /// Render a single entry of the ref picker
+    fn render_ref_item(
+        &self,
+        label: String,
+        kind_label: &'static str,
+        is_selected: bool,
+        is_available: bool,
+        value: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        div()
+            .flex()
+            .flex_row()
+            .justify_between()
+            .gap_2()
+            .px_2()
+            .py(px(2.0))
+            .rounded_md()
+            .text_xs()
+            .when(is_selected, |this| this.bg(self.theme.bg_selected))
+            .when(is_available, |this| {
+                this.cursor_pointer()
+                    .text_color(self.theme.text_secondary)
+                    .hover(|style| style.bg(self.theme.bg_hover))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            this.handle_ref_pick(value.clone(), cx);
+                        }),
+                    )
+            })
+            .when(!is_available, |this| {
+                this.text_color(self.theme.text_tertiary)
+            })
+            .child(div().min_w_0().truncate().child(label))
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .text_color(self.theme.text_tertiary)
+                    .child(kind_label),
+            )
+    }
+
     /// Render a single worktree item
-    fn render_worktree_item(&self, idx: usize, worktree: &Worktree, cx: &mut Context<Self>) -> Div {
+    fn render_worktree_item(
+        &self,
+        idx: usize,
+        worktree: &Worktree,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
         let is_selected = self.selected_worktree_index == idx;
         let is_root = idx == 0;
 
@@ -491,13 +1760,32 @@ impl MainWindow {
                 .to_string()
         };
 
+        let bootstrap_status = self
+            .bootstraps
+            .get(&worktree.path)
+            .map(|view| view.read(cx).status().clone());
+
         let branch_display = if worktree.is_detached {
             format!("detached: {}", &worktree.branch)
         } else {
             worktree.branch.clone()
         };
 
+        let status = self.status_service.read(cx).status(&worktree.path).cloned();
+
+        let mut tooltip_lines: Vec<SharedString> = vec![worktree.path.display().to_string().into()];
+        if worktree.is_locked {
+            tooltip_lines.push(match &worktree.lock_reason {
+                Some(reason) => format!("🔒 Locked: {}", reason).into(),
+                None => "🔒 Locked".into(),
+            });
+        }
+        if let Some(status) = &status {
+            tooltip_lines.extend(status_summary(status).into_iter().map(SharedString::from));
+        }
+
         div()
+            .id(("worktree-item", idx))
             .flex()
             .flex_col()
             .gap_1()
@@ -506,6 +1794,7 @@ impl MainWindow {
             .bg(bg_color)
             .rounded_md()
             .cursor_pointer()
+            .tooltip(move |_window, cx| TextTooltip::build(tooltip_lines.clone(), cx))
             .hover(|style| {
                 if !is_selected {
                     style.bg(self.theme.bg_hover)
@@ -519,6 +1808,12 @@ impl MainWindow {
                     this.handle_worktree_click(idx, cx);
                 }),
             )
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                    this.handle_worktree_right_click(idx, cx);
+                }),
+            )
             .child(
                 div()
                     .flex()
@@ -547,6 +1842,13 @@ impl MainWindow {
                                 .text_color(self.theme.text_tertiary)
                                 .child("🔒"),
                         )
+                    })
+                    .when_some(bootstrap_status, |this, status| {
+                        let (badge, color) = match status {
+                            BootstrapStatus::Failed(_) => ("setup failed", self.theme.error),
+                            _ => ("setting up…", self.theme.text_tertiary),
+                        };
+                        this.child(div().text_xs().text_color(color).child(badge))
                     }),
             )
             .child(
@@ -555,33 +1857,168 @@ impl MainWindow {
                     .text_color(self.theme.text_tertiary)
                     .child(branch_display),
             )
+            .when_some(status, |this, status| {
+                this.child(self.render_status_badges(&status))
+            })
+    }
+
+    /// Render compact badges summarizing a worktree's status
+    fn render_status_badges(&self, status: &WorktreeStatus) -> Div {
+        let badge = |text: String, color: Hsla| div().text_xs().text_color(color).child(text);
+
+        div()
+            .flex()
+            .flex_row()
+            .flex_wrap()
+            .gap_2()
+            .when_some(status.operation, |this, operation| {
+                this.child(
+                    badge(operation.label().to_uppercase(), self.theme.error)
+                        .font_weight(FontWeight::SEMIBOLD),
+                )
+            })
+            .when(status.conflicted > 0, |this| {
+                this.child(badge(format!("!{}", status.conflicted), self.theme.error))
+            })
+            .when(status.staged > 0, |this| {
+                this.child(badge(
+                    format!("+{}", status.staged),
+                    self.theme.diff_added_text,
+                ))
+            })
+            .when(status.modified > 0, |this| {
+                this.child(badge(
+                    format!("~{}", status.modified),
+                    self.theme.text_secondary,
+                ))
+            })
+            .when(status.untracked > 0, |this| {
+                this.child(badge(
+                    format!("?{}", status.untracked),
+                    self.theme.text_tertiary,
+                ))
+            })
+            .when_some(
+                status.upstream.as_ref().filter(|ab| !ab.is_even()),
+                |this, upstream| {
+                    this.child(badge(
+                        format!("↑{} ↓{}", upstream.ahead, upstream.behind),
+                        self.theme.accent,
+                    ))
+                },
+            )
+            .when_some(status.default_branch.as_ref(), |this, default| {
+                this.child(badge(
+                    format!(
+                        "{} ↑{} ↓{}",
+                        default.reference, default.ahead, default.behind
+                    ),
+                    self.theme.text_tertiary,
+                ))
+            })
+    }
+
+    /// Render the content area with the tab bar and the active tab
+    fn render_content(&self, cx: &mut Context<Self>) -> Div {
+        let body = match self.active_tab {
+            FeatureTab::Terminal => self.render_terminal_tab().into_any_element(),
+            FeatureTab::GitReview => self.render_review_tab().into_any_element(),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_w_0()
+            .bg(self.theme.bg_primary)
+            .child(self.render_tab_bar(cx))
+            .child(div().flex().flex_col().flex_1().min_h_0().child(body))
+    }
+
+    /// Render the tab switcher above the feature view
+    fn render_tab_bar(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .flex_row()
+            .gap_1()
+            .px_2()
+            .pt_2()
+            .bg(self.theme.bg_surface)
+            .border_b_1()
+            .border_color(self.theme.border_subtle)
+            .child(self.render_tab(FeatureTab::Terminal, "Terminal", cx))
+            .child(self.render_tab(FeatureTab::GitReview, "Git Review", cx))
+    }
+
+    /// Render a single tab button
+    fn render_tab(&self, tab: FeatureTab, label: &'static str, cx: &mut Context<Self>) -> Div {
+        let is_active = self.active_tab == tab;
+
+        div()
+            .px_4()
+            .py_2()
+            .rounded_t_md()
+            .text_sm()
+            .cursor_pointer()
+            .when(is_active, |this| {
+                this.bg(self.theme.bg_primary)
+                    .text_color(self.theme.text_primary)
+                    .font_weight(FontWeight::SEMIBOLD)
+            })
+            .when(!is_active, |this| {
+                this.text_color(self.theme.text_secondary)
+                    .hover(|style| style.bg(self.theme.bg_hover))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                    this.handle_tab_click(tab, cx);
+                }),
+            )
+            .child(label)
     }
 
-    /// Render the content area with terminal or placeholder
-    fn render_content(&self, _cx: &mut Context<Self>) -> impl IntoElement {
-        // If there's a terminal error, show error message
-        if let Some(error) = &self.terminal_error {
+    /// Render the git review tab
+    fn render_review_tab(&self) -> impl IntoElement {
+        if let Some(review_view) = &self.active_review_view {
             return div()
                 .flex()
                 .flex_col()
                 .flex_1()
                 .bg(self.theme.bg_primary)
-                .items_center()
-                .justify_center()
-                .gap_4()
-                .child(
-                    div()
-                        .text_lg()
-                        .text_color(self.theme.text_primary)
-                        .child("Terminal Error"),
-                )
-                .child(
-                    div()
-                        .text_sm()
-                        .text_color(hsla(0.0, 0.7, 0.6, 1.0))
-                        .px_8()
-                        .child(error.clone()),
-                )
+                .child(review_view.clone())
+                .into_any_element();
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .bg(self.theme.bg_primary)
+            .items_center()
+            .justify_center()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(self.theme.text_secondary)
+                    .child("Select a worktree to review its changes"),
+            )
+            .into_any_element()
+    }
+
+    /// Render the terminal tab with terminal or placeholder
+    fn render_terminal_tab(&self) -> impl IntoElement {
+        // A worktree being set up shows its bootstrap log until it is ready
+        if let Some(bootstrap) = self
+            .selected_worktree()
+            .and_then(|wt| self.bootstraps.get(&wt.path))
+        {
+            return div()
+                .flex()
+                .flex_col()
+                .flex_1()
+                .bg(self.theme.bg_primary)
+                .child(bootstrap.clone())
                 .into_any_element();
         }
 
@@ -744,10 +2181,69 @@ impl Render for MainWindow {
     }
 }
 
+/// Describe a worktree status in full sentences for the sidebar tooltip
+fn status_summary(status: &WorktreeStatus) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    if let Some(operation) = status.operation {
+        lines.push(format!("In progress: {}", operation.label()));
+    }
+    if status.is_clean() {
+        lines.push("No local changes".to_string());
+    } else {
+        lines.push(format!(
+            "{} staged, {} modified, {} untracked, {} conflicted",
+            status.staged, status.modified, status.untracked, status.conflicted
+        ));
+    }
+    for ahead_behind in [&status.upstream, &status.default_branch]
+        .into_iter()
+        .flatten()
+    {
+        lines.push(format!(
+            "{} ahead, {} behind {}",
+            ahead_behind.ahead, ahead_behind.behind, ahead_behind.reference
+        ));
+    }
+
+    lines
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     // Note: Tests requiring Context<Self> are skipped as they need GPUI runtime
     // The MainWindow::new and with_error methods require a GPUI context which is not available in unit tests
+
+    #[test]
+    fn test_status_summary() {
+        use crate::git::{AheadBehind, RepoOperation};
+
+        assert_eq!(
+            status_summary(&WorktreeStatus::default()),
+            vec!["No local changes"]
+        );
+
+        let status = WorktreeStatus {
+            staged: 1,
+            modified: 2,
+            conflicted: 1,
+            operation: Some(RepoOperation::Merge),
+            default_branch: Some(AheadBehind {
+                reference: "origin/main".to_string(),
+                ahead: 3,
+                behind: 1,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            status_summary(&status),
+            vec![
+                "In progress: merging",
+                "1 staged, 2 modified, 0 untracked, 1 conflicted",
+                "3 ahead, 1 behind origin/main",
+            ]
+        );
+    }
 }
@@ -1,8 +1,16 @@
+pub mod bootstrap_view;
 pub mod creation_dialog;
+pub mod git_review_view;
 pub mod main_window;
 pub mod sidebar;
+pub mod status_service;
+pub mod terminal_element;
+pub mod terminal_layout_view;
+pub mod terminal_row;
 pub mod terminal_view;
 pub mod theme;
+pub mod tooltip;
 pub mod workspace_selector;
+pub mod workspace_watcher;
 
 pub use workspace_selector::WorkspaceSelector;
@@ -0,0 +1,112 @@
+use crate::git::WorktreeStatus;
+use crate::git::status::get_worktree_status;
+use gpui::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often statuses are recomputed as a fallback for changes the file
+/// watcher doesn't see, such as a fetch in another clone of the repository
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Keeps the status summary of every worktree up to date in the background
+///
+/// Statuses are computed on the background executor, one refresh at a time;
+/// refreshes requested while one is running are coalesced into a single
+/// follow-up. Observers are notified whenever new statuses arrive.
+pub struct WorktreeStatusService {
+    worktree_paths: Vec<PathBuf>,
+    statuses: HashMap<PathBuf, WorktreeStatus>,
+    refreshing: bool,
+    refresh_queued: bool,
+}
+
+impl WorktreeStatusService {
+    /// Create the service and start refreshing periodically
+    pub fn new(worktree_paths: Vec<PathBuf>, cx: &mut Context<Self>) -> Self {
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                if this.update(cx, |service, cx| service.refresh(cx)).is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+
+        let mut service = Self {
+            worktree_paths,
+            statuses: HashMap::new(),
+            refreshing: false,
+            refresh_queued: false,
+        };
+        service.refresh(cx);
+        service
+    }
+
+    /// Status of a worktree, None until it has been computed
+    pub fn status(&self, worktree_path: &Path) -> Option<&WorktreeStatus> {
+        self.statuses.get(worktree_path)
+    }
+
+    /// Replace the set of worktrees to track and refresh their statuses
+    pub fn set_worktrees(&mut self, worktree_paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        self.statuses
+            .retain(|path, _| worktree_paths.contains(path));
+        self.worktree_paths = worktree_paths;
+        self.refresh(cx);
+    }
+
+    /// Recompute all statuses in the background
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        if self.refreshing {
+            self.refresh_queued = true;
+            return;
+        }
+        self.refreshing = true;
+
+        let paths = self.worktree_paths.clone();
+        let task = cx.background_executor().spawn(async move {
+            paths
+                .into_iter()
+                .filter_map(|path| match get_worktree_status(&path) {
+                    Ok(status) => Some((path, status)),
+                    Err(e) => {
+                        eprintln!("Failed to read status of {}: {}", path.display(), e);
+                        None
+                    }
+                })
+                .collect::<HashMap<_, _>>()
+        });
+
+        cx.spawn(async move |this, cx| {
+            let statuses = task.await;
+            let _ = this.update(cx, |service, cx| service.finish_refresh(statuses, cx));
+        })
+        .detach();
+    }
+
+    fn finish_refresh(
+        &mut self,
+        statuses: HashMap<PathBuf, WorktreeStatus>,
+        cx: &mut Context<Self>,
+    ) {
+        self.refreshing = false;
+
+        // Drop results for worktrees removed while the refresh was running
+        let statuses: HashMap<_, _> = statuses
+            .into_iter()
+            .filter(|(path, _)| self.worktree_paths.contains(path))
+            .collect();
+
+        if statuses != self.statuses {
+            self.statuses = statuses;
+            cx.notify();
+        }
+
+        if self.refresh_queued {
+            self.refresh_queued = false;
+            self.refresh(cx);
+        }
+    }
+}
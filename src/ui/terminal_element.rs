@@ -0,0 +1,418 @@
+//! GPUI element painting the terminal grid at exact cell coordinates.
+//!
+//! Each row is painted as background quads followed by its text runs,
+//! shaped with every glyph advance forced to the cell width, so columns line
+//! up regardless of the font's own metrics. Wide characters get a run of
+//! their own starting at their first cell. Underlines are drawn here rather
+//! than by the text system to support the styles terminals use, and the
+//! cursor is painted on top in the shape the application asked for.
+//!
+//! Box-drawing, block, Powerline and Braille characters are painted as
+//! geometry filling their cells instead of font glyphs, so the borders and
+//! charts of TUIs are seamless whatever the font.
+
+use std::sync::Arc;
+
+use gpui::*;
+
+use crate::terminal::builtin_glyphs::{CellBox, GlyphShape, builtin_glyph};
+use crate::terminal::render::{BatchedTextRun, CursorShape, LineRuns, Underline};
+
+/// Font and cell size of the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMetrics {
+    pub font_family: SharedString,
+    pub font_size: Pixels,
+    pub cell_width: Pixels,
+    pub line_height: Pixels,
+}
+
+impl CellMetrics {
+    /// Bounds of `cells` cells starting at `col`, relative to the row origin
+    fn cells(&self, origin: Point<Pixels>, col: u16, cells: usize) -> Bounds<Pixels> {
+        Bounds::new(
+            point(origin.x + self.cell_width * col as f32, origin.y),
+            size(self.cell_width * cells as f32, self.line_height),
+        )
+    }
+
+    /// Width of underlines and light box-drawing lines
+    fn line_thickness(&self) -> Pixels {
+        (self.font_size / 14.).max(px(1.)).round()
+    }
+
+    /// Bounds of cells rounded to whole pixels, so that geometry drawn in
+    /// neighbouring cells meets without seams
+    fn snapped_cells(&self, origin: Point<Pixels>, col: u16, cells: usize) -> Bounds<Pixels> {
+        let bounds = self.cells(origin, col, cells);
+        Bounds::from_corners(
+            point(bounds.origin.x.round(), bounds.origin.y.round()),
+            point(bounds.right().round(), bounds.bottom().round()),
+        )
+    }
+
+    /// Cell box for drawing built-in glyphs into `bounds`
+    fn cell_box(&self, bounds: Bounds<Pixels>) -> CellBox {
+        CellBox {
+            width: bounds.size.width.into(),
+            height: bounds.size.height.into(),
+            thickness: self.line_thickness().into(),
+        }
+    }
+
+    fn font(&self, bold: bool, italic: bool) -> Font {
+        Font {
+            weight: if bold {
+                FontWeight::BOLD
+            } else {
+                FontWeight::NORMAL
+            },
+            style: if italic {
+                FontStyle::Italic
+            } else {
+                FontStyle::Normal
+            },
+            ..font(self.font_family.clone())
+        }
+    }
+
+    /// Shapes text so that every glyph takes up exactly one cell
+    fn shape(
+        &self,
+        text: &str,
+        color: Hsla,
+        bold: bool,
+        italic: bool,
+        window: &Window,
+    ) -> ShapedLine {
+        let run = TextRun {
+            len: text.len(),
+            font: self.font(bold, italic),
+            color,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        window.text_system().shape_line(
+            SharedString::from(text.to_string()),
+            self.font_size,
+            &[run],
+            Some(self.cell_width),
+        )
+    }
+}
+
+/// Row contents prepared for painting
+#[derive(Default)]
+pub struct RowLayout {
+    /// Shaped text by the column it starts at
+    text: Vec<(u16, ShapedLine)>,
+    /// Characters drawn as geometry
+    glyphs: Vec<BuiltinGlyph>,
+}
+
+struct BuiltinGlyph {
+    origin: Point<Pixels>,
+    color: Hsla,
+    shapes: Vec<GlyphShape>,
+}
+
+/// Paints one row of the grid
+pub struct TerminalRowElement {
+    line: Arc<LineRuns>,
+    metrics: CellMetrics,
+}
+
+impl TerminalRowElement {
+    pub fn new(line: Arc<LineRuns>, metrics: CellMetrics) -> Self {
+        TerminalRowElement { line, metrics }
+    }
+}
+
+impl IntoElement for TerminalRowElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for TerminalRowElement {
+    type RequestLayoutState = ();
+    type PrepaintState = RowLayout;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.size.width = relative(1.).into();
+        style.size.height = self.metrics.line_height.into();
+        (window.request_layout(style, [], cx), ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        _cx: &mut App,
+    ) -> Self::PrepaintState {
+        let mut layout = RowLayout::default();
+        // Blank runs only matter for their decorations
+        for run in self
+            .line
+            .text_runs
+            .iter()
+            .filter(|run| !run.text.chars().all(|c| c == ' '))
+        {
+            let color: Hsla = rgb(run.style.fg.to_u32()).into();
+            let style = &run.style;
+            if !style.is_decorative {
+                let shaped = self
+                    .metrics
+                    .shape(&run.text, color, style.bold, style.italic, window);
+                layout.text.push((run.start_col, shaped));
+                continue;
+            }
+
+            // Decorative characters are drawn one by one, as geometry where
+            // there is a drawing for them
+            let cells = if run.text.chars().count() == 1 {
+                run.cell_count
+            } else {
+                1
+            };
+            for (i, c) in run.text.chars().enumerate() {
+                let col = run.start_col + (i * cells) as u16;
+                let cell = self.metrics.snapped_cells(bounds.origin, col, cells);
+                match builtin_glyph(c, self.metrics.cell_box(cell)) {
+                    Some(shapes) => layout.glyphs.push(BuiltinGlyph {
+                        origin: cell.origin,
+                        color,
+                        shapes,
+                    }),
+                    None => {
+                        let text = c.to_string();
+                        let shaped =
+                            self.metrics
+                                .shape(&text, color, style.bold, style.italic, window);
+                        layout.text.push((col, shaped));
+                    }
+                }
+            }
+        }
+        layout
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        layout: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let origin = bounds.origin;
+        let metrics = &self.metrics;
+
+        for bg in &self.line.backgrounds {
+            let cells = (bg.end_col - bg.start_col) as usize;
+            window.paint_quad(fill(
+                metrics.cells(origin, bg.start_col, cells),
+                rgb(bg.color.to_u32()),
+            ));
+        }
+
+        for (col, shaped) in &layout.text {
+            let run_origin = metrics.cells(origin, *col, 1).origin;
+            if let Err(e) = shaped.paint(run_origin, metrics.line_height, window, cx) {
+                eprintln!("Failed to paint terminal text: {}", e);
+            }
+        }
+
+        for glyph in &layout.glyphs {
+            paint_glyph(glyph, window);
+        }
+
+        // Decorations go over the text, so descenders don't cover them
+        for run in &self.line.text_runs {
+            paint_decorations(run, origin, metrics, window);
+        }
+    }
+}
+
+/// Paints the shapes of a built-in glyph
+fn paint_glyph(glyph: &BuiltinGlyph, window: &mut Window) {
+    let at = |x: f32, y: f32| point(glyph.origin.x + px(x), glyph.origin.y + px(y));
+    for shape in &glyph.shapes {
+        match shape {
+            GlyphShape::Rect {
+                x,
+                y,
+                width,
+                height,
+                alpha,
+            } => {
+                window.paint_quad(fill(
+                    Bounds::new(at(*x, *y), size(px(*width), px(*height))),
+                    glyph.color.opacity(*alpha),
+                ));
+            }
+            GlyphShape::Polygon(points) => {
+                let points: Vec<_> = points.iter().map(|&(x, y)| at(x, y)).collect();
+                let mut path = PathBuilder::fill();
+                path.add_polygon(&points, true);
+                match path.build() {
+                    Ok(path) => window.paint_path(path, glyph.color),
+                    Err(e) => eprintln!("Failed to paint terminal glyph: {}", e),
+                }
+            }
+        }
+    }
+}
+
+/// Paints the underline and strikethrough of a run
+fn paint_decorations(
+    run: &BatchedTextRun,
+    origin: Point<Pixels>,
+    metrics: &CellMetrics,
+    window: &mut Window,
+) {
+    let style = &run.style;
+    if style.underline.is_none() && !style.strikethrough {
+        return;
+    }
+
+    let cells = metrics.cells(origin, run.start_col, run.cell_count);
+    let thickness = metrics.line_thickness();
+    let color: Hsla = rgb(style.fg.to_u32()).into();
+
+    if style.strikethrough {
+        let y = cells.origin.y + metrics.line_height / 2.;
+        window.paint_quad(fill(
+            Bounds::new(point(cells.origin.x, y), size(cells.size.width, thickness)),
+            color,
+        ));
+    }
+
+    let Some(underline) = style.underline else {
+        return;
+    };
+    let color: Hsla = style
+        .underline_color
+        .map(|color| rgb(color.to_u32()).into())
+        .unwrap_or(color);
+    // Just below the baseline, leaving room for a second line or a wave
+    let y = cells.origin.y + metrics.line_height - thickness * 3.;
+    let left = cells.origin.x;
+    let width = cells.size.width;
+
+    let line = |window: &mut Window, y: Pixels, wavy: bool| {
+        window.paint_underline(
+            point(left, y),
+            width,
+            &UnderlineStyle {
+                thickness,
+                color: Some(color),
+                wavy,
+            },
+        );
+    };
+    // Short segments of `length` every `period` pixels
+    let segments = |window: &mut Window, length: Pixels, period: Pixels| {
+        let mut x = left;
+        while x < left + width {
+            let length = length.min(left + width - x);
+            window.paint_quad(fill(
+                Bounds::new(point(x, y + thickness), size(length, thickness)),
+                color,
+            ));
+            x += period;
+        }
+    };
+
+    match underline {
+        Underline::Single => line(window, y + thickness, false),
+        Underline::Double => {
+            line(window, y, false);
+            line(window, y + thickness * 2., false);
+        }
+        Underline::Curly => line(window, y, true),
+        Underline::Dotted => segments(window, thickness, thickness * 2.),
+        Underline::Dashed => segments(window, metrics.cell_width * 0.6, metrics.cell_width),
+    }
+}
+
+/// The cursor as it should be painted
+#[derive(Debug, Clone)]
+pub struct TerminalCursor {
+    pub row: u16,
+    pub col: u16,
+    pub shape: CursorShape,
+    /// Covers both cells of a wide character
+    pub wide: bool,
+    /// Character under the cursor, drawn inverted inside a block cursor
+    pub text: Option<char>,
+}
+
+/// Paints the cursor over the grid whose top left corner is `origin`
+pub fn paint_cursor(
+    origin: Point<Pixels>,
+    cursor: &TerminalCursor,
+    metrics: &CellMetrics,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let color: Hsla = rgb(0xffffff).into();
+    let row_origin = point(origin.x, origin.y + metrics.line_height * cursor.row as f32);
+    let cell = metrics.cells(row_origin, cursor.col, if cursor.wide { 2 } else { 1 });
+    let thickness = (metrics.cell_width / 6.).max(px(1.)).round();
+
+    match cursor.shape {
+        CursorShape::Block => {
+            window.paint_quad(fill(cell, color));
+            if let Some(c) = cursor.text.filter(|c| *c != ' ') {
+                let shaped =
+                    metrics.shape(&c.to_string(), rgb(0x000000).into(), false, false, window);
+                if let Err(e) = shaped.paint(cell.origin, metrics.line_height, window, cx) {
+                    eprintln!("Failed to paint cursor: {}", e);
+                }
+            }
+        }
+        CursorShape::HollowBlock => {
+            window.paint_quad(outline(cell, color, BorderStyle::Solid));
+        }
+        CursorShape::Beam => {
+            window.paint_quad(fill(
+                Bounds::new(cell.origin, size(thickness, metrics.line_height)),
+                color,
+            ));
+        }
+        CursorShape::Underline => {
+            window.paint_quad(fill(
+                Bounds::new(
+                    point(cell.origin.x, cell.bottom() - thickness),
+                    size(cell.size.width, thickness),
+                ),
+                color,
+            ));
+        }
+        CursorShape::Hidden => {}
+    }
+}
@@ -0,0 +1,443 @@
+use crate::settings::TerminalSettings;
+use crate::terminal::layout::{FocusDirection, PaneId, PaneNode, SplitDirection, TerminalLayout};
+use crate::terminal::{SharedTerminalSession, TerminalSession};
+use crate::ui::terminal_view::TerminalView;
+use crate::ui::theme::Theme;
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Tab being renamed inline in the tab bar
+struct RenameState {
+    index: usize,
+    name: String,
+}
+
+/// Tabs and split panes of the terminals of one worktree
+///
+/// Owns one long-lived terminal session per pane. Sessions are spawned when
+/// a pane appears in the layout and stopped when it is closed; the layout is
+/// saved after every change so it is restored when the worktree is reopened.
+/// Every pane's view keeps processing its session's output while hidden.
+///
+/// Shortcuts: Ctrl+Shift+T new tab, Ctrl+Shift+D split side by side,
+/// Ctrl+Shift+E split stacked, Ctrl+Shift+W close pane, Ctrl+Shift+Arrows
+/// move focus, Ctrl+Shift+N/P next/previous pane, Ctrl+(Shift+)Tab cycle
+/// tabs. Double-click a tab to rename it.
+pub struct TerminalLayoutView {
+    layout: TerminalLayout,
+    sessions: HashMap<PaneId, SharedTerminalSession>,
+    views: HashMap<PaneId, Entity<TerminalView>>,
+    errors: HashMap<PaneId, String>,
+    renaming: Option<RenameState>,
+    settings: TerminalSettings,
+    /// Move keyboard focus to the focused pane on the next render
+    focus_pending: bool,
+    focus_handle: FocusHandle,
+    theme: Theme,
+}
+
+impl TerminalLayoutView {
+    /// Restore the saved layout of a worktree, or start with a single terminal
+    pub fn new(worktree_path: PathBuf, settings: TerminalSettings, cx: &mut Context<Self>) -> Self {
+        let layout = match TerminalLayout::load(&worktree_path) {
+            Ok(Some(layout)) => layout,
+            Ok(None) => TerminalLayout::new(worktree_path),
+            Err(e) => {
+                eprintln!("Failed to load terminal layout: {}", e);
+                TerminalLayout::new(worktree_path)
+            }
+        };
+
+        let mut view = Self {
+            layout,
+            sessions: HashMap::new(),
+            views: HashMap::new(),
+            errors: HashMap::new(),
+            renaming: None,
+            settings,
+            focus_pending: true,
+            focus_handle: cx.focus_handle(),
+            theme: Theme::new(),
+        };
+        view.sync_sessions(cx);
+        view
+    }
+
+    /// Spawn sessions for new panes, stop sessions of closed ones and save
+    /// the layout
+    fn sync_sessions(&mut self, cx: &mut Context<Self>) {
+        let panes = self.layout.all_panes();
+
+        let closed: Vec<PaneId> = self
+            .sessions
+            .keys()
+            .filter(|pane| !panes.contains(pane))
+            .copied()
+            .collect();
+        for pane in closed {
+            self.views.remove(&pane);
+            if let Some(session) = self.sessions.remove(&pane)
+                && let Err(e) = session.lock().unwrap().kill()
+            {
+                eprintln!("Failed to stop terminal session: {}", e);
+            }
+        }
+        self.errors.retain(|pane, _| panes.contains(pane));
+
+        for pane in panes {
+            if self.sessions.contains_key(&pane) || self.errors.contains_key(&pane) {
+                continue;
+            }
+            // The view fits the session to its pane on the first layout
+            match TerminalSession::new_shared(self.layout.worktree_path.clone(), None, 24, 80) {
+                Ok(session) => {
+                    let settings = self.settings.clone();
+                    let view = cx.new(|cx| TerminalView::new(session.clone(), settings, cx));
+                    self.sessions.insert(pane, session);
+                    self.views.insert(pane, view);
+                }
+                Err(e) => {
+                    self.errors
+                        .insert(pane, format!("Failed to create terminal session: {}", e));
+                }
+            }
+        }
+
+        if let Err(e) = self.layout.save() {
+            eprintln!("Failed to save terminal layout: {}", e);
+        }
+        self.focus_pending = true;
+        cx.notify();
+    }
+
+    /// Save the layout and the state of the focused session
+    pub fn save_state(&self) {
+        if let Err(e) = self.layout.save() {
+            eprintln!("Failed to save terminal layout: {}", e);
+        }
+        if let Some(session) = self.sessions.get(&self.layout.focused_pane())
+            && let Err(e) = session.lock().unwrap().save_state()
+        {
+            eprintln!("Failed to save session state: {}", e);
+        }
+    }
+
+    /// Apply changed terminal settings to every pane
+    pub fn set_settings(&mut self, settings: TerminalSettings, cx: &mut Context<Self>) {
+        for view in self.views.values() {
+            let settings = settings.clone();
+            view.update(cx, |view, _cx| view.set_settings(settings));
+        }
+        self.settings = settings;
+    }
+
+    /// Stop every session, used when the worktree goes away
+    pub fn kill_all(&mut self) {
+        self.views.clear();
+        for (_, session) in self.sessions.drain() {
+            if let Err(e) = session.lock().unwrap().kill() {
+                eprintln!("Failed to stop terminal session: {}", e);
+            }
+        }
+    }
+
+    fn new_tab(&mut self, cx: &mut Context<Self>) {
+        self.renaming = None;
+        self.layout.add_tab(None);
+        self.sync_sessions(cx);
+    }
+
+    fn close_tab(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.renaming = None;
+        self.layout.close_tab(index);
+        self.sync_sessions(cx);
+    }
+
+    fn select_tab(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.layout.select_tab(index);
+        self.layout_changed(cx);
+    }
+
+    fn split(&mut self, direction: SplitDirection, cx: &mut Context<Self>) {
+        self.layout.split_focused(direction);
+        self.sync_sessions(cx);
+    }
+
+    fn close_focused_pane(&mut self, cx: &mut Context<Self>) {
+        self.renaming = None;
+        self.layout.close_pane(self.layout.focused_pane());
+        self.sync_sessions(cx);
+    }
+
+    /// Save a change that didn't add or remove panes and refocus
+    fn layout_changed(&mut self, cx: &mut Context<Self>) {
+        if let Err(e) = self.layout.save() {
+            eprintln!("Failed to save terminal layout: {}", e);
+        }
+        self.focus_pending = true;
+        cx.notify();
+    }
+
+    fn start_rename(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.layout.tabs().get(index) else {
+            return;
+        };
+        self.renaming = Some(RenameState {
+            index,
+            name: tab.name.clone(),
+        });
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn finish_rename(&mut self, cx: &mut Context<Self>) {
+        if let Some(rename) = self.renaming.take() {
+            self.layout.rename_tab(rename.index, &rename.name);
+        }
+        self.layout_changed(cx);
+    }
+
+    fn cancel_rename(&mut self, cx: &mut Context<Self>) {
+        self.renaming = None;
+        self.focus_pending = true;
+        cx.notify();
+    }
+
+    /// Handle layout shortcuts before keys reach the focused terminal
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) {
+        let key = event.keystroke.key.as_str();
+        let modifiers = &event.keystroke.modifiers;
+
+        // Typing goes to the tab name while renaming
+        if let Some(rename) = &mut self.renaming {
+            match key {
+                "enter" => self.finish_rename(cx),
+                "escape" => self.cancel_rename(cx),
+                "backspace" => {
+                    rename.name.pop();
+                    cx.notify();
+                }
+                _ => match &event.keystroke.key_char {
+                    Some(text) if !modifiers.control && !modifiers.alt => {
+                        rename.name.push_str(text);
+                        cx.notify();
+                    }
+                    _ => return,
+                },
+            }
+            cx.stop_propagation();
+            return;
+        }
+
+        if !modifiers.control || modifiers.alt {
+            return;
+        }
+
+        if key == "tab" {
+            if modifiers.shift {
+                self.layout.previous_tab();
+            } else {
+                self.layout.next_tab();
+            }
+            self.layout_changed(cx);
+            cx.stop_propagation();
+            return;
+        }
+
+        if !modifiers.shift {
+            return;
+        }
+
+        match key {
+            "t" => self.new_tab(cx),
+            "w" => self.close_focused_pane(cx),
+            "d" => self.split(SplitDirection::Vertical, cx),
+            "e" => self.split(SplitDirection::Horizontal, cx),
+            "n" => {
+                self.layout.focus_next();
+                self.layout_changed(cx);
+            }
+            "p" => {
+                self.layout.focus_previous();
+                self.layout_changed(cx);
+            }
+            "left" | "right" | "up" | "down" => {
+                let direction = match key {
+                    "left" => FocusDirection::Left,
+                    "right" => FocusDirection::Right,
+                    "up" => FocusDirection::Up,
+                    _ => FocusDirection::Down,
+                };
+                if self.layout.focus_direction(direction) {
+                    self.layout_changed(cx);
+                }
+            }
+            _ => return,
+        }
+        cx.stop_propagation();
+    }
+
+    fn render_tab_bar(&self, cx: &mut Context<Self>) -> Div {
+        let active = self.layout.active_tab_index();
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .h(px(28.0))
+            .bg(self.theme.bg_surface)
+            .border_b_1()
+            .border_color(self.theme.border_subtle)
+            .track_focus(&self.focus_handle)
+            .children(self.layout.tabs().iter().enumerate().map(|(index, tab)| {
+                let renaming = self.renaming.as_ref().filter(|r| r.index == index);
+                let label = match renaming {
+                    Some(rename) => format!("{}▏", rename.name),
+                    None => tab.name.clone(),
+                };
+                let is_active = index == active;
+
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_2()
+                    .h_full()
+                    .px_3()
+                    .text_xs()
+                    .cursor_pointer()
+                    .border_r_1()
+                    .border_color(self.theme.border_subtle)
+                    .when(is_active, |this| {
+                        this.bg(self.theme.bg_primary)
+                            .text_color(self.theme.text_primary)
+                    })
+                    .when(!is_active, |this| {
+                        this.text_color(self.theme.text_secondary)
+                            .hover(|style| style.bg(self.theme.bg_hover))
+                    })
+                    .when(renaming.is_some(), |this| {
+                        this.border_b_1().border_color(self.theme.accent)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                            if event.click_count >= 2 {
+                                this.start_rename(index, window, cx);
+                            } else {
+                                this.select_tab(index, cx);
+                            }
+                        }),
+                    )
+                    .child(label)
+                    .child(
+                        div()
+                            .text_color(self.theme.text_tertiary)
+                            .hover(|style| style.text_color(self.theme.text_primary))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    cx.stop_propagation();
+                                    this.close_tab(index, cx);
+                                }),
+                            )
+                            .child("×"),
+                    )
+            }))
+            .child(
+                div()
+                    .px_3()
+                    .text_sm()
+                    .cursor_pointer()
+                    .text_color(self.theme.text_secondary)
+                    .hover(|style| style.text_color(self.theme.text_primary))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.new_tab(cx)),
+                    )
+                    .child("+"),
+            )
+    }
+
+    fn render_node(&self, node: &PaneNode, show_focus: bool, cx: &mut Context<Self>) -> Div {
+        match node {
+            PaneNode::Pane { id } => {
+                let pane = *id;
+                let focused = show_focus && pane == self.layout.focused_pane();
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .overflow_hidden()
+                    .border_1()
+                    .border_color(if focused {
+                        self.theme.accent
+                    } else {
+                        self.theme.border_subtle
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if this.layout.focused_pane() != pane {
+                                this.layout.focus_pane(pane);
+                                this.layout_changed(cx);
+                            }
+                        }),
+                    )
+                    .when_some(self.views.get(&pane), |this, view| this.child(view.clone()))
+                    .when_some(self.errors.get(&pane), |this, error| {
+                        this.items_center()
+                            .justify_center()
+                            .text_sm()
+                            .text_color(hsla(0.0, 0.7, 0.6, 1.0))
+                            .child(error.clone())
+                    })
+            }
+            PaneNode::Split {
+                direction,
+                first,
+                second,
+            } => div()
+                .flex()
+                .flex_1()
+                .overflow_hidden()
+                .when(*direction == SplitDirection::Horizontal, |this| {
+                    this.flex_col()
+                })
+                .when(*direction == SplitDirection::Vertical, |this| {
+                    this.flex_row()
+                })
+                .child(self.render_node(first, show_focus, cx))
+                .child(self.render_node(second, show_focus, cx)),
+        }
+    }
+}
+
+impl Render for TerminalLayoutView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.focus_pending && self.renaming.is_none() {
+            self.focus_pending = false;
+            if let Some(view) = self.views.get(&self.layout.focused_pane()) {
+                view.read(cx).focus(window);
+            }
+        }
+
+        // Only outline the focused pane when there is more than one
+        let root = self.layout.active_tab().root.clone();
+        let show_focus = matches!(root, PaneNode::Split { .. });
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(self.theme.bg_primary)
+            .capture_key_down(cx.listener(|this, event, _window, cx| {
+                this.handle_key_down(event, cx);
+            }))
+            .child(self.render_tab_bar(cx))
+            .child(self.render_node(&root, show_focus, cx))
+    }
+}
@@ -0,0 +1,41 @@
+//! One row of the terminal grid as a view of its own.
+//!
+//! The terminal view renders its rows as cached views, so GPUI reuses the
+//! layout and paint of every row whose runs didn't change since the last
+//! frame instead of building its elements again.
+
+use std::sync::Arc;
+
+use gpui::*;
+
+use crate::terminal::render::LineRuns;
+use crate::ui::terminal_element::{CellMetrics, TerminalRowElement};
+
+/// Backgrounds and text of one row of the viewport
+pub struct TerminalRow {
+    line: Arc<LineRuns>,
+    metrics: CellMetrics,
+}
+
+impl TerminalRow {
+    pub fn new(line: Arc<LineRuns>, metrics: CellMetrics) -> Self {
+        TerminalRow { line, metrics }
+    }
+
+    /// Replaces the runs and cell metrics of the row, returning whether
+    /// anything changed
+    pub fn set_content(&mut self, line: &Arc<LineRuns>, metrics: &CellMetrics) -> bool {
+        let changed = !Arc::ptr_eq(&self.line, line) || self.metrics != *metrics;
+        if changed {
+            self.line = line.clone();
+            self.metrics = metrics.clone();
+        }
+        changed
+    }
+}
+
+impl Render for TerminalRow {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        TerminalRowElement::new(self.line.clone(), self.metrics.clone())
+    }
+}
@@ -1,22 +1,31 @@
+use alacritty_terminal::index::Side;
+use alacritty_terminal::selection::SelectionType;
+use alacritty_terminal::term::TermMode;
+use gpui::prelude::FluentBuilder;
 use gpui::*;
+use std::cell::Cell;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::rc::Rc;
+use std::time::Duration;
 
-use crate::terminal::render::{RenderableContent, Rgba};
-use crate::terminal::TerminalSession;
-
-/// Text selection state for copy/paste functionality
-#[derive(Clone, Debug)]
-pub struct Selection {
-    pub start: (u16, u16),
-    pub end: (u16, u16),
-}
+use crate::settings::TerminalSettings;
+use crate::terminal::events::ExitStatus;
+use crate::terminal::keys::{KeyModifiers, KeyPress, encode_key};
+use crate::terminal::mouse::{self, MouseAction, MouseReport};
+use crate::terminal::paste;
+use crate::terminal::render::{CursorShape, RenderableContent};
+use crate::terminal::search::SearchQuery;
+use crate::terminal::{SharedTerminalSession, TerminalSession};
+use crate::ui::terminal_element::{CellMetrics, TerminalCursor, paint_cursor};
+use crate::ui::terminal_row::TerminalRow;
 
 /// Terminal view state
 #[derive(Clone, Debug, PartialEq)]
 pub enum TerminalState {
     Loading,
     Ready,
+    /// The shell ended; its output stays readable until it is restarted
+    Exited(ExitStatus),
     Error(String),
 }
 
@@ -36,46 +45,146 @@ const FALLBACK_FONTS: &[&str] = &[
     "Menlo",              // macOS alternative
 ];
 
+/// Width of the scrollbar track in pixels
+const SCROLLBAR_WIDTH: f32 = 10.0;
+
+/// Lines scrolled per notch of a mouse wheel without precise scrolling
+const LINES_PER_WHEEL_NOTCH: f32 = 3.0;
+
+/// Lines of a pending paste shown in the confirmation
+const PASTE_PREVIEW_LINES: usize = 5;
+
+/// Line height as a multiple of the font size
+const LINE_HEIGHT_FACTOR: f32 = 1.2;
+
+/// How long the view must keep its size before the PTY follows, so dragging
+/// a window edge doesn't send the shell a SIGWINCH on every frame
+const RESIZE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How long a blinking cursor stays shown or hidden
+const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Smallest grid the PTY is ever resized to
+const MIN_ROWS: u16 = 2;
+const MIN_COLS: u16 = 10;
+
+/// Grid size before the view has been laid out
+const DEFAULT_GRID_SIZE: (u16, u16) = (24, 80);
+
+/// State of the find bar while it is open
+struct FindBar {
+    query: SearchQuery,
+    /// Why the query could not be searched for, e.g. an invalid regex
+    error: Option<String>,
+}
+
 /// Terminal view component that renders terminal content and handles user input
 pub struct TerminalView {
-    session: Arc<Mutex<TerminalSession>>,
+    session: SharedTerminalSession,
     font_size: f32,
     font_family: String,
-    scroll_offset: usize,
-    selection: Option<Selection>,
+    /// Advance width of a cell in the configured font, in pixels
+    cell_width: f32,
+    /// Height of a terminal line in pixels
+    line_height: f32,
+    /// Rows and columns the session was last sized to, None until the
+    /// first layout
+    grid_size: Option<(u16, u16)>,
+    /// Debounced resize waiting for the view size to settle; replacing it
+    /// cancels the previous one
+    resize_task: Option<Task<()>>,
+    /// Wheel movement not yet amounting to a whole line
+    scroll_remainder: f32,
+    /// Whether the scrollbar thumb is being dragged
+    dragging_scrollbar: bool,
+    /// Whether a selection is being dragged with the left button
+    selecting: bool,
+    /// Button whose press was reported to the application, until released
+    reported_button: Option<mouse::MouseButton>,
+    /// Cell of the last reported mouse event, so motion is only reported
+    /// when it moves to another cell
+    last_reported_cell: Option<(usize, usize)>,
+    /// Multi-line paste waiting for confirmation
+    pending_paste: Option<String>,
+    settings: TerminalSettings,
+    /// Bounds of the cell grid from the last paint, for mapping mouse positions
+    content_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
     state: TerminalState,
     worktree_path: PathBuf,
     focus_handle: FocusHandle,
+    /// The find bar, when open
+    find: Option<FindBar>,
+    /// Focus of the find bar, so typing goes to the query instead of the shell
+    find_focus_handle: FocusHandle,
     /// Cached renderable content for efficient batched rendering
     cached_content: Option<RenderableContent>,
-    /// Counter for polling frames after user input
-    poll_frames_remaining: u32,
+    /// Views of the rows of the grid, reused between frames
+    rows: Vec<Entity<TerminalRow>>,
+    /// Whether the terminal had keyboard focus in the last render
+    focused: bool,
+    /// Whether a blinking cursor is in its visible phase
+    cursor_blink_on: bool,
+    /// Toggles the phase of a blinking cursor, only running while the
+    /// focused terminal shows one
+    blink_task: Option<Task<()>>,
+    /// Processes the session's output as the PTY produces it
+    _output_task: Task<()>,
+    /// Output generation of the session the cache was built from
+    cached_generation: u64,
 }
 
 impl TerminalView {
-    /// Creates a new terminal view with the given session
-    pub fn new(session: TerminalSession, cx: &mut Context<Self>) -> Self {
-        let worktree_path = session.worktree_path().clone();
-        let session_arc = Arc::new(Mutex::new(session));
+    /// Creates a terminal view attached to an existing session.
+    /// The session keeps running after the view is dropped, so a later view
+    /// attached to it picks up where this one left off.
+    pub fn new(
+        session: SharedTerminalSession,
+        settings: TerminalSettings,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let focus_handle = cx.focus_handle();
 
-        // Get initial renderable content
-        let cached_content = {
-            let session = session_arc.lock().unwrap();
-            Some(session.get_renderable_content())
+        // Catch up on output produced while no view was attached
+        let (worktree_path, cached_content, cached_generation) = {
+            let mut session = session.lock().unwrap();
+            session.process_events();
+            (
+                session.worktree_path().clone(),
+                Some(session.get_renderable_content()),
+                session.output_generation(),
+            )
         };
 
+        let output_task = Self::watch_output(&session, cx);
+
         TerminalView {
-            session: session_arc,
+            session,
             font_size: 14.0,
             font_family: DEFAULT_TERMINAL_FONT.to_string(),
-            scroll_offset: 0,
-            selection: None,
+            cell_width: 14.0 * 0.6,
+            line_height: 14.0 * LINE_HEIGHT_FACTOR,
+            grid_size: None,
+            resize_task: None,
+            scroll_remainder: 0.0,
+            dragging_scrollbar: false,
+            selecting: false,
+            reported_button: None,
+            last_reported_cell: None,
+            pending_paste: None,
+            settings,
+            content_bounds: Rc::new(Cell::new(None)),
             state: TerminalState::Ready,
             worktree_path,
             focus_handle,
+            find: None,
+            find_focus_handle: cx.focus_handle(),
             cached_content,
-            poll_frames_remaining: 10, // Start with some polling to catch initial output
+            rows: Vec::new(),
+            focused: false,
+            cursor_blink_on: true,
+            blink_task: None,
+            _output_task: output_task,
+            cached_generation,
         }
     }
 
@@ -84,6 +193,11 @@ impl TerminalView {
         self.font_family = font_family.into();
     }
 
+    /// Applies changed terminal settings
+    pub fn set_settings(&mut self, settings: TerminalSettings) {
+        self.settings = settings;
+    }
+
     /// Gets the current font family
     pub fn font_family(&self) -> &str {
         &self.font_family
@@ -94,49 +208,113 @@ impl TerminalView {
         FALLBACK_FONTS
     }
 
-    /// Polls for output and updates cached content if there's new data
-    fn poll_and_update_cache(&mut self) -> bool {
-        if let Ok(mut session) = self.session.lock() {
-            // Process events from the async channel
-            let had_output = match session.process_events() {
-                Some(had_output) => had_output,
-                None => {
-                    // Process exited
-                    self.state = TerminalState::Error("Process exited".to_string());
-                    false
+    /// Processes the session's output whenever its event loop reports new
+    /// events, including while the view is hidden. Notifications that arrive
+    /// before the view catches up are merged, and `cx.notify` renders once
+    /// per frame, so a burst of output doesn't cost a frame per batch.
+    fn watch_output(session: &SharedTerminalSession, cx: &mut Context<Self>) -> Task<()> {
+        let notifications = session.lock().unwrap().output_notifications();
+        cx.spawn(async move |this, cx| {
+            // Ends when the session shuts down or is replaced by a restart
+            while notifications.recv().await.is_ok() {
+                let updated = this.update(cx, |view, cx| {
+                    if view.state == TerminalState::Ready {
+                        view.process_output();
+                        cx.notify();
+                    }
+                });
+                if updated.is_err() {
+                    break;
                 }
-            };
-            if had_output {
+            }
+        })
+    }
+
+    /// Processes pending output and updates cached content if there's new data
+    fn process_output(&mut self) {
+        if let Ok(mut session) = self.session.lock() {
+            if session.process_events().is_none() {
+                // Process exited
+                let status = session.exit_status().unwrap_or(ExitStatus::Unknown);
+                self.state = TerminalState::Exited(status);
+            }
+            if session.output_generation() != self.cached_generation {
                 // Use batched renderable content for efficient rendering
                 self.cached_content = Some(session.get_renderable_content());
+                self.cached_generation = session.output_generation();
             }
-            had_output
-        } else {
-            false
         }
     }
 
+    /// Starts the blink timer when the focused terminal shows a blinking
+    /// cursor and drops it otherwise, so other terminals show the cursor
+    /// steadily and don't wake the UI
+    fn update_blink_task(&mut self, cx: &mut Context<Self>) {
+        let blinking = self.focused
+            && self
+                .cached_content
+                .as_ref()
+                .is_some_and(|content| content.cursor_blinking);
+        if blinking == self.blink_task.is_some() {
+            return;
+        }
+
+        self.cursor_blink_on = true;
+        self.blink_task = blinking.then(|| {
+            cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor().timer(CURSOR_BLINK_INTERVAL).await;
+                    if this.update(cx, |view, cx| view.blink_cursor(cx)).is_err() {
+                        break;
+                    }
+                }
+            })
+        });
+    }
+
+    /// Shows or hides a blinking cursor for its next phase
+    fn blink_cursor(&mut self, cx: &mut Context<Self>) {
+        self.cursor_blink_on = !self.cursor_blink_on;
+        cx.notify();
+    }
+
+    /// Font and cell size the grid is painted with
+    fn cell_metrics(&self) -> CellMetrics {
+        CellMetrics {
+            font_family: self.font_family.clone().into(),
+            font_size: px(self.font_size),
+            cell_width: px(self.cell_width),
+            line_height: px(self.line_height),
+        }
+    }
+
+    /// Moves keyboard focus to the terminal
+    pub fn focus(&self, window: &mut Window) {
+        self.focus_handle.focus(window);
+    }
+
     /// Restarts the terminal session
     pub fn restart_session(&mut self, cx: &mut Context<Self>) {
         self.state = TerminalState::Loading;
         cx.notify();
 
-        // Create a new session
-        match TerminalSession::new(self.worktree_path.clone(), None, 24, 80) {
+        // Create a new session at the size of the old one
+        let (rows, cols) = self.grid_size.unwrap_or(DEFAULT_GRID_SIZE);
+        match TerminalSession::new(self.worktree_path.clone(), None, rows, cols) {
             Ok(new_session) => {
-                let session_arc = Arc::new(Mutex::new(new_session));
-
-                // Get initial renderable content
+                // Replace the session in place so its other owners see the new one
                 {
-                    let session = session_arc.lock().unwrap();
+                    let mut session = self.session.lock().unwrap();
+                    *session = new_session;
                     self.cached_content = Some(session.get_renderable_content());
+                    self.cached_generation = session.output_generation();
                 }
 
-                self.session = session_arc;
                 self.state = TerminalState::Ready;
-                self.selection = None;
-                self.scroll_offset = 0;
-                self.poll_frames_remaining = 10;
+                self.selecting = false;
+                self.reported_button = None;
+                self.scroll_remainder = 0.0;
+                self._output_task = Self::watch_output(&self.session, cx);
 
                 eprintln!("Terminal session restarted successfully");
             }
@@ -151,319 +329,1015 @@ impl TerminalView {
 
     /// Checks if the session is alive and updates state accordingly
     fn check_session_health(&mut self) {
-        if let Ok(session) = self.session.lock() {
+        if let Ok(mut session) = self.session.lock() {
             if !session.is_alive() && self.state == TerminalState::Ready {
-                self.state = TerminalState::Error("Terminal session ended".to_string());
+                let status = session.exit_status().unwrap_or(ExitStatus::Unknown);
+                self.state = TerminalState::Exited(status);
+                // The last output may have been processed while the view was hidden
+                self.cached_content = Some(session.get_renderable_content());
+                self.cached_generation = session.output_generation();
             }
         }
     }
 
     /// Handles keyboard input
-    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) {
-        // Convert key event to terminal input sequence
-        let input = self.key_event_to_input(event);
+    fn handle_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // A pending paste takes the keyboard until it is confirmed or cancelled
+        if self.pending_paste.is_some() {
+            match event.keystroke.key.as_str() {
+                "enter" => self.confirm_paste(cx),
+                "escape" => self.cancel_paste(cx),
+                _ => {}
+            }
+            return;
+        }
 
-        if let Some(data) = input {
-            if let Ok(mut session) = self.session.lock() {
-                let _ = session.write_input(&data);
+        // Clipboard and find shortcuts never reach the shell
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control && modifiers.shift {
+            match event.keystroke.key.as_str() {
+                "c" => return self.handle_copy(cx),
+                "v" => return self.handle_paste(cx),
+                "f" => return self.open_find(window, cx),
+                _ => {}
             }
-            // Start polling for output
-            self.poll_frames_remaining = 30; // Poll for ~500ms after input
-            cx.notify();
         }
+
+        // Shift with paging keys moves through the scrollback
+        if modifiers.shift && !modifiers.control && !modifiers.alt {
+            match event.keystroke.key.as_str() {
+                "pageup" => return self.scroll_session(cx, |s| s.scroll_page_up()),
+                "pagedown" => return self.scroll_session(cx, |s| s.scroll_page_down()),
+                "home" => return self.scroll_session(cx, |s| s.scroll_to_top()),
+                "end" => return self.scroll_to_bottom(cx),
+                _ => {}
+            }
+        }
+
+        // Nothing is listening once the shell has exited
+        if self.state != TerminalState::Ready {
+            return;
+        }
+
+        let Ok(mut session) = self.session.lock() else {
+            return;
+        };
+        // Convert key event to terminal input sequence
+        let Some(data) = Self::key_event_to_input(event, session.mode()) else {
+            return;
+        };
+
+        // Typing brings the latest output back into view
+        if session.display_offset() != 0 {
+            session.scroll_to_bottom();
+            self.cached_content = Some(session.get_renderable_content());
+        }
+        let _ = session.write_input(&data);
+        drop(session);
+
+        // Keep a blinking cursor visible while typing
+        self.cursor_blink_on = true;
+        cx.notify();
     }
 
-    /// Converts GPUI key events to terminal input sequences
-    fn key_event_to_input(&self, event: &KeyDownEvent) -> Option<Vec<u8>> {
-        let key = &event.keystroke.key;
-
-        // Handle special keys
-        match key.as_str() {
-            "enter" => Some(b"\r".to_vec()),
-            "tab" => Some(b"\t".to_vec()),
-            "backspace" => Some(b"\x7f".to_vec()),
-            "escape" => Some(b"\x1b".to_vec()),
-            "up" => Some(b"\x1b[A".to_vec()),
-            "down" => Some(b"\x1b[B".to_vec()),
-            "right" => Some(b"\x1b[C".to_vec()),
-            "left" => Some(b"\x1b[D".to_vec()),
-            "home" => Some(b"\x1b[H".to_vec()),
-            "end" => Some(b"\x1b[F".to_vec()),
-            "pageup" => Some(b"\x1b[5~".to_vec()),
-            "pagedown" => Some(b"\x1b[6~".to_vec()),
-            "delete" => Some(b"\x1b[3~".to_vec()),
-            _ => {
-                // Handle regular character input
-                if key.len() == 1 {
-                    // Check for Ctrl modifier
-                    if event.keystroke.modifiers.control {
-                        let ch = key.chars().next()?;
-                        if ch.is_ascii_alphabetic() {
-                            // Ctrl+A = 0x01, Ctrl+B = 0x02, etc.
-                            let ctrl_char = (ch.to_ascii_lowercase() as u8) - b'a' + 1;
-                            return Some(vec![ctrl_char]);
-                        }
-                    }
+    /// Converts GPUI key events to terminal input sequences for the modes
+    /// the running application has set
+    fn key_event_to_input(event: &KeyDownEvent, mode: TermMode) -> Option<Vec<u8>> {
+        let keystroke = &event.keystroke;
+        let press = KeyPress {
+            key: &keystroke.key,
+            text: keystroke.key_char.as_deref(),
+            modifiers: KeyModifiers {
+                shift: keystroke.modifiers.shift,
+                alt: keystroke.modifiers.alt,
+                control: keystroke.modifiers.control,
+                super_key: keystroke.modifiers.platform,
+            },
+            repeat: event.is_held,
+        };
+        encode_key(&press, mode)
+    }
 
-                    // Regular character
-                    Some(key.as_bytes().to_vec())
-                } else {
-                    None
-                }
+    /// Width and height of a terminal cell in pixels
+    fn cell_size(&self) -> (f32, f32) {
+        (self.cell_width, self.line_height)
+    }
+
+    /// Measures the cell size of the configured font, returning whether it
+    /// changed
+    fn measure_cell(&mut self, window: &Window) -> bool {
+        let text_system = window.text_system();
+        let font_id = text_system.resolve_font(&font(self.font_family.clone()));
+        // Monospace fonts are usually about 0.6em wide
+        let cell_width = text_system
+            .em_advance(font_id, px(self.font_size))
+            .map(f32::from)
+            .unwrap_or(self.font_size * 0.6);
+        let line_height = self.font_size * LINE_HEIGHT_FACTOR;
+
+        let changed = (cell_width, line_height) != (self.cell_width, self.line_height);
+        self.cell_width = cell_width;
+        self.line_height = line_height;
+        changed
+    }
+
+    /// Resizes the session to the grid that fits the view, after the size
+    /// has settled
+    ///
+    /// The first layout of a view sizes its session right away.
+    fn schedule_resize(&mut self, cx: &mut Context<Self>) {
+        let Some(bounds) = self.content_bounds.get() else {
+            return;
+        };
+        let size = grid_size(
+            f32::from(bounds.size.width) - SCROLLBAR_WIDTH,
+            f32::from(bounds.size.height),
+            self.cell_width,
+            self.line_height,
+        );
+
+        if self.grid_size == Some(size) {
+            self.resize_task = None;
+            return;
+        }
+        if self.grid_size.is_none() {
+            return self.apply_resize(size, cx);
+        }
+
+        self.resize_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(RESIZE_DEBOUNCE).await;
+            let _ = this.update(cx, |view, cx| view.apply_resize(size, cx));
+        }));
+    }
+
+    /// Resizes the session and its PTY
+    fn apply_resize(&mut self, (rows, cols): (u16, u16), cx: &mut Context<Self>) {
+        self.resize_task = None;
+        self.grid_size = Some((rows, cols));
+
+        let session = self.session.clone();
+        if let Ok(mut session) = session.lock() {
+            if let Err(e) = session.resize(rows, cols) {
+                eprintln!("Failed to resize terminal: {}", e);
             }
+            self.refresh_selection(&mut session, cx);
         }
     }
 
-    /// Handles mouse down event for text selection
-    fn handle_mouse_down(&mut self, _event: &MouseDownEvent, _cx: &mut Context<Self>) {
-        // TODO: Implement text selection start
+    /// Maps a window position to a viewport cell and the half of the cell it is in
+    fn cell_at(&self, position: Point<Pixels>) -> Option<(usize, usize, Side)> {
+        let bounds = self.content_bounds.get()?;
+        let (cell_width, line_height) = self.cell_size();
+
+        let x = (f32::from(position.x) - f32::from(bounds.origin.x)).max(0.0);
+        let y = (f32::from(position.y) - f32::from(bounds.origin.y)).max(0.0);
+        let col = (x / cell_width) as usize;
+        let row = (y / line_height) as usize;
+        let side = if x % cell_width < cell_width / 2.0 {
+            Side::Left
+        } else {
+            Side::Right
+        };
+
+        Some((row, col, side))
     }
 
-    /// Handles mouse move event for text selection
-    fn handle_mouse_move(&mut self, _event: &MouseMoveEvent, _cx: &mut Context<Self>) {
-        // TODO: Implement text selection update
+    /// Applies a viewport change to the session and redraws
+    fn scroll_session(
+        &mut self,
+        cx: &mut Context<Self>,
+        scroll: impl FnOnce(&mut TerminalSession),
+    ) {
+        let session = self.session.clone();
+        if let Ok(mut session) = session.lock() {
+            scroll(&mut session);
+            self.refresh_selection(&mut session, cx);
+        }
     }
 
-    /// Handles mouse up event for text selection
-    fn handle_mouse_up(&mut self, _event: &MouseUpEvent, _cx: &mut Context<Self>) {
-        // TODO: Implement text selection end
+    /// Jumps back to the latest output
+    fn scroll_to_bottom(&mut self, cx: &mut Context<Self>) {
+        self.scroll_remainder = 0.0;
+        self.scroll_session(cx, |session| session.scroll_to_bottom());
     }
 
-    /// Handles copy operation
-    fn handle_copy(&mut self, cx: &mut Context<Self>) {
-        if let Some(selection) = &self.selection {
-            if let Ok(session) = self.session.lock() {
-                // Get the raw string content for copy operation
-                let content = session.get_visible_content();
-                let mut selected_text = String::new();
-
-                // Extract selected text from content
-                let (start_row, start_col) = selection.start;
-                let (end_row, end_col) = selection.end;
-
-                for row in start_row..=end_row {
-                    if let Some(line) = content.get(row as usize) {
-                        if row == start_row && row == end_row {
-                            // Single line selection
-                            let start = start_col as usize;
-                            let end = end_col as usize;
-                            if start < line.len() && end <= line.len() {
-                                selected_text.push_str(&line[start..end]);
-                            }
-                        } else if row == start_row {
-                            // First line
-                            let start = start_col as usize;
-                            if start < line.len() {
-                                selected_text.push_str(&line[start..]);
-                                selected_text.push('\n');
-                            }
-                        } else if row == end_row {
-                            // Last line
-                            let end = end_col as usize;
-                            if end <= line.len() {
-                                selected_text.push_str(&line[..end]);
-                            }
-                        } else {
-                            // Middle lines
-                            selected_text.push_str(line);
-                            selected_text.push('\n');
-                        }
-                    }
-                }
+    /// Moves the viewport to the scrollbar position under the pointer
+    fn scroll_to_position(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let (Some(bounds), Some(content)) = (self.content_bounds.get(), &self.cached_content) else {
+            return;
+        };
+        let height = f32::from(bounds.size.height);
+        if height <= 0.0 {
+            return;
+        }
 
-                // Copy to clipboard
-                cx.write_to_clipboard(ClipboardItem::new_string(selected_text));
+        let fraction = (f32::from(position.y) - f32::from(bounds.origin.y)) / height;
+        let display_offset = content.display_offset_at(fraction);
+        self.scroll_session(cx, |session| session.scroll_to_offset(display_offset));
+    }
+
+    /// Scrolls through the scrollback with the mouse wheel or touchpad
+    fn handle_scroll_wheel(&mut self, event: &ScrollWheelEvent, cx: &mut Context<Self>) {
+        let (_, line_height) = self.cell_size();
+        let lines = match event.delta {
+            ScrollDelta::Lines(delta) => delta.y * LINES_PER_WHEEL_NOTCH,
+            ScrollDelta::Pixels(delta) => f32::from(delta.y) / line_height,
+        };
+
+        // Touchpads report fractions of a line; keep the rest for the next event
+        self.scroll_remainder += lines;
+        let whole_lines = self.scroll_remainder.trunc();
+        self.scroll_remainder -= whole_lines;
+
+        if whole_lines == 0.0 {
+            return;
+        }
+        let lines = whole_lines as i32;
+
+        let mode = self.terminal_mode();
+        if !event.modifiers.shift && mouse::is_reporting(mode) {
+            let button = if lines > 0 {
+                mouse::MouseButton::WheelUp
+            } else {
+                mouse::MouseButton::WheelDown
+            };
+            for _ in 0..lines.unsigned_abs() {
+                self.report_mouse(button, MouseAction::Press, event.position, &event.modifiers);
+            }
+            return;
+        }
+
+        // Full screen applications have no scrollback; like other terminals,
+        // scroll them with the arrow keys instead
+        if mode.contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL) {
+            let press = KeyPress {
+                key: if lines > 0 { "up" } else { "down" },
+                text: None,
+                modifiers: KeyModifiers::default(),
+                repeat: false,
+            };
+            if let (Some(input), Ok(session)) = (encode_key(&press, mode), self.session.lock()) {
+                let _ = session
+                    .terminal_handle()
+                    .send_input(input.repeat(lines.unsigned_abs() as usize));
             }
+            return;
         }
+
+        self.scroll_session(cx, |session| session.scroll_lines(lines));
     }
 
-    /// Handles paste operation
-    fn handle_paste(&mut self, cx: &mut Context<Self>) {
-        if let Some(clipboard_item) = cx.read_from_clipboard() {
-            if let Some(text) = clipboard_item.text() {
-                if let Ok(mut session) = self.session.lock() {
-                    let _ = session.write_input(text.as_bytes());
-                    cx.notify();
-                }
+    /// Mode flags set by the application running in the terminal
+    fn terminal_mode(&self) -> TermMode {
+        self.session
+            .lock()
+            .map(|session| session.mode())
+            .unwrap_or_default()
+    }
+
+    /// Sends a mouse event to the application, which must have enabled
+    /// mouse tracking. Positions outside the grid are clamped to its edges.
+    fn report_mouse(
+        &mut self,
+        button: mouse::MouseButton,
+        action: MouseAction,
+        position: Point<Pixels>,
+        modifiers: &Modifiers,
+    ) {
+        let (Some((row, col, _)), Some(content)) = (self.cell_at(position), &self.cached_content)
+        else {
+            return;
+        };
+        let (rows, cols) = content.size;
+        let cell = (
+            row.min((rows as usize).saturating_sub(1)),
+            col.min((cols as usize).saturating_sub(1)),
+        );
+        if action == MouseAction::Motion && self.last_reported_cell == Some(cell) {
+            return;
+        }
+
+        let report = MouseReport {
+            button,
+            action,
+            modifiers: KeyModifiers {
+                shift: modifiers.shift,
+                alt: modifiers.alt,
+                control: modifiers.control,
+                super_key: false,
+            },
+            row: cell.0,
+            col: cell.1,
+        };
+        if let Ok(session) = self.session.lock() {
+            if let Some(bytes) = mouse::encode_mouse(&report, session.mode()) {
+                let _ = session.terminal_handle().send_input(bytes);
             }
         }
+        self.last_reported_cell = Some(cell);
     }
 
-    /// Converts our Rgba color to GPUI rgb format
-    fn rgba_to_gpui(&self, color: &Rgba) -> u32 {
-        ((color.r as u32) << 16) | ((color.g as u32) << 8) | (color.b as u32)
+    /// The reportable button for a GPUI mouse button
+    fn report_button(button: MouseButton) -> Option<mouse::MouseButton> {
+        match button {
+            MouseButton::Left => Some(mouse::MouseButton::Left),
+            MouseButton::Middle => Some(mouse::MouseButton::Middle),
+            MouseButton::Right => Some(mouse::MouseButton::Right),
+            MouseButton::Navigate(_) => None,
+        }
     }
 
-    /// Renders terminal content using batched text runs for performance
-    fn render_terminal_content(&self) -> Div {
-        let Some(ref content) = self.cached_content else {
-            // No content yet - render empty terminal
-            return div()
-                .w_full()
-                .h_full()
-                .bg(rgb(0x000000))
-                .font_family(self.font_family.clone())
-                .text_size(px(self.font_size));
+    /// Rebuilds the cached content after the selection changed
+    fn refresh_selection(&mut self, session: &mut TerminalSession, cx: &mut Context<Self>) {
+        self.cached_content = Some(session.get_renderable_content());
+        cx.notify();
+    }
+
+    /// Handles mouse down event for text selection
+    ///
+    /// Presses go to the application when it enabled mouse tracking, unless
+    /// Shift is held. Otherwise a single click starts a character selection
+    /// (a block selection with Alt), a double click selects a word and a
+    /// triple click a line. Shift+click extends the current selection.
+    fn handle_mouse_down(&mut self, event: &MouseDownEvent, cx: &mut Context<Self>) {
+        if !event.modifiers.shift && mouse::is_reporting(self.terminal_mode()) {
+            if let Some(button) = Self::report_button(event.button) {
+                self.report_mouse(button, MouseAction::Press, event.position, &event.modifiers);
+                self.reported_button = Some(button);
+            }
+            return;
+        }
+        if event.button != MouseButton::Left {
+            return;
+        }
+
+        let Some((row, col, side)) = self.cell_at(event.position) else {
+            return;
+        };
+        let session = self.session.clone();
+        let Ok(mut session) = session.lock() else {
+            return;
         };
 
-        let (cursor_row, cursor_col) = content.cursor;
-        let (rows, _cols) = content.size;
-        let line_height = px(self.font_size * 1.2);
-        let char_width = px(self.font_size * 0.6); // Approximate monospace char width
-
-        // Build row elements
-        let mut row_elements: Vec<Div> = Vec::with_capacity(rows as usize);
-
-        for row in 0..rows {
-            // Get background rectangles for this row
-            let row_backgrounds: Vec<_> = content
-                .backgrounds
-                .iter()
-                .filter(|bg| bg.row == row)
-                .collect();
-
-            // Get text runs for this row
-            let row_runs: Vec<_> = content
-                .text_runs
-                .iter()
-                .filter(|run| run.row == row)
-                .collect();
-
-            // Build the row with backgrounds and text overlaid
-            let mut row_div = div()
-                .h(line_height)
-                .w_full()
-                .relative();
+        if event.modifiers.shift && event.click_count == 1 && session.has_selection() {
+            session.update_selection(row, col, side);
+        } else {
+            let ty = match event.click_count {
+                1 if event.modifiers.alt => SelectionType::Block,
+                1 => SelectionType::Simple,
+                2 => SelectionType::Semantic,
+                _ => SelectionType::Lines,
+            };
+            session.start_selection(ty, row, col, side);
+        }
+        self.selecting = true;
+        self.refresh_selection(&mut session, cx);
+    }
+
+    /// Handles mouse move event for mouse reporting, text selection and
+    /// scrollbar dragging
+    fn handle_mouse_move(&mut self, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        if let Some(button) = self.reported_button {
+            // Drags that started in the application stay there
+            return self.report_mouse(button, MouseAction::Motion, event.position, &event.modifiers);
+        }
+        if event.pressed_button.is_none()
+            && !event.modifiers.shift
+            && mouse::is_reporting(self.terminal_mode())
+        {
+            let button = mouse::MouseButton::None;
+            return self.report_mouse(button, MouseAction::Motion, event.position, &event.modifiers);
+        }
 
-            // Render background rectangles first (positioned absolutely)
-            for bg in row_backgrounds {
-                let left_offset = px((bg.start_col as f32) * self.font_size * 0.6);
-                let width = px(((bg.end_col - bg.start_col) as f32) * self.font_size * 0.6);
-                let bg_color = self.rgba_to_gpui(&bg.color);
+        if event.pressed_button != Some(MouseButton::Left) {
+            return;
+        }
+        if self.dragging_scrollbar {
+            return self.scroll_to_position(event.position, cx);
+        }
+        if !self.selecting {
+            return;
+        }
+        let (Some((row, col, side)), Some(bounds)) =
+            (self.cell_at(event.position), self.content_bounds.get())
+        else {
+            return;
+        };
 
-                row_div = row_div.child(
-                    div()
-                        .absolute()
-                        .top_0()
-                        .left(left_offset)
-                        .w(width)
-                        .h(line_height)
-                        .bg(rgb(bg_color)),
-                );
+        let session = self.session.clone();
+        if let Ok(mut session) = session.lock() {
+            // Dragging past the top or bottom edge scrolls to extend the
+            // selection into the scrollback
+            if event.position.y < bounds.top() {
+                session.scroll_lines(1);
+            } else if event.position.y > bounds.bottom() {
+                session.scroll_lines(-1);
             }
+            session.update_selection(row, col, side);
+            self.refresh_selection(&mut session, cx);
+        }
+    }
 
-            // Render text runs (each positioned absolutely)
-            for run in &row_runs {
-                let left_offset = px((run.start_col as f32) * self.font_size * 0.6);
-                let fg_color = self.rgba_to_gpui(&run.style.fg);
+    /// Handles mouse up event for mouse reporting, text selection and
+    /// scrollbar dragging
+    fn handle_mouse_up(&mut self, event: &MouseUpEvent, cx: &mut Context<Self>) {
+        if let Some(button) = self.reported_button.take() {
+            self.report_mouse(button, MouseAction::Release, event.position, &event.modifiers);
+            return;
+        }
+        self.selecting = false;
+        if self.dragging_scrollbar {
+            self.dragging_scrollbar = false;
+            cx.notify();
+        }
+    }
 
-                let mut text_div = div()
-                    .absolute()
-                    .top_0()
-                    .left(left_offset)
-                    .text_color(rgb(fg_color));
+    /// Handles copy operation
+    fn handle_copy(&mut self, cx: &mut Context<Self>) {
+        let text = match self.session.lock() {
+            Ok(session) => session.selection_text(),
+            Err(_) => None,
+        };
+        if let Some(text) = text {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
 
-                // Apply text styling
-                if run.style.bold {
-                    text_div = text_div.font_weight(FontWeight::BOLD);
-                }
-                if run.style.italic {
-                    text_div = text_div.italic();
-                }
+    /// Handles paste operation
+    ///
+    /// Multi-line text for an application without bracketed paste would run
+    /// line by line, so it waits for confirmation if the settings ask for it.
+    fn handle_paste(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+
+        if self.settings.confirm_multiline_paste
+            && paste::runs_lines(&text, self.terminal_mode())
+        {
+            self.pending_paste = Some(text);
+            cx.notify();
+        } else {
+            self.paste_text(&text, cx);
+        }
+    }
+
+    /// Writes pasted text to the session
+    fn paste_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        let session = self.session.clone();
+        if let Ok(mut session) = session.lock() {
+            if let Err(e) = session.paste(text) {
+                eprintln!("Failed to paste: {}", e);
+            }
+            // Pasting scrolls back to the latest output
+            self.refresh_selection(&mut session, cx);
+        }
+    }
 
-                // Handle cursor within this run
-                let run_start = run.start_col;
-                let run_end = run.start_col + run.cell_count as u16;
+    /// Pastes the text waiting for confirmation
+    fn confirm_paste(&mut self, cx: &mut Context<Self>) {
+        if let Some(text) = self.pending_paste.take() {
+            self.paste_text(&text, cx);
+        }
+    }
 
-                if row == cursor_row && cursor_col >= run_start && cursor_col < run_end {
-                    // Cursor is within this run - split the text
-                    let cursor_offset = (cursor_col - run_start) as usize;
-                    let chars: Vec<char> = run.text.chars().collect();
+    /// Drops the text waiting for confirmation
+    fn cancel_paste(&mut self, cx: &mut Context<Self>) {
+        self.pending_paste = None;
+        cx.notify();
+    }
 
-                    let before: String = chars.iter().take(cursor_offset).collect();
-                    let cursor_char = chars.get(cursor_offset).copied().unwrap_or(' ');
-                    let after: String = chars.iter().skip(cursor_offset + 1).collect();
+    /// Renders the confirmation for a pending multi-line paste
+    fn render_paste_confirmation(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let text = self.pending_paste.as_ref()?;
+        let lines: Vec<&str> = text.lines().collect();
+        let mut preview: Vec<String> = lines
+            .iter()
+            .take(PASTE_PREVIEW_LINES)
+            .map(|line| line.to_string())
+            .collect();
+        if lines.len() > PASTE_PREVIEW_LINES {
+            preview.push(format!("… {} more", lines.len() - PASTE_PREVIEW_LINES));
+        }
 
-                    text_div = text_div
+        let button = |label: &'static str, primary: bool| {
+            div()
+                .px_3()
+                .py_1()
+                .rounded_md()
+                .cursor_pointer()
+                .text_color(rgb(0xffffff))
+                .bg(rgb(if primary { 0x0e639c } else { 0x3a3a3a }))
+                .hover(|style| style.bg(rgb(if primary { 0x1177bb } else { 0x4a4a4a })))
+                .child(label)
+        };
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(rgba(0x00000080))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|_this, _event, _window, cx| cx.stop_propagation()),
+                )
+                .child(
+                    div()
                         .flex()
-                        .flex_row()
-                        .child(div().child(before))
+                        .flex_col()
+                        .gap_2()
+                        .p_4()
+                        .max_w(px(480.0))
+                        .rounded_md()
+                        .bg(rgb(0x2d2d2d))
+                        .border_1()
+                        .border_color(rgb(0x3a3a3a))
+                        .text_sm()
                         .child(
                             div()
-                                .bg(rgb(0xffffff))
-                                .text_color(rgb(0x000000))
-                                .child(cursor_char.to_string()),
+                                .text_color(rgb(0xffffff))
+                                .child(format!("Paste {} line(s)?", lines.len())),
                         )
-                        .child(div().child(after));
-                } else {
-                    text_div = text_div.child(run.text.clone());
-                }
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0xaaaaaa))
+                                .child("Bracketed paste is off, so each line will run on its own."),
+                        )
+                        .child(
+                            div()
+                                .p_2()
+                                .rounded_sm()
+                                .bg(rgb(0x1e1e1e))
+                                .text_xs()
+                                .text_color(rgb(0xcccccc))
+                                .whitespace_nowrap()
+                                .overflow_hidden()
+                                .children(preview.into_iter().map(|line| div().child(line))),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .flex_row()
+                                .justify_end()
+                                .gap_2()
+                                .child(button("Cancel (Esc)", false).on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.cancel_paste(cx);
+                                    }),
+                                ))
+                                .child(button("Paste (Enter)", true).on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.confirm_paste(cx);
+                                    }),
+                                )),
+                        ),
+                ),
+        )
+    }
+
+    /// Opens the find bar, or focuses it when already open
+    fn open_find(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.find.is_none() {
+            self.find = Some(FindBar {
+                query: SearchQuery::default(),
+                error: None,
+            });
+        }
+        self.find_focus_handle.focus(window);
+        cx.notify();
+    }
 
-                row_div = row_div.child(text_div);
+    /// Closes the find bar, removes the highlights and returns to the shell
+    fn close_find(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.find = None;
+        let session = self.session.clone();
+        if let Ok(mut session) = session.lock() {
+            session.clear_search();
+            self.refresh_selection(&mut session, cx);
+        }
+        self.focus_handle.focus(window);
+    }
+
+    /// Searches the scrollback again after the query or its options changed
+    fn update_search(&mut self, cx: &mut Context<Self>) {
+        let Some(find) = self.find.as_mut() else {
+            return;
+        };
+        let session = self.session.clone();
+        let Ok(mut session) = session.lock() else {
+            return;
+        };
+
+        find.error = None;
+        if find.query.text.is_empty() {
+            session.clear_search();
+        } else if let Err(e) = session.start_search(&find.query) {
+            session.clear_search();
+            find.error = Some(e.to_string());
+        }
+        self.refresh_selection(&mut session, cx);
+    }
+
+    /// Moves to the next match up (`older`) or down the scrollback
+    fn find_step(&mut self, older: bool, cx: &mut Context<Self>) {
+        let session = self.session.clone();
+        if let Ok(mut session) = session.lock() {
+            if older {
+                session.search_previous();
+            } else {
+                session.search_next();
             }
+            self.refresh_selection(&mut session, cx);
+        }
+    }
 
-            // If cursor is on this row but not within any text run (empty line), render cursor
-            if row == cursor_row {
-                let cursor_in_run = row_runs
-                    .iter()
-                    .any(|run| {
-                        cursor_col >= run.start_col
-                            && cursor_col < run.start_col + run.cell_count as u16
-                    });
+    /// Handles typing in the find bar
+    ///
+    /// Enter goes to the previous (older) match and Shift+Enter to the next
+    /// one, Alt+C toggles case sensitivity, Alt+R toggles regex mode and
+    /// Escape closes the bar.
+    fn handle_find_key_down(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        let modifiers = &keystroke.modifiers;
+        let Some(find) = self.find.as_mut() else {
+            return;
+        };
 
-                if !cursor_in_run {
-                    let cursor_left = px((cursor_col as f32) * self.font_size * 0.6);
-                    row_div = row_div.child(
-                        div()
-                            .absolute()
-                            .top_0()
-                            .left(cursor_left)
-                            .w(char_width)
-                            .h(line_height)
-                            .bg(rgb(0xffffff)),
-                    );
-                }
+        match keystroke.key.as_str() {
+            "escape" => return self.close_find(window, cx),
+            "enter" => return self.find_step(!modifiers.shift, cx),
+            "backspace" => {
+                find.query.text.pop();
             }
+            "c" if modifiers.alt => find.query.case_sensitive = !find.query.case_sensitive,
+            "r" if modifiers.alt => find.query.regex = !find.query.regex,
+            _ if modifiers.control || modifiers.alt || modifiers.platform => return,
+            _ => match &keystroke.key_char {
+                Some(text) => find.query.text.push_str(text),
+                None => return,
+            },
+        }
+        self.update_search(cx);
+    }
+
+    /// Renders the find bar over the top right corner of the terminal
+    fn render_find_bar(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let find = self.find.as_ref()?;
+
+        let status = match &find.error {
+            Some(error) => error.clone(),
+            None => match self.session.lock().ok().and_then(|s| s.search_status()) {
+                Some((_, 0)) => "No results".to_string(),
+                Some((Some(focused), total)) => format!("{} of {}", focused, total),
+                Some((None, total)) => format!("{} matches", total),
+                None => String::new(),
+            },
+        };
+        let status_color = if find.error.is_some() { 0xff6b6b } else { 0x808080 };
+
+        let toggle = |label: &'static str, active: bool| {
+            div()
+                .px_1()
+                .rounded_sm()
+                .cursor_pointer()
+                .text_color(rgb(if active { 0xffffff } else { 0x808080 }))
+                .when(active, |d| d.bg(rgb(0x094771)))
+                .hover(|style| style.bg(rgb(0x4a4a4a)))
+                .child(label)
+        };
+        let button = |label: &'static str| {
+            div()
+                .px_1()
+                .rounded_sm()
+                .cursor_pointer()
+                .text_color(rgb(0xcccccc))
+                .hover(|style| style.bg(rgb(0x4a4a4a)))
+                .child(label)
+        };
+
+        Some(
+            div()
+                .absolute()
+                .top(px(4.0))
+                .right(px(SCROLLBAR_WIDTH + 8.0))
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_1()
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .bg(rgb(0x2d2d2d))
+                .border_1()
+                .border_color(rgb(0x3a3a3a))
+                .text_xs()
+                .track_focus(&self.find_focus_handle)
+                .on_key_down(cx.listener(|this, event, window, cx| {
+                    cx.stop_propagation();
+                    this.handle_find_key_down(event, window, cx);
+                }))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, window, cx| {
+                        cx.stop_propagation();
+                        this.find_focus_handle.focus(window);
+                    }),
+                )
+                .child(
+                    div()
+                        .min_w(px(160.0))
+                        .px_1()
+                        .bg(rgb(0x1e1e1e))
+                        .text_color(rgb(0xffffff))
+                        .child(format!("{}▏", find.query.text)),
+                )
+                .child(toggle("Aa", find.query.case_sensitive).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        cx.stop_propagation();
+                        if let Some(find) = this.find.as_mut() {
+                            find.query.case_sensitive = !find.query.case_sensitive;
+                        }
+                        this.update_search(cx);
+                    }),
+                ))
+                .child(toggle(".*", find.query.regex).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        cx.stop_propagation();
+                        if let Some(find) = this.find.as_mut() {
+                            find.query.regex = !find.query.regex;
+                        }
+                        this.update_search(cx);
+                    }),
+                ))
+                .child(
+                    div()
+                        .min_w(px(70.0))
+                        .text_color(rgb(status_color))
+                        .child(status),
+                )
+                .child(button("↑").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        cx.stop_propagation();
+                        this.find_step(true, cx);
+                    }),
+                ))
+                .child(button("↓").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        cx.stop_propagation();
+                        this.find_step(false, cx);
+                    }),
+                ))
+                .child(button("×").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, window, cx| {
+                        cx.stop_propagation();
+                        this.close_find(window, cx);
+                    }),
+                )),
+        )
+    }
 
-            row_elements.push(row_div);
+    /// Renders terminal content using batched text runs for performance
+    fn render_terminal_content(&mut self, cx: &mut Context<Self>) -> Div {
+        let Some(content) = self.cached_content.clone() else {
+            // No content yet - render empty terminal
+            return div()
+                .w_full()
+                .h_full()
+                .bg(rgb(0x000000))
+                .font_family(self.font_family.clone())
+                .text_size(px(self.font_size));
+        };
+
+        let metrics = self.cell_metrics();
+
+        // Rows keep their layout and paint from the last frame unless their
+        // runs changed. A row changed here can't be notified anymore, as the
+        // frame is already being drawn, so it skips the cache this once.
+        let mut row_elements: Vec<AnyView> = Vec::with_capacity(content.lines.len());
+        for (index, line) in content.lines.iter().enumerate() {
+            let (row, changed) = match self.rows.get(index) {
+                Some(row) => {
+                    let row = row.clone();
+                    let changed = row.update(cx, |row, _cx| row.set_content(line, &metrics));
+                    (row, changed)
+                }
+                None => {
+                    let row = cx.new(|_cx| TerminalRow::new(line.clone(), metrics.clone()));
+                    self.rows.push(row.clone());
+                    (row, true)
+                }
+            };
+            let view = AnyView::from(row);
+            row_elements.push(if changed {
+                view
+            } else {
+                view.cached(StyleRefinement::default().w_full().h(metrics.line_height))
+            });
         }
+        self.rows.truncate(content.lines.len());
+
+        // Record where the grid was painted so mouse positions map to cells,
+        // and fit the PTY to it whenever the layout changes
+        let content_bounds = self.content_bounds.clone();
+        let view = cx.entity().downgrade();
+        let bounds_tracker = canvas(
+            move |bounds, _window, cx| {
+                if content_bounds.replace(Some(bounds)) != Some(bounds) {
+                    cx.defer(move |cx| {
+                        let _ = view.update(cx, |view, cx| view.schedule_resize(cx));
+                    });
+                }
+            },
+            |_bounds, _state, _window, _cx| {},
+        )
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full();
 
         div()
             .w_full()
             .h_full()
+            .relative()
             .bg(rgb(0x000000))
             .font_family(self.font_family.clone())
             .text_size(px(self.font_size))
             .text_color(rgb(0xffffff))
             .overflow_hidden()
+            .child(bounds_tracker)
             .children(row_elements)
+            .children(self.render_cursor(&content))
+            .children(self.render_scrollbar(&content, cx))
+            .children(self.render_lines_below(&content, cx))
+            .children(self.render_find_bar(cx))
+            .children(self.render_paste_confirmation(cx))
+    }
+
+    /// Renders the cursor in the shape the application asked for, hollow
+    /// while the terminal isn't focused
+    fn render_cursor(&self, content: &RenderableContent) -> Option<Canvas<()>> {
+        if !content.cursor_visible {
+            // Scrolled away from the cursor
+            return None;
+        }
+        let shape = match content.cursor_shape {
+            CursorShape::Hidden => return None,
+            _ if !self.focused => CursorShape::HollowBlock,
+            _ if content.cursor_blinking && !self.cursor_blink_on => return None,
+            shape => shape,
+        };
+
+        let (row, col) = content.cursor;
+        let text = content.lines.get(row as usize).and_then(|line| {
+            let run = line.text_runs.iter().find(|run| {
+                col >= run.start_col && col < run.start_col + run.cell_count as u16
+            })?;
+            run.text.chars().nth((col - run.start_col) as usize)
+        });
+        let cursor = TerminalCursor {
+            row,
+            col,
+            shape,
+            wide: content.cursor_wide,
+            text,
+        };
+        let metrics = self.cell_metrics();
+
+        Some(
+            canvas(
+                |_bounds, _window, _cx| {},
+                move |bounds, _, window, cx| {
+                    paint_cursor(bounds.origin, &cursor, &metrics, window, cx);
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+    }
+
+    /// Renders the scrollbar along the right edge while there is scrollback
+    fn render_scrollbar(
+        &self,
+        content: &RenderableContent,
+        cx: &mut Context<Self>,
+    ) -> Option<Div> {
+        let (thumb_top, thumb_height) = content.scrollbar_thumb()?;
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .right_0()
+                .h_full()
+                .w(px(SCROLLBAR_WIDTH))
+                .hover(|style| style.bg(rgba(0xffffff10)))
+                .child(
+                    div()
+                        .absolute()
+                        .right(px(2.0))
+                        .w(px(SCROLLBAR_WIDTH - 4.0))
+                        .top(relative(thumb_top))
+                        .h(relative(thumb_height))
+                        .min_h(px(16.0))
+                        .rounded_sm()
+                        .bg(if self.dragging_scrollbar {
+                            rgba(0xffffff60)
+                        } else {
+                            rgba(0xffffff30)
+                        }),
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, event: &MouseDownEvent, _window, cx| {
+                        cx.stop_propagation();
+                        this.dragging_scrollbar = true;
+                        this.scroll_to_position(event.position, cx);
+                    }),
+                ),
+        )
+    }
+
+    /// Renders how far the viewport is above the latest output, with a
+    /// button to jump back down
+    fn render_lines_below(
+        &self,
+        content: &RenderableContent,
+        cx: &mut Context<Self>,
+    ) -> Option<Div> {
+        if content.display_offset == 0 {
+            return None;
+        }
+
+        Some(
+            div()
+                .absolute()
+                .bottom(px(8.0))
+                .right(px(SCROLLBAR_WIDTH + 8.0))
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .bg(rgb(0x3a3a3a))
+                .text_xs()
+                .text_color(rgb(0xffffff))
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(0x4a4a4a)))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        cx.stop_propagation();
+                        this.scroll_to_bottom(cx);
+                    }),
+                )
+                .child(format!("↓ {} lines below", content.display_offset)),
+        )
     }
 }
 
 impl Render for TerminalView {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Check session health
         self.check_session_health();
+        self.focused = self.focus_handle.is_focused(window);
+        self.update_blink_task(cx);
 
-        // Poll for output and update cache
-        if self.state == TerminalState::Ready {
-            let had_output = self.poll_and_update_cache();
-
-            if had_output {
-                // Keep polling while there's output
-                self.poll_frames_remaining = 30;
-                cx.notify();
-            } else if self.poll_frames_remaining > 0 {
-                // Continue polling for a bit after input
-                self.poll_frames_remaining -= 1;
-                cx.notify();
-            }
+        // A different font or size changes how many cells fit
+        if self.measure_cell(window) {
+            self.schedule_resize(cx);
         }
 
         match &self.state {
             TerminalState::Loading => self.render_loading_state().into_any_element(),
             TerminalState::Error(msg) => self.render_error_state(msg.clone(), cx).into_any_element(),
-            TerminalState::Ready => self.render_ready_state(cx).into_any_element(),
+            TerminalState::Ready | TerminalState::Exited(_) => {
+                self.render_ready_state(cx).into_any_element()
+            }
         }
     }
 }
@@ -541,7 +1415,7 @@ impl TerminalView {
     }
 
     /// Renders the ready/normal terminal state
-    fn render_ready_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render_ready_state(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .w_full()
             .h_full()
@@ -549,28 +1423,88 @@ impl TerminalView {
             .flex()
             .flex_col()
             .track_focus(&self.focus_handle)
-            .child(self.render_terminal_content())
+            .child(self.render_terminal_content(cx))
+            .children(self.render_exit_banner(cx))
             .child(self.render_status_bar())
-            .on_key_down(cx.listener(|this, event, _window, cx| {
-                this.handle_key_down(event, cx);
+            .on_key_down(cx.listener(|this, event, window, cx| {
+                this.handle_key_down(event, window, cx);
+            }))
+            .on_any_mouse_down(cx.listener(|this, event, window, cx| {
+                // Focus the terminal when clicked
+                this.focus_handle.focus(window);
+                this.handle_mouse_down(event, cx);
+                cx.notify();
             }))
-            .on_mouse_down(
-                MouseButton::Left,
-                cx.listener(|this, _event, window, cx| {
-                    // Focus the terminal when clicked
-                    this.focus_handle.focus(window);
-                    cx.notify();
-                }),
-            )
             .on_mouse_move(cx.listener(|this, event, _window, cx| {
                 this.handle_mouse_move(event, cx);
             }))
+            .on_scroll_wheel(cx.listener(|this, event, _window, cx| {
+                this.handle_scroll_wheel(event, cx);
+            }))
             .on_mouse_up(
                 MouseButton::Left,
                 cx.listener(|this, event, _window, cx| {
                     this.handle_mouse_up(event, cx);
                 }),
             )
+            .on_mouse_up(
+                MouseButton::Middle,
+                cx.listener(|this, event, _window, cx| {
+                    this.handle_mouse_up(event, cx);
+                }),
+            )
+            .on_mouse_up(
+                MouseButton::Right,
+                cx.listener(|this, event, _window, cx| {
+                    this.handle_mouse_up(event, cx);
+                }),
+            )
+    }
+
+    /// Renders how the shell ended, with a button to start a new one
+    fn render_exit_banner(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let TerminalState::Exited(status) = self.state else {
+            return None;
+        };
+        let color = if status.success() { 0x89d185 } else { 0xff6b6b };
+
+        Some(
+            div()
+                .w_full()
+                .px_2()
+                .py_1()
+                .flex()
+                .flex_row()
+                .items_center()
+                .justify_between()
+                .bg(rgb(0x2d2d2d))
+                .border_t_1()
+                .border_color(rgb(0x3a3a3a))
+                .text_sm()
+                .child(
+                    div()
+                        .text_color(rgb(color))
+                        .child(format!("Process {}", status)),
+                )
+                .child(
+                    div()
+                        .px_3()
+                        .py(px(2.0))
+                        .bg(rgb(0x3a3a3a))
+                        .rounded_md()
+                        .cursor_pointer()
+                        .text_color(rgb(0xffffff))
+                        .hover(|style| style.bg(rgb(0x4a4a4a)))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                cx.stop_propagation();
+                                this.restart_session(cx);
+                            }),
+                        )
+                        .child("Restart Terminal"),
+                ),
+        )
     }
 
     /// Renders the status bar with keyboard shortcuts hint
@@ -593,7 +1527,36 @@ impl TerminalView {
                 div()
                     .text_xs()
                     .text_color(rgb(0x606060))
-                    .child("Ctrl+Shift+C: Copy | Ctrl+Shift+V: Paste"),
+                    .child(
+                        "Shift+PgUp/PgDn: Scroll | Ctrl+Shift+F: Find | \
+                         Ctrl+Shift+C: Copy | Ctrl+Shift+V: Paste",
+                    ),
             )
     }
 }
+
+/// Rows and columns of whole cells that fit in an area of the given size
+fn grid_size(width: f32, height: f32, cell_width: f32, line_height: f32) -> (u16, u16) {
+    let fit = |length: f32, cell: f32, min: u16| {
+        (length / cell.max(1.0))
+            .floor()
+            .clamp(min as f32, u16::MAX as f32) as u16
+    };
+    (
+        fit(height, line_height, MIN_ROWS),
+        fit(width, cell_width, MIN_COLS),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_size_fits_whole_cells() {
+        assert_eq!(grid_size(800.0, 480.0, 8.0, 16.0), (30, 100));
+        assert_eq!(grid_size(805.0, 495.0, 8.0, 16.0), (30, 100));
+        // Tiny or collapsed panes keep a usable minimum
+        assert_eq!(grid_size(0.0, -5.0, 8.0, 16.0), (MIN_ROWS, MIN_COLS));
+    }
+}
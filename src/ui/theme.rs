@@ -19,6 +19,16 @@ pub struct Theme {
 
     // Borders
     pub border_subtle: Hsla,
+
+    // Diff
+    pub diff_added_bg: Hsla,
+    pub diff_added_text: Hsla,
+    pub diff_removed_bg: Hsla,
+    pub diff_removed_text: Hsla,
+    pub diff_hunk_header_bg: Hsla,
+
+    // Status
+    pub error: Hsla,
 }
 
 impl Default for Theme {
@@ -41,6 +51,16 @@ impl Default for Theme {
 
             // Borders
             border_subtle: hsla(0.0, 0.0, 1.0, 0.08), // rgba(255, 255, 255, 0.08)
+
+            // Diff
+            diff_added_bg: hsla(135.0, 0.45, 0.35, 0.25),
+            diff_added_text: hsla(135.0, 0.55, 0.65, 1.0),
+            diff_removed_bg: hsla(0.0, 0.55, 0.45, 0.25),
+            diff_removed_text: hsla(0.0, 0.7, 0.7, 1.0),
+            diff_hunk_header_bg: hsla(211.0, 0.4, 0.3, 0.25),
+
+            // Status
+            error: hsla(0.0, 0.7, 0.6, 1.0),
         }
     }
 }
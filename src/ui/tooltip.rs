@@ -0,0 +1,38 @@
+use crate::ui::theme::Theme;
+use gpui::*;
+
+/// Plain text tooltip, one line per entry
+pub struct TextTooltip {
+    lines: Vec<SharedString>,
+    theme: Theme,
+}
+
+impl TextTooltip {
+    /// Build a tooltip view for use with `.tooltip(...)`
+    pub fn build(lines: Vec<SharedString>, cx: &mut App) -> AnyView {
+        cx.new(|_cx| Self {
+            lines,
+            theme: Theme::new(),
+        })
+        .into()
+    }
+}
+
+impl Render for TextTooltip {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .px_2()
+            .py_1()
+            .max_w(px(360.0))
+            .bg(self.theme.bg_surface)
+            .border_1()
+            .border_color(self.theme.border_subtle)
+            .rounded_md()
+            .text_xs()
+            .text_color(self.theme.text_primary)
+            .children(self.lines.iter().cloned())
+    }
+}
@@ -0,0 +1,76 @@
+use crate::workspace::watcher::{FileWatcher, WatchEvent};
+use gpui::*;
+use std::path::PathBuf;
+
+/// Emits a [`WatchEvent`] for every change in the workspace
+///
+/// Wraps a [`FileWatcher`] so views can `cx.subscribe` to file changes
+/// instead of refreshing on a timer. Debounced batches are awaited from the
+/// watcher thread, so an idle workspace doesn't wake the UI at all.
+pub struct WorkspaceWatcher {
+    watcher: Option<FileWatcher>,
+    worktree_paths: Vec<PathBuf>,
+}
+
+impl EventEmitter<WatchEvent> for WorkspaceWatcher {}
+
+impl WorkspaceWatcher {
+    /// Start watching the given worktrees, root worktree first
+    pub fn new(worktree_paths: Vec<PathBuf>, cx: &mut Context<Self>) -> Self {
+        let (tx, rx) = async_channel::unbounded();
+
+        let watcher = if worktree_paths.is_empty() {
+            None
+        } else {
+            match FileWatcher::new(&worktree_paths, move |events| {
+                let _ = tx.try_send(events);
+            }) {
+                Ok(watcher) => Some(watcher),
+                Err(e) => {
+                    eprintln!("Failed to watch workspace: {:#}", e);
+                    None
+                }
+            }
+        };
+
+        if watcher.is_some() {
+            cx.spawn(async move |this, cx| {
+                // Ends when the watcher thread stops and drops the sender
+                while let Ok(mut events) = rx.recv().await {
+                    while let Ok(batch) = rx.try_recv() {
+                        events.extend(batch);
+                    }
+
+                    let emitted = this.update(cx, |_watcher, cx| {
+                        for event in events {
+                            cx.emit(event);
+                        }
+                    });
+                    if emitted.is_err() {
+                        break;
+                    }
+                }
+            })
+            .detach();
+        }
+
+        Self {
+            watcher,
+            worktree_paths,
+        }
+    }
+
+    /// Follow worktrees added or removed since the watcher was started
+    pub fn set_worktrees(&mut self, worktree_paths: Vec<PathBuf>) {
+        if worktree_paths == self.worktree_paths {
+            return;
+        }
+
+        if let Some(watcher) = &mut self.watcher
+            && let Err(e) = watcher.set_worktrees(&worktree_paths)
+        {
+            eprintln!("Failed to update watched worktrees: {:#}", e);
+        }
+        self.worktree_paths = worktree_paths;
+    }
+}
@@ -0,0 +1,714 @@
+use anyhow::{Context, Result};
+use crossbeam_channel::unbounded;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use crate::settings::config::IN_REPO_WORKTREES_DIR;
+
+/// Per-repository configuration file, read from the root worktree
+pub const REPO_CONFIG_FILE: &str = ".maestro.json";
+
+/// Environment variable pointing setup commands at the root worktree
+pub const ROOT_WORKTREE_ENV: &str = "MAESTRO_ROOT_WORKTREE";
+
+/// Contents of `.maestro.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepoConfig {
+    /// Steps run when a new worktree is created
+    #[serde(default)]
+    pub bootstrap: BootstrapConfig,
+}
+
+/// How a new worktree is prepared before it is handed to an agent
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BootstrapConfig {
+    /// Files, directories or globs copied from the root worktree
+    #[serde(default)]
+    pub copy: Vec<String>,
+    /// Files, directories or globs symlinked to the root worktree
+    #[serde(default)]
+    pub symlink: Vec<String>,
+    /// Shell commands run in the new worktree, in order
+    #[serde(default)]
+    pub setup: Vec<String>,
+}
+
+impl BootstrapConfig {
+    /// Whether there is nothing to do
+    pub fn is_empty(&self) -> bool {
+        self.copy.is_empty() && self.symlink.is_empty() && self.setup.is_empty()
+    }
+}
+
+/// Progress reported while bootstrapping a worktree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootstrapEvent {
+    /// A file or directory was copied into the worktree
+    Copied(PathBuf),
+    /// A file or directory was symlinked into the worktree
+    Linked(PathBuf),
+    /// A pattern or path was not provisioned
+    Skipped { path: PathBuf, reason: String },
+    /// A setup command started
+    CommandStarted(String),
+    /// A line of output from the running setup command
+    Output(String),
+}
+
+/// Stops a running bootstrap from another thread, for example when its
+/// worktree is removed
+#[derive(Debug, Clone, Default)]
+pub struct BootstrapCancel(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    /// Process group of the running setup command
+    command_group: Mutex<Option<u32>>,
+}
+
+impl BootstrapCancel {
+    /// Skip the remaining steps and terminate the running setup command
+    /// along with everything it started
+    pub fn cancel(&self) {
+        let group = self
+            .0
+            .command_group
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        if let Some(id) = *group {
+            terminate_process_group(id);
+        }
+    }
+
+    /// Whether the bootstrap was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            anyhow::bail!("Bootstrap was cancelled");
+        }
+        Ok(())
+    }
+}
+
+/// Load `.maestro.json` from the root worktree
+///
+/// Returns the default (empty) configuration if the file doesn't exist.
+pub fn load_repo_config(root_path: &Path) -> Result<RepoConfig> {
+    let config_path = root_path.join(REPO_CONFIG_FILE);
+
+    if !config_path.exists() {
+        return Ok(RepoConfig::default());
+    }
+
+    let contents = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", config_path.display()))
+}
+
+/// Prepare a new worktree: copy and link files from the root worktree, then
+/// run the setup commands
+///
+/// Progress is reported through `on_event` as it happens. Stops at the first
+/// failing command, or as soon as `cancel` is triggered.
+///
+/// # Arguments
+///
+/// * `root_path` - Path to the root worktree the files come from
+/// * `worktree_path` - Path to the new worktree
+/// * `config` - Bootstrap steps to run
+/// * `cancel` - Stops the bootstrap from another thread
+/// * `on_event` - Called for every step and every line of command output
+pub fn run_bootstrap(
+    root_path: &Path,
+    worktree_path: &Path,
+    config: &BootstrapConfig,
+    cancel: &BootstrapCancel,
+    mut on_event: impl FnMut(BootstrapEvent),
+) -> Result<()> {
+    for pattern in &config.copy {
+        cancel.check()?;
+        provision(root_path, worktree_path, pattern, false, &mut on_event)?;
+    }
+    for pattern in &config.symlink {
+        cancel.check()?;
+        provision(root_path, worktree_path, pattern, true, &mut on_event)?;
+    }
+
+    for command in &config.setup {
+        cancel.check()?;
+        on_event(BootstrapEvent::CommandStarted(command.clone()));
+        run_setup_command(root_path, worktree_path, command, cancel, &mut on_event)?;
+    }
+
+    Ok(())
+}
+
+/// Copy or link everything matching a pattern into the worktree
+fn provision(
+    root_path: &Path,
+    worktree_path: &Path,
+    pattern: &str,
+    symlink: bool,
+    on_event: &mut impl FnMut(BootstrapEvent),
+) -> Result<()> {
+    validate_pattern(pattern)?;
+
+    let matches = expand_glob(root_path, pattern);
+    if matches.is_empty() {
+        on_event(BootstrapEvent::Skipped {
+            path: PathBuf::from(pattern),
+            reason: "no matching files in the root worktree".to_string(),
+        });
+        return Ok(());
+    }
+
+    for relative in matches {
+        let source = root_path.join(&relative);
+        let destination = worktree_path.join(&relative);
+
+        // Never clobber files that are tracked or already created
+        if destination.symlink_metadata().is_ok() {
+            on_event(BootstrapEvent::Skipped {
+                path: relative,
+                reason: "already exists in the worktree".to_string(),
+            });
+            continue;
+        }
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        if symlink {
+            create_symlink(&source, &destination)
+                .with_context(|| format!("Failed to link {}", relative.display()))?;
+            on_event(BootstrapEvent::Linked(relative));
+        } else {
+            copy_recursively(&source, &destination)
+                .with_context(|| format!("Failed to copy {}", relative.display()))?;
+            on_event(BootstrapEvent::Copied(relative));
+        }
+    }
+
+    Ok(())
+}
+
+/// Run one setup command through the shell, streaming stdout and stderr
+fn run_setup_command(
+    root_path: &Path,
+    worktree_path: &Path,
+    command: &str,
+    cancel: &BootstrapCancel,
+    on_event: &mut impl FnMut(BootstrapEvent),
+) -> Result<()> {
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(command)
+        .current_dir(worktree_path)
+        .env(ROOT_WORKTREE_ENV, root_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Own process group, so cancelling also stops what the command started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+
+    // Holding the lock while starting means a cancel either comes first or
+    // sees the new process group
+    let mut group = cancel
+        .0
+        .command_group
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    cancel.check()?;
+    let mut child = shell
+        .spawn()
+        .with_context(|| format!("Failed to start `{}`", command))?;
+    *group = Some(child.id());
+    drop(group);
+
+    // Read both pipes on their own threads so neither can fill up and block
+    let (line_tx, line_rx) = unbounded();
+    let readers: Vec<_> = [
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|pipe| {
+        let line_tx = line_tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(|line| line.ok()) {
+                if line_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        })
+    })
+    .collect();
+    drop(line_tx);
+
+    for line in line_rx {
+        on_event(BootstrapEvent::Output(line));
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let status = child.wait();
+    *cancel
+        .0
+        .command_group
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+    let status = status.with_context(|| format!("Failed to wait for `{}`", command))?;
+
+    cancel.check()?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => anyhow::bail!("`{}` failed with exit code {}", command, code),
+            None => anyhow::bail!("`{}` was terminated by a signal", command),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject patterns that could reach outside the worktree
+fn validate_pattern(pattern: &str) -> Result<()> {
+    let path = Path::new(pattern);
+    if pattern.trim().is_empty() {
+        anyhow::bail!("Empty bootstrap path in {}", REPO_CONFIG_FILE);
+    }
+    if path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+        anyhow::bail!(
+            "Bootstrap path '{}' must be relative to the repository",
+            pattern
+        );
+    }
+    Ok(())
+}
+
+/// Expand a glob relative to `root`, returning relative paths
+///
+/// Supports `*` and `?` within a path segment and `**` for any number of
+/// directories. The `.git` directory, in-repository worktrees and other
+/// nested worktrees or repositories are never matched, and `**` doesn't
+/// descend into directories git ignores, such as `node_modules`.
+fn expand_glob(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let segments: Vec<&str> = pattern
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+
+    let glob = Glob {
+        root,
+        repo: Repository::open(root).ok(),
+    };
+    let mut matches = Vec::new();
+    glob.expand(PathBuf::new(), &segments, &mut matches);
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+/// Root directory a glob is expanded in, with its repository for ignore rules
+struct Glob<'a> {
+    root: &'a Path,
+    repo: Option<Repository>,
+}
+
+impl Glob<'_> {
+    /// Whether `**` should skip a directory because git ignores it
+    fn is_ignored(&self, relative: &Path) -> bool {
+        self.repo
+            .as_ref()
+            .is_some_and(|repo| repo.is_path_ignored(relative).unwrap_or(false))
+    }
+
+    fn expand(&self, relative: PathBuf, segments: &[&str], out: &mut Vec<PathBuf>) {
+        let root = self.root;
+        let Some((segment, rest)) = segments.split_first() else {
+            if !relative.as_os_str().is_empty() {
+                out.push(relative);
+            }
+            return;
+        };
+
+        if *segment == "**" {
+            // Zero directories, then one or more
+            self.expand(relative.clone(), rest, out);
+            for (name, is_dir) in list_dir(&root.join(&relative)) {
+                let dir = relative.join(name);
+                if is_dir && !self.is_ignored(&dir) {
+                    self.expand(dir, segments, out);
+                }
+            }
+            return;
+        }
+
+        if !segment.contains(['*', '?']) {
+            let next = relative.join(segment);
+            if root.join(&next).symlink_metadata().is_ok() {
+                self.expand(next, rest, out);
+            }
+            return;
+        }
+
+        for (name, _) in list_dir(&root.join(&relative)) {
+            if wildcard_match(segment, &name) {
+                self.expand(relative.join(name), rest, out);
+            }
+        }
+    }
+}
+
+/// Entries of a directory as (name, is_dir)
+///
+/// Skips `.git`, the directory of in-repository worktrees and directories
+/// with a `.git` of their own, which are other worktrees or repositories.
+fn list_dir(dir: &Path) -> Vec<(String, bool)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let is_dir = entry.file_type().ok()?.is_dir();
+            Some((name, is_dir))
+        })
+        .filter(|(name, is_dir)| {
+            name != ".git"
+                && !(*is_dir
+                    && (name == IN_REPO_WORKTREES_DIR || dir.join(name).join(".git").exists()))
+        })
+        .collect()
+}
+
+/// Match a single path segment against a pattern with `*` and `?`
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            // Let the last `*` absorb one more character
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn copy_recursively(source: &Path, destination: &Path) -> Result<()> {
+    let metadata = fs::metadata(source)?;
+
+    if metadata.is_dir() {
+        fs::create_dir_all(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursively(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, destination)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn terminate_process_group(id: u32) {
+    use nix::sys::signal::{Signal, killpg};
+    use nix::unistd::Pid;
+
+    // The group may already be gone
+    let _ = killpg(Pid::from_raw(id as i32), Signal::SIGTERM);
+}
+
+#[cfg(not(unix))]
+fn terminate_process_group(_id: u32) {}
+
+#[cfg(unix)]
+fn create_symlink(source: &Path, destination: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(source, destination)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn create_symlink(source: &Path, destination: &Path) -> Result<()> {
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(source, destination)?;
+    } else {
+        std::os::windows::fs::symlink_file(source, destination)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dirs(name: &str) -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&base);
+        let root = base.join("root");
+        let worktree = base.join("worktree");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&worktree).unwrap();
+        (root, worktree)
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.env", "local.env"));
+        assert!(wildcard_match(".env*", ".env.local"));
+        assert!(wildcard_match("a?c", "abc"));
+        assert!(wildcard_match("*", "anything"));
+        assert!(!wildcard_match("*.env", "env.txt"));
+        assert!(!wildcard_match("a?c", "ac"));
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        assert!(validate_pattern(".env").is_ok());
+        assert!(validate_pattern("config/**/*.local.json").is_ok());
+        assert!(validate_pattern("../secrets").is_err());
+        assert!(validate_pattern("/etc/passwd").is_err());
+        assert!(validate_pattern(" ").is_err());
+    }
+
+    #[test]
+    fn test_repo_config_parsing() {
+        let config: RepoConfig =
+            serde_json::from_str(r#"{"bootstrap": {"copy": [".env"], "setup": ["npm install"]}}"#)
+                .unwrap();
+
+        assert_eq!(config.bootstrap.copy, vec![".env"]);
+        assert!(config.bootstrap.symlink.is_empty());
+        assert_eq!(config.bootstrap.setup, vec!["npm install"]);
+        assert!(!config.bootstrap.is_empty());
+
+        let empty: RepoConfig = serde_json::from_str("{}").unwrap();
+        assert!(empty.bootstrap.is_empty());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary directories
+    fn test_expand_glob() {
+        let (root, _) = temp_dirs("maestro_bootstrap_glob_test");
+        fs::create_dir_all(root.join("config/nested")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join(".env"), "A=1").unwrap();
+        fs::write(root.join(".env.local"), "B=2").unwrap();
+        fs::write(root.join("config/app.local.json"), "{}").unwrap();
+        fs::write(root.join("config/nested/db.local.json"), "{}").unwrap();
+        fs::write(root.join("config/app.json"), "{}").unwrap();
+
+        assert_eq!(expand_glob(&root, ".env"), vec![PathBuf::from(".env")]);
+        assert_eq!(
+            expand_glob(&root, ".env*"),
+            vec![PathBuf::from(".env"), PathBuf::from(".env.local")]
+        );
+        assert_eq!(
+            expand_glob(&root, "config/**/*.local.json"),
+            vec![
+                PathBuf::from("config/app.local.json"),
+                PathBuf::from("config/nested/db.local.json"),
+            ]
+        );
+        assert!(expand_glob(&root, "missing.txt").is_empty());
+        assert!(
+            expand_glob(&root, "*")
+                .iter()
+                .all(|p| p != Path::new(".git"))
+        );
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary directories and a git repository
+    fn test_expand_glob_skips_other_worktrees_and_ignored_dirs() {
+        let (root, _) = temp_dirs("maestro_bootstrap_glob_in_repo_test");
+        Command::new("git")
+            .arg("init")
+            .current_dir(&root)
+            .output()
+            .expect("Failed to run git init");
+        fs::write(root.join(".gitignore"), ".env\nnode_modules/\n").unwrap();
+
+        // In-repository worktrees and a nested worktree elsewhere, each with
+        // a `.git` file pointing back at the repository
+        for dir in [".worktrees/feature", "tools/checkout"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join(".git"), "gitdir: ../.git/worktrees/x").unwrap();
+            fs::write(root.join(dir).join(".env"), "OTHER=1").unwrap();
+        }
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("node_modules/pkg/.env"), "PKG=1").unwrap();
+        fs::create_dir_all(root.join("services/api")).unwrap();
+        fs::write(root.join(".env"), "A=1").unwrap();
+        fs::write(root.join("services/api/.env"), "B=2").unwrap();
+
+        assert_eq!(
+            expand_glob(&root, "**/.env"),
+            vec![PathBuf::from(".env"), PathBuf::from("services/api/.env")]
+        );
+        assert!(
+            expand_glob(&root, "*")
+                .iter()
+                .all(|p| p != Path::new(".worktrees"))
+        );
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary directories
+    fn test_run_bootstrap_copies_links_and_runs_commands() {
+        let (root, worktree) = temp_dirs("maestro_bootstrap_run_test");
+        fs::write(root.join(".env"), "SECRET=1").unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let config = BootstrapConfig {
+            copy: vec![".env".to_string(), "missing.txt".to_string()],
+            symlink: vec!["node_modules".to_string()],
+            setup: vec!["echo hello; echo oops >&2".to_string()],
+        };
+
+        let cancel = BootstrapCancel::default();
+        let mut events = Vec::new();
+        run_bootstrap(&root, &worktree, &config, &cancel, |event| {
+            events.push(event)
+        })
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(worktree.join(".env")).unwrap(),
+            "SECRET=1"
+        );
+        assert!(
+            worktree
+                .join("node_modules")
+                .symlink_metadata()
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert!(events.contains(&BootstrapEvent::Copied(PathBuf::from(".env"))));
+        assert!(events.contains(&BootstrapEvent::Linked(PathBuf::from("node_modules"))));
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, BootstrapEvent::Skipped { path, .. } if path == Path::new("missing.txt")))
+        );
+        assert!(events.contains(&BootstrapEvent::Output("hello".to_string())));
+        assert!(events.contains(&BootstrapEvent::Output("oops".to_string())));
+
+        // Running again skips what already exists instead of overwriting
+        let mut events = Vec::new();
+        run_bootstrap(&root, &worktree, &config, &cancel, |event| {
+            events.push(event)
+        })
+        .unwrap();
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, BootstrapEvent::Copied(_)))
+        );
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary directories
+    fn test_run_bootstrap_reports_failing_command() {
+        let (root, worktree) = temp_dirs("maestro_bootstrap_fail_test");
+
+        let config = BootstrapConfig {
+            setup: vec!["exit 3".to_string(), "echo never".to_string()],
+            ..Default::default()
+        };
+
+        let cancel = BootstrapCancel::default();
+        let mut events = Vec::new();
+        let err = run_bootstrap(&root, &worktree, &config, &cancel, |event| {
+            events.push(event)
+        })
+        .unwrap_err();
+
+        assert!(err.to_string().contains("exit code 3"));
+        assert!(!events.contains(&BootstrapEvent::CommandStarted("echo never".to_string())));
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary directories
+    fn test_cancel_stops_running_command() {
+        let (root, worktree) = temp_dirs("maestro_bootstrap_cancel_test");
+
+        let config = BootstrapConfig {
+            setup: vec!["sleep 30 & wait".to_string(), "echo never".to_string()],
+            ..Default::default()
+        };
+
+        let cancel = BootstrapCancel::default();
+        let canceller = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(200));
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let mut events = Vec::new();
+        let err = run_bootstrap(&root, &worktree, &config, &cancel, |event| {
+            events.push(event)
+        })
+        .unwrap_err();
+
+        assert!(err.to_string().contains("cancelled"));
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert!(!events.contains(&BootstrapEvent::CommandStarted("echo never".to_string())));
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod bootstrap;
 pub mod manager;
+pub mod watcher;
 
 pub use manager::WorkspaceManager;
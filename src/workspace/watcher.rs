@@ -0,0 +1,637 @@
+use anyhow::{Context, Result};
+use crossbeam_channel::{Receiver, RecvTimeoutError, unbounded};
+use git2::Repository;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last change before events are delivered
+pub const DEBOUNCE_DELAY: Duration = Duration::from_millis(300);
+
+/// Longest time events are held back while changes keep coming in
+pub const MAX_DEBOUNCE_DELAY: Duration = Duration::from_secs(2);
+
+/// Git admin files whose changes affect what a worktree shows
+const TRACKED_ADMIN_ENTRIES: &[&str] = &[
+    "HEAD",
+    "index",
+    "MERGE_HEAD",
+    "REBASE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "BISECT_LOG",
+    "rebase-merge",
+    "rebase-apply",
+    "sequencer",
+];
+
+/// A change in the workspace, delivered after debouncing
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WatchEvent {
+    /// Files, the index, HEAD or an in-progress operation of a worktree changed
+    WorktreeChanged(PathBuf),
+    /// Branches, tags or remote-tracking refs changed
+    RefsChanged,
+    /// Worktrees were added, removed, moved, locked or unlocked
+    WorktreeListChanged,
+}
+
+/// A worktree being watched, with the paths needed to classify changes
+struct WatchedWorktree {
+    /// Path as given by the caller, reported in events
+    path: PathBuf,
+    /// Canonical working directory
+    workdir: PathBuf,
+    /// Canonical git directory (`.git` or `.git/worktrees/<name>`)
+    git_dir: PathBuf,
+    /// Used to check paths against .gitignore
+    repo: Repository,
+}
+
+/// Maps changed paths to workspace events
+pub struct WatchTargets {
+    /// Canonical git directory shared by all worktrees
+    common_dir: PathBuf,
+    worktrees: Vec<WatchedWorktree>,
+}
+
+impl WatchTargets {
+    /// Open every worktree of a workspace
+    ///
+    /// # Arguments
+    ///
+    /// * `worktree_paths` - Worktrees to watch, root worktree first
+    pub fn new(worktree_paths: &[PathBuf]) -> Result<Self> {
+        let first = worktree_paths.first().context("No worktrees to watch")?;
+        let first_repo = Repository::open(first).context("Failed to open worktree repository")?;
+        let common_dir = canonical(first_repo.commondir());
+
+        let mut worktrees = Vec::new();
+        for path in worktree_paths {
+            let repo = match Repository::open(path) {
+                Ok(repo) => repo,
+                Err(e) => {
+                    // Worktrees whose directory is gone are still listed until pruned
+                    eprintln!("Not watching {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let Some(workdir) = repo.workdir().map(canonical) else {
+                continue;
+            };
+
+            worktrees.push(WatchedWorktree {
+                path: path.clone(),
+                workdir,
+                git_dir: canonical(repo.path()),
+                repo,
+            });
+        }
+
+        Ok(Self {
+            common_dir,
+            worktrees,
+        })
+    }
+
+    /// Directories to watch, with how deep each one is watched
+    ///
+    /// Working trees are walked one directory at a time so ignored
+    /// directories such as `target/` or `node_modules/` are never watched.
+    pub fn watch_dirs(&self) -> HashMap<PathBuf, RecursiveMode> {
+        let mut dirs = HashMap::new();
+        self.watch_dirs_under(&self.common_dir, &mut dirs);
+        for worktree in &self.worktrees {
+            self.watch_dirs_under(&worktree.workdir, &mut dirs);
+        }
+        dirs
+    }
+
+    /// Add `dir` and the directories below it that need watching to `dirs`
+    pub fn watch_dirs_under(&self, dir: &Path, dirs: &mut HashMap<PathBuf, RecursiveMode>) {
+        let Some(mode) = self.watch_mode(dir) else {
+            return;
+        };
+        if dirs.insert(dir.to_path_buf(), mode).is_some() || mode == RecursiveMode::Recursive {
+            return;
+        }
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            // Symlinked directories are not followed
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                self.watch_dirs_under(&entry.path(), dirs);
+            }
+        }
+    }
+
+    /// How a directory is watched, or None if nothing in it matters
+    pub fn watch_mode(&self, dir: &Path) -> Option<RecursiveMode> {
+        if let Ok(relative) = dir.strip_prefix(&self.common_dir) {
+            return match normal_components(relative).as_slice() {
+                [] | ["worktrees"] | ["worktrees", _] => Some(RecursiveMode::NonRecursive),
+                ["refs"] => Some(RecursiveMode::Recursive),
+                [entry] | ["worktrees", _, entry] if TRACKED_ADMIN_ENTRIES.contains(entry) => {
+                    Some(RecursiveMode::Recursive)
+                }
+                // Objects, logs and other git internals
+                _ => None,
+            };
+        }
+
+        let worktree = self.owning_worktree(dir)?;
+        let relative = dir.strip_prefix(&worktree.workdir).ok()?;
+        if relative.as_os_str().is_empty() {
+            return Some(RecursiveMode::NonRecursive);
+        }
+        if relative.starts_with(".git") || worktree.repo.is_path_ignored(relative).unwrap_or(false)
+        {
+            return None;
+        }
+        // Nested repositories and worktrees the workspace does not track
+        if dir.join(".git").exists() {
+            return None;
+        }
+
+        Some(RecursiveMode::NonRecursive)
+    }
+
+    /// Classify a changed path, returning None for changes nobody cares about
+    pub fn classify(&self, path: &Path) -> Option<WatchEvent> {
+        if let Ok(relative) = path.strip_prefix(&self.common_dir) {
+            return self.classify_git_path(relative);
+        }
+
+        let worktree = self.owning_worktree(path)?;
+        let relative = path.strip_prefix(&worktree.workdir).ok()?;
+
+        if relative.as_os_str().is_empty() {
+            // The worktree directory itself was created, moved or deleted
+            return Some(WatchEvent::WorktreeListChanged);
+        }
+        if relative.starts_with(".git") {
+            return None;
+        }
+        if worktree.repo.is_path_ignored(relative).unwrap_or(false) {
+            return None;
+        }
+
+        Some(WatchEvent::WorktreeChanged(worktree.path.clone()))
+    }
+
+    /// The innermost worktree containing the path, which owns it
+    fn owning_worktree(&self, path: &Path) -> Option<&WatchedWorktree> {
+        self.worktrees
+            .iter()
+            .filter(|wt| path.starts_with(&wt.workdir))
+            .max_by_key(|wt| wt.workdir.components().count())
+    }
+
+    /// Classify a path inside the common git directory
+    fn classify_git_path(&self, relative: &Path) -> Option<WatchEvent> {
+        match normal_components(relative).as_slice() {
+            // Admin directories of linked worktrees
+            ["worktrees"] | ["worktrees", _] => Some(WatchEvent::WorktreeListChanged),
+            ["worktrees", _, "locked" | "gitdir"] => Some(WatchEvent::WorktreeListChanged),
+            ["worktrees", name, rest @ ..] => {
+                let git_dir = self.common_dir.join("worktrees").join(name);
+                self.admin_change(&git_dir, rest)
+            }
+            ["refs", ..] | ["packed-refs"] => Some(WatchEvent::RefsChanged),
+            rest => self.admin_change(&self.common_dir, rest),
+        }
+    }
+
+    /// Change event for the worktree owning `git_dir`, if the file matters
+    fn admin_change(&self, git_dir: &Path, entry: &[&str]) -> Option<WatchEvent> {
+        let first = entry.first()?;
+        if entry.last()?.ends_with(".lock") || !TRACKED_ADMIN_ENTRIES.contains(first) {
+            return None;
+        }
+
+        self.worktrees
+            .iter()
+            .find(|wt| wt.git_dir == git_dir)
+            .map(|wt| WatchEvent::WorktreeChanged(wt.path.clone()))
+    }
+}
+
+/// Watches the working trees and git directory of a workspace
+///
+/// Only directories that are not ignored are watched, each on its own, and
+/// directories created later are picked up as they appear. Changes are
+/// filtered against .gitignore, classified into [`WatchEvent`]s and
+/// delivered in debounced batches on a background thread.
+pub struct FileWatcher {
+    watches: Arc<Mutex<Watches>>,
+    targets: Arc<Mutex<WatchTargets>>,
+}
+
+impl FileWatcher {
+    /// Start watching a workspace
+    ///
+    /// # Arguments
+    ///
+    /// * `worktree_paths` - Worktrees to watch, root worktree first
+    /// * `on_events` - Called on a background thread with each debounced batch
+    pub fn new(
+        worktree_paths: &[PathBuf],
+        on_events: impl Fn(Vec<WatchEvent>) + Send + 'static,
+    ) -> Result<Self> {
+        let targets = Arc::new(Mutex::new(WatchTargets::new(worktree_paths)?));
+        let (event_tx, event_rx) = unbounded();
+        let (dir_tx, dir_rx) = unbounded::<PathBuf>();
+
+        let callback_targets = targets.clone();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("File watcher error: {}", e);
+                    return;
+                }
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            let maybe_new_dir = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+            );
+
+            let targets = lock(&callback_targets);
+            for path in &event.paths {
+                if let Some(watch_event) = targets.classify(path) {
+                    let _ = event_tx.send(watch_event);
+                }
+                // Watches can't be added from inside the callback, so new
+                // directories are handed to the thread below
+                if maybe_new_dir && path.is_dir() && targets.watch_mode(path).is_some() {
+                    let _ = dir_tx.send(path.clone());
+                }
+            }
+        })
+        .context("Failed to create file watcher")?;
+
+        let watches = Arc::new(Mutex::new(Watches {
+            watcher,
+            dirs: HashMap::new(),
+        }));
+
+        // Both exit once the watcher, and with it the senders, is dropped
+        thread::spawn(move || {
+            debounce_events(event_rx, DEBOUNCE_DELAY, MAX_DEBOUNCE_DELAY, on_events);
+        });
+        let dir_targets = targets.clone();
+        let dir_watches = Arc::downgrade(&watches);
+        thread::spawn(move || {
+            while let Ok(dir) = dir_rx.recv() {
+                let mut dirs = HashMap::new();
+                lock(&dir_targets).watch_dirs_under(&dir, &mut dirs);
+                let Some(watches) = dir_watches.upgrade() else {
+                    return;
+                };
+                if let Err(e) = lock(&watches).add(dirs) {
+                    eprintln!("File watcher error: {:#}", e);
+                }
+            }
+        });
+
+        let file_watcher = Self { watches, targets };
+        file_watcher.update_watches()?;
+        Ok(file_watcher)
+    }
+
+    /// Replace the set of watched worktrees
+    pub fn set_worktrees(&mut self, worktree_paths: &[PathBuf]) -> Result<()> {
+        let targets = WatchTargets::new(worktree_paths)?;
+        *lock(&self.targets) = targets;
+        self.update_watches()
+    }
+
+    /// Watch new directories and stop watching directories that went away
+    fn update_watches(&self) -> Result<()> {
+        let dirs = lock(&self.targets).watch_dirs();
+        lock(&self.watches).set(dirs)
+    }
+}
+
+/// The underlying watcher and the directories it watches
+struct Watches {
+    watcher: RecommendedWatcher,
+    dirs: HashMap<PathBuf, RecursiveMode>,
+}
+
+impl Watches {
+    /// Watch directories, including known ones that may have been recreated
+    fn add(&mut self, dirs: HashMap<PathBuf, RecursiveMode>) -> Result<()> {
+        for (dir, mode) in dirs {
+            if let Err(e) = self.watcher.watch(&dir, mode) {
+                // The directory may already be gone again
+                if !dir.exists() {
+                    continue;
+                }
+                return Err(e).with_context(|| format!("Failed to watch {}", dir.display()));
+            }
+            self.dirs.insert(dir, mode);
+        }
+        Ok(())
+    }
+
+    /// Watch exactly `dirs`, only touching directories that changed
+    fn set(&mut self, dirs: HashMap<PathBuf, RecursiveMode>) -> Result<()> {
+        let stale: Vec<PathBuf> = self
+            .dirs
+            .iter()
+            .filter(|(dir, mode)| dirs.get(*dir) != Some(*mode))
+            .map(|(dir, _)| dir.clone())
+            .collect();
+        for dir in stale {
+            // The directory may already be gone
+            let _ = self.watcher.unwatch(&dir);
+            self.dirs.remove(&dir);
+        }
+
+        let new_dirs = dirs
+            .into_iter()
+            .filter(|(dir, _)| !self.dirs.contains_key(dir))
+            .collect();
+        self.add(new_dirs)
+    }
+}
+
+/// Group events into batches, delivering a batch once no new event arrived
+/// for `delay`, or at the latest `max_delay` after its first event
+///
+/// Duplicate events within a batch are dropped. Returns when the sender is
+/// disconnected, after delivering any pending batch.
+fn debounce_events(
+    rx: Receiver<WatchEvent>,
+    delay: Duration,
+    max_delay: Duration,
+    on_events: impl Fn(Vec<WatchEvent>),
+) {
+    while let Ok(first) = rx.recv() {
+        let started = Instant::now();
+        let mut batch = vec![first];
+        let mut disconnected = false;
+
+        loop {
+            let remaining = max_delay.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+
+            match rx.recv_timeout(delay.min(remaining)) {
+                Ok(event) => {
+                    if !batch.contains(&event) {
+                        batch.push(event);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+
+        on_events(batch);
+        if disconnected {
+            return;
+        }
+    }
+}
+
+/// Lock a mutex, carrying on if another thread panicked while holding it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Path components as strings, for matching against git's layout
+fn normal_components(path: &Path) -> Vec<&str> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use std::sync::mpsc;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+    }
+
+    /// Repository with a linked worktree on `feature` and a .gitignore
+    fn init_repo_with_worktree(name: &str) -> (PathBuf, PathBuf) {
+        let temp_dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&temp_dir);
+        let repo_dir = temp_dir.join("repo");
+        fs::create_dir_all(&repo_dir).unwrap();
+
+        run_git(&repo_dir, &["init"]);
+        run_git(&repo_dir, &["config", "user.name", "Maestro Test"]);
+        run_git(&repo_dir, &["config", "user.email", "test@maestro.dev"]);
+        fs::write(repo_dir.join(".gitignore"), "target/\n").unwrap();
+        fs::write(repo_dir.join("README.md"), "# Test").unwrap();
+        run_git(&repo_dir, &["add", "."]);
+        run_git(&repo_dir, &["commit", "-m", "Initial commit"]);
+        run_git(
+            &repo_dir,
+            &["worktree", "add", "-b", "feature", "../feature"],
+        );
+
+        (
+            repo_dir.canonicalize().unwrap(),
+            temp_dir.join("feature").canonicalize().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_debounce_events_batches_and_dedups() {
+        let (tx, rx) = unbounded();
+        let (batch_tx, batch_rx) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            debounce_events(
+                rx,
+                Duration::from_millis(50),
+                Duration::from_secs(5),
+                move |batch| batch_tx.send(batch).unwrap(),
+            );
+        });
+
+        let path = PathBuf::from("/tmp/worktree");
+        tx.send(WatchEvent::WorktreeChanged(path.clone())).unwrap();
+        tx.send(WatchEvent::WorktreeChanged(path.clone())).unwrap();
+        tx.send(WatchEvent::RefsChanged).unwrap();
+
+        let batch = batch_rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(
+            batch,
+            vec![
+                WatchEvent::WorktreeChanged(path.clone()),
+                WatchEvent::RefsChanged
+            ]
+        );
+
+        // A later change starts a new batch; disconnecting flushes it
+        tx.send(WatchEvent::WorktreeListChanged).unwrap();
+        drop(tx);
+        let batch = batch_rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(batch, vec![WatchEvent::WorktreeListChanged]);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_debounce_events_respects_max_delay() {
+        let (tx, rx) = unbounded();
+        let (batch_tx, batch_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            debounce_events(
+                rx,
+                Duration::from_millis(200),
+                Duration::from_millis(300),
+                move |batch| batch_tx.send(batch).unwrap(),
+            );
+        });
+
+        // Keep sending faster than the quiet period for longer than max_delay
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_millis(600) {
+            tx.send(WatchEvent::RefsChanged).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let batch = batch_rx.recv_timeout(Duration::from_millis(100)).unwrap();
+        assert_eq!(batch, vec![WatchEvent::RefsChanged]);
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_classify_paths() {
+        let (repo_dir, feature_dir) = init_repo_with_worktree("maestro_watcher_classify_test");
+        let targets = WatchTargets::new(&[repo_dir.clone(), feature_dir.clone()]).unwrap();
+        let git_dir = repo_dir.join(".git");
+
+        // Working tree files, respecting .gitignore
+        assert_eq!(
+            targets.classify(&repo_dir.join("src/main.rs")),
+            Some(WatchEvent::WorktreeChanged(repo_dir.clone()))
+        );
+        assert_eq!(
+            targets.classify(&feature_dir.join("README.md")),
+            Some(WatchEvent::WorktreeChanged(feature_dir.clone()))
+        );
+        assert_eq!(targets.classify(&repo_dir.join("target/debug/app")), None);
+        assert_eq!(targets.classify(&feature_dir.join(".git")), None);
+
+        // Admin files of the root and linked worktrees
+        assert_eq!(
+            targets.classify(&git_dir.join("index")),
+            Some(WatchEvent::WorktreeChanged(repo_dir.clone()))
+        );
+        assert_eq!(targets.classify(&git_dir.join("index.lock")), None);
+        assert_eq!(targets.classify(&git_dir.join("objects/ab/cdef")), None);
+        assert_eq!(
+            targets.classify(&git_dir.join("worktrees/feature/HEAD")),
+            Some(WatchEvent::WorktreeChanged(feature_dir.clone()))
+        );
+        assert_eq!(
+            targets.classify(&git_dir.join("refs/heads/feature")),
+            Some(WatchEvent::RefsChanged)
+        );
+
+        // Worktrees added, removed or locked from the CLI
+        assert_eq!(
+            targets.classify(&git_dir.join("worktrees/other")),
+            Some(WatchEvent::WorktreeListChanged)
+        );
+        assert_eq!(
+            targets.classify(&git_dir.join("worktrees/feature/locked")),
+            Some(WatchEvent::WorktreeListChanged)
+        );
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+
+    #[test]
+    #[ignore] // This test creates temporary git repositories and worktrees
+    fn test_watch_dirs_skip_ignored_directories() {
+        let (repo_dir, feature_dir) = init_repo_with_worktree("maestro_watcher_dirs_test");
+        fs::create_dir_all(repo_dir.join("src/ui")).unwrap();
+        fs::create_dir_all(repo_dir.join("target/debug")).unwrap();
+        fs::create_dir_all(repo_dir.join("vendor/lib")).unwrap();
+        run_git(&repo_dir.join("vendor/lib"), &["init"]);
+
+        let targets = WatchTargets::new(&[repo_dir.clone(), feature_dir.clone()]).unwrap();
+        let git_dir = repo_dir.join(".git");
+        let dirs = targets.watch_dirs();
+
+        for dir in [
+            repo_dir.clone(),
+            repo_dir.join("src"),
+            repo_dir.join("src/ui"),
+            repo_dir.join("vendor"),
+            feature_dir.clone(),
+            git_dir.clone(),
+            git_dir.join("worktrees"),
+            git_dir.join("worktrees/feature"),
+        ] {
+            assert_eq!(
+                dirs.get(&dir),
+                Some(&RecursiveMode::NonRecursive),
+                "{:?}",
+                dir
+            );
+        }
+        assert_eq!(
+            dirs.get(&git_dir.join("refs")),
+            Some(&RecursiveMode::Recursive)
+        );
+
+        // Ignored directories, nested repositories and git internals
+        for dir in [
+            repo_dir.join("target"),
+            repo_dir.join("target/debug"),
+            repo_dir.join("vendor/lib"),
+            git_dir.join("objects"),
+            git_dir.join("refs/heads"),
+        ] {
+            assert!(!dirs.contains_key(&dir), "{:?}", dir);
+        }
+
+        // Directories created later are watched the same way
+        fs::create_dir_all(feature_dir.join("docs")).unwrap();
+        assert_eq!(
+            targets.watch_mode(&feature_dir.join("docs")),
+            Some(RecursiveMode::NonRecursive)
+        );
+        assert_eq!(targets.watch_mode(&repo_dir.join("target/release")), None);
+
+        let _ = fs::remove_dir_all(repo_dir.parent().unwrap());
+    }
+}